@@ -83,16 +83,13 @@ class V11ApiService {
     throw UnimplementedError('Mock: Get liminal transition');
   }
 
-  /// Update consecutive days streak
-  Future<RoleProgressModel> updateConsecutiveDays({
-    required String roleId,
-    required int days,
-  }) async {
+  /// Recompute consecutive days from the streak engine
+  Future<RoleProgressModel> refreshConsecutiveDays(String roleId) async {
     // TODO: Replace with actual FFI call
-    // final json = await _api.updateConsecutiveDays(roleId: roleId, days: days);
+    // final json = await _api.refreshConsecutiveDays(roleId: roleId);
     // return RoleProgressModel.fromJson(jsonDecode(json));
 
-    throw UnimplementedError('Mock: Update consecutive days');
+    throw UnimplementedError('Mock: Refresh consecutive days');
   }
 
   // ====================================================================
@@ -3,23 +3,82 @@ use flutter_rust_bridge::frb;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
-
+use std::path::Path;
+
+use crate::certificates::{CompletionRecord, SignedCertificate};
+use crate::coherence::{CoherenceEngine, SceneInput};
+use crate::content::{find_script, scripts_for_role, ContentLibrary};
+use crate::context::{rank_scripts, PracticeContext};
+use crate::emotion::{EmotionMatch, Tone};
+use crate::emotion_trends::{emotion_trajectory, TrajectoryConfig};
+use crate::leech::{LeechConfig, LeechReport};
 use crate::monetization::{ContentAccess, ContentType, ContentUnlock, Purchase, Subscription};
+use crate::notifications::{plan_notifications, PlannerConfig};
+use crate::recommend::{recommend, RecommenderConfig};
+use crate::review_queue::{QueueConfig, ReviewQueue};
 use crate::roles::{
-    liminal_transition, EmotionTag, Reflection, ResonanceTrace, Role, RoleCoherenceScore,
-    RoleProgress,
+    EmotionTag, Reflection, ResonanceTrace, Role, RoleCoherenceScore, RolePath, RoleProgress,
 };
 use crate::runner::RunnerState;
+use crate::scheduler::{Grade, SchedulerKind};
 use crate::scripts::Script;
+use crate::speech::score_speak_check;
 use crate::storage::Store;
 use crate::telemetry::{DeviceContext, EventBatch, TelemetryEvent};
+use crate::transitions::resolve_transition;
 
 // Global state
 static SCRIPTS: Lazy<Mutex<HashMap<String, Script>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 static ROLES: Lazy<Mutex<HashMap<String, Role>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static PATHS: Lazy<Mutex<HashMap<String, RolePath>>> = Lazy::new(|| Mutex::new(HashMap::new()));
 static STORE: Lazy<Mutex<Option<Store>>> = Lazy::new(|| Mutex::new(None));
+static CONTENT_DIRS: Lazy<Mutex<ContentDirs>> = Lazy::new(|| Mutex::new(ContentDirs::default()));
+
+/// Directories SCRIPTS and ROLES were last loaded from
+///
+/// Loading either kind rebuilds the whole library from both, so role scenes
+/// survive a script reload and a role reload leaves no stale scenes behind.
+#[derive(Clone, Default)]
+struct ContentDirs {
+    scripts: Option<String>,
+    roles: Option<String>,
+}
+
+impl ContentDirs {
+    /// The library and the number of standalone scripts in it
+    fn load(&self) -> Result<(ContentLibrary, usize), String> {
+        let mut library = ContentLibrary::new();
+        let mut standalone = 0;
+        if let Some(dir) = &self.scripts {
+            standalone = library
+                .load_scripts_dir(Path::new(dir))
+                .map_err(|e| e.to_string())?;
+        }
+        if let Some(dir) = &self.roles {
+            library
+                .load_roles_dir(Path::new(dir))
+                .map_err(|e| e.to_string())?;
+        }
+        Ok((library, standalone))
+    }
+}
+
+/// Rebuild SCRIPTS and ROLES after `update` changes the remembered directories
+///
+/// The directories only change once the new library has loaded. Returns
+/// the number of standalone scripts and of roles.
+fn reload_content(update: impl FnOnce(&mut ContentDirs)) -> Result<(u32, u32), String> {
+    let mut dirs = CONTENT_DIRS.lock();
+    let mut next = dirs.clone();
+    update(&mut next);
+    let (library, standalone) = next.load()?;
+    *dirs = next;
+
+    let roles = library.roles.len() as u32;
+    *SCRIPTS.lock() = library.scripts;
+    *ROLES.lock() = library.roles;
+    Ok((standalone as u32, roles))
+}
 
 // ============================================================================
 // Script Management
@@ -27,26 +86,8 @@ static STORE: Lazy<Mutex<Option<Store>>> = Lazy::new(|| Mutex::new(None));
 
 #[frb(sync)]
 pub fn load_scripts_from_dir(dir: String) -> Result<u32, String> {
-    let mut map = SCRIPTS.lock();
-    map.clear();
-
-    let path = PathBuf::from(&dir);
-    if !path.exists() {
-        return Err(format!("Directory not found: {}", dir));
-    }
-
-    for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
-
-        if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
-            let txt = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-            let script: Script = serde_yaml::from_str(&txt).map_err(|e| e.to_string())?;
-            map.insert(script.id.clone(), script);
-        }
-    }
-
-    Ok(map.len() as u32)
+    let (scripts, _) = reload_content(|dirs| dirs.scripts = Some(dir))?;
+    Ok(scripts)
 }
 
 #[frb(sync)]
@@ -63,6 +104,14 @@ pub fn get_script_json(script_id: String) -> Result<String, String> {
     serde_json::to_string(script).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn suggest_scripts_for_context(context_json: String) -> Result<String, String> {
+    let ctx: PracticeContext = serde_json::from_str(&context_json).map_err(|e| e.to_string())?;
+    let map = SCRIPTS.lock();
+    let matches = rank_scripts(map.values(), &ctx);
+    serde_json::to_string(&matches).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Runner
 // ============================================================================
@@ -73,10 +122,30 @@ pub fn start_runner(script_id: String) -> Result<String, String> {
     let script = map
         .get(&script_id)
         .ok_or_else(|| format!("Script not found: {}", script_id))?;
-    let state = RunnerState::new(script);
+    let mut state = RunnerState::new(script);
+    let progress = state.progress(script);
+    drop(map);
+
+    persist_runner(&mut state, progress)?;
     serde_json::to_string(&state).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn resume_runner(script_id: Option<String>) -> Result<Option<String>, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let state = store
+        .get_resumable_session(script_id.as_deref())
+        .map_err(|e| e.to_string())?;
+
+    state
+        .map(|s| serde_json::to_string(&s).map_err(|e| e.to_string()))
+        .transpose()
+}
+
 #[frb(sync)]
 pub fn runner_next(state_json: String) -> Result<String, String> {
     let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
@@ -84,7 +153,16 @@ pub fn runner_next(state_json: String) -> Result<String, String> {
     let script = map
         .get(&state.script_id)
         .ok_or_else(|| format!("Script not found: {}", state.script_id))?;
-    state.next(script);
+    let was_completed = state.completed;
+    state.next(script).map_err(|e| e.to_string())?;
+    let progress = state.progress(script);
+    let finished = (state.completed && !was_completed).then(|| script.clone());
+    drop(map);
+
+    persist_runner(&mut state, progress)?;
+    if let Some(script) = finished {
+        harvest_completed(&script)?;
+    }
     serde_json::to_string(&state).map_err(|e| e.to_string())
 }
 
@@ -92,6 +170,26 @@ pub fn runner_next(state_json: String) -> Result<String, String> {
 pub fn runner_prev(state_json: String) -> Result<String, String> {
     let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
     state.prev();
+    let progress = SCRIPTS
+        .lock()
+        .get(&state.script_id)
+        .map(|script| state.progress(script))
+        .unwrap_or(0.0);
+
+    persist_runner(&mut state, progress)?;
+    serde_json::to_string(&state).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn runner_save(state_json: String) -> Result<String, String> {
+    let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    let progress = SCRIPTS
+        .lock()
+        .get(&state.script_id)
+        .map(|script| state.progress(script))
+        .unwrap_or(0.0);
+
+    persist_runner(&mut state, progress)?;
     serde_json::to_string(&state).map_err(|e| e.to_string())
 }
 
@@ -120,6 +218,132 @@ pub fn runner_current_step(state_json: String) -> Result<String, String> {
     serde_json::to_string(step).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn runner_submit_answer(
+    state_json: String,
+    step_index: u32,
+    choice: u32,
+) -> Result<String, String> {
+    let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&state.script_id)
+        .ok_or_else(|| format!("Script not found: {}", state.script_id))?;
+
+    let feedback = state
+        .submit_answer(script, step_index as usize, choice as usize)
+        .map_err(|e| e.to_string())?;
+    let progress = state.progress(script);
+    drop(map);
+
+    persist_runner(&mut state, progress)?;
+    let result = serde_json::json!({
+        "state": state,
+        "feedback": feedback,
+    });
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn score_speak_check_json(
+    script_id: String,
+    step_index: u32,
+    transcript: String,
+) -> Result<String, String> {
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&script_id)
+        .ok_or_else(|| format!("Script not found: {}", script_id))?;
+
+    let score =
+        score_speak_check(script, step_index as usize, &transcript).map_err(|e| e.to_string())?;
+    serde_json::to_string(&score).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn runner_score_speak_check(
+    state_json: String,
+    step_index: u32,
+    transcript: String,
+) -> Result<String, String> {
+    let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&state.script_id)
+        .ok_or_else(|| format!("Script not found: {}", state.script_id))?;
+
+    let score =
+        score_speak_check(script, step_index as usize, &transcript).map_err(|e| e.to_string())?;
+    state.record_speak_score(step_index as usize, &score);
+    let progress = state.progress(script);
+    drop(map);
+
+    persist_runner(&mut state, progress)?;
+    let result = serde_json::json!({
+        "state": state,
+        "score": score,
+    });
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn runner_reveal_hint(state_json: String) -> Result<String, String> {
+    let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&state.script_id)
+        .ok_or_else(|| format!("Script not found: {}", state.script_id))?;
+
+    let hint = state.reveal_hint(script);
+    let progress = state.progress(script);
+    drop(map);
+
+    persist_runner(&mut state, progress)?;
+    let result = serde_json::json!({
+        "state": state,
+        "hint": hint,
+    });
+    serde_json::to_string(&result).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn runner_can_advance(state_json: String) -> Result<bool, String> {
+    let state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&state.script_id)
+        .ok_or_else(|| format!("Script not found: {}", state.script_id))?;
+    Ok(state.can_advance(script))
+}
+
+#[frb(sync)]
+pub fn runner_set_require_correct(state_json: String, require: bool) -> Result<String, String> {
+    let mut state: RunnerState = serde_json::from_str(&state_json).map_err(|e| e.to_string())?;
+    state.require_correct = require;
+    serde_json::to_string(&state).map_err(|e| e.to_string())
+}
+
+/// Save the runner to its session row when storage is initialized
+///
+/// Without storage the runner still works, it just isn't resumable.
+fn persist_runner(state: &mut RunnerState, progress: f32) -> Result<(), String> {
+    let guard = STORE.lock();
+    if let Some(store) = guard.as_ref() {
+        store
+            .save_runner_session(state, progress)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn harvest_completed(script: &Script) -> Result<(), String> {
+    let guard = STORE.lock();
+    if let Some(store) = guard.as_ref() {
+        store.harvest_phrases(script).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 // ============================================================================
 // Storage
 // ============================================================================
@@ -162,6 +386,21 @@ pub fn export_data() -> Result<String, String> {
     store.export_json().map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn import_data(snapshot_json: String, mode: String) -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let mode: crate::storage::ImportMode =
+        serde_json::from_str(&format!("\"{}\"", mode)).map_err(|e| e.to_string())?;
+    let summary = store
+        .import_json(&snapshot_json, mode)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&summary).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Statistics
 // ============================================================================
@@ -175,6 +414,55 @@ pub fn get_streak() -> Result<u32, String> {
     store.get_streak().map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn get_streak_summary(histogram_days: u32) -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let summary = store
+        .get_streak_summary(histogram_days)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&summary).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn set_user_timezone(utc_offset_minutes: i32) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .set_utc_offset_minutes(utc_offset_minutes)
+        .map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn add_streak_freeze_tokens(count: u32) -> Result<u32, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .add_streak_freeze_tokens(count)
+        .map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn apply_streak_freezes() -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let today = store
+        .local_date(chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+    let frozen = store
+        .apply_streak_freezes(today)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&frozen).map_err(|e| e.to_string())
+}
+
 #[frb(sync)]
 pub fn get_use_in_wild_count() -> Result<u32, String> {
     let guard = STORE.lock();
@@ -185,31 +473,187 @@ pub fn get_use_in_wild_count() -> Result<u32, String> {
 }
 
 // ============================================================================
-// Role Management
+// Spaced Repetition
 // ============================================================================
 
 #[frb(sync)]
-pub fn load_roles_from_dir(dir: String) -> Result<u32, String> {
-    let mut map = ROLES.lock();
-    map.clear();
+pub fn harvest_script_phrases(script_id: String) -> Result<u32, String> {
+    let script = SCRIPTS
+        .lock()
+        .get(&script_id)
+        .cloned()
+        .ok_or_else(|| format!("Script not found: {}", script_id))?;
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let added = store.harvest_phrases(&script).map_err(|e| e.to_string())?;
+    Ok(added as u32)
+}
 
-    let path = PathBuf::from(&dir);
-    if !path.exists() {
-        return Err(format!("Directory not found: {}", dir));
+#[frb(sync)]
+pub fn get_memory_links_json(script_id: Option<String>) -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let links = match script_id {
+        Some(id) => store.get_memory_links_for_script(&id),
+        None => store.get_all_memory_links(),
     }
+    .map_err(|e| e.to_string())?;
+    serde_json::to_string(&links).map_err(|e| e.to_string())
+}
 
-    for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
-        let entry = entry.map_err(|e| e.to_string())?;
-        let path = entry.path();
+fn review_queue(config_json: Option<String>) -> Result<ReviewQueue, String> {
+    let config: QueueConfig = match config_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string())?,
+        None => QueueConfig::default(),
+    };
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .review_queue(chrono::Utc::now(), &config)
+        .map_err(|e| e.to_string())
+}
 
-        if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
-            let txt = fs::read_to_string(&path).map_err(|e| e.to_string())?;
-            let role: Role = serde_yaml::from_str(&txt).map_err(|e| e.to_string())?;
-            map.insert(role.id.clone(), role);
-        }
-    }
+#[frb(sync)]
+pub fn get_review_queue_json(config_json: Option<String>) -> Result<String, String> {
+    let queue = review_queue(config_json)?;
+    serde_json::to_string(&queue).map_err(|e| e.to_string())
+}
 
-    Ok(map.len() as u32)
+#[frb(sync)]
+pub fn get_next_review_item(config_json: Option<String>) -> Result<Option<String>, String> {
+    let queue = review_queue(config_json)?;
+    queue
+        .next()
+        .map(|item| serde_json::to_string(item).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+#[frb(sync)]
+pub fn get_due_review_count(config_json: Option<String>) -> Result<u32, String> {
+    Ok(review_queue(config_json)?.due_count() as u32)
+}
+
+#[frb(sync)]
+pub fn plan_notifications_json(config_json: Option<String>) -> Result<String, String> {
+    let config: PlannerConfig = match config_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string())?,
+        None => PlannerConfig::default(),
+    };
+    let scripts = SCRIPTS.lock();
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let links = store.get_all_memory_links().map_err(|e| e.to_string())?;
+    let offset = store.get_utc_offset().map_err(|e| e.to_string())?;
+    let scheduler = store.scheduler().map_err(|e| e.to_string())?;
+    let plan = plan_notifications(
+        &links,
+        |script_id| scripts.get(script_id).map(|s| &s.rehearsal),
+        scheduler.as_ref(),
+        chrono::Utc::now(),
+        offset,
+        &config,
+    )
+    .map_err(|e| e.to_string())?;
+    serde_json::to_string(&plan).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_leeches_json() -> Result<String, String> {
+    let scripts = SCRIPTS.lock();
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let reports: Vec<LeechReport> = store
+        .get_leeches()
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|link| LeechReport::new(link, &scripts))
+        .collect();
+    serde_json::to_string(&reports).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn resolve_leech(phrase: String) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store.resolve_leech(&phrase).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn set_leech_config(config_json: String) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let config: LeechConfig = serde_json::from_str(&config_json).map_err(|e| e.to_string())?;
+    store.set_leech_config(&config).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn set_scheduler(kind: String) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let kind: SchedulerKind =
+        serde_json::from_str(&format!("\"{}\"", kind)).map_err(|e| e.to_string())?;
+    store.set_scheduler_kind(kind).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn review_phrase(phrase: String, grade: String) -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let grade: Grade =
+        serde_json::from_str(&format!("\"{}\"", grade)).map_err(|e| e.to_string())?;
+
+    let link = store
+        .review_memory_link(&phrase, grade, chrono::Utc::now())
+        .map_err(|e| e.to_string())?;
+    let scheduler = store.scheduler().map_err(|e| e.to_string())?;
+
+    serde_json::to_string(&serde_json::json!({
+        "next_review_at": scheduler.next_review(&link),
+        "link": link,
+    }))
+    .map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn fit_retention_parameters(apply: bool) -> Result<Option<String>, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .fit_personal_decay(apply)
+        .map_err(|e| e.to_string())?
+        .map(|report| serde_json::to_string(&report).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+// ============================================================================
+// Role Management
+// ============================================================================
+
+#[frb(sync)]
+pub fn load_roles_from_dir(dir: String) -> Result<u32, String> {
+    let (_, roles) = reload_content(|dirs| dirs.roles = Some(dir))?;
+    Ok(roles)
 }
 
 #[frb(sync)]
@@ -226,6 +670,16 @@ pub fn get_role_json(role_id: String) -> Result<String, String> {
     serde_json::to_string(role).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn get_role_scripts_json(role_id: String) -> Result<String, String> {
+    let roles = ROLES.lock();
+    let role = roles
+        .get(&role_id)
+        .ok_or_else(|| format!("Role not found: {}", role_id))?;
+    let scripts = SCRIPTS.lock();
+    serde_json::to_string(&scripts_for_role(role, &scripts)).map_err(|e| e.to_string())
+}
+
 #[frb(sync)]
 pub fn calculate_role_coherence(
     role_id: String,
@@ -233,36 +687,97 @@ pub fn calculate_role_coherence(
     use_in_wild_count: u32,
     skipped_steps: u32,
 ) -> Result<String, String> {
-    let map = ROLES.lock();
-    let role = map
-        .get(&role_id)
-        .ok_or_else(|| format!("Role not found: {}", role_id))?;
+    let mut scenes = role_scenes(&role_id).ok_or_else(|| format!("Role not found: {}", role_id))?;
 
-    let total_scenarios = role.scenario_ids.len() as u32;
     let mut coherence = RoleCoherenceScore {
         role_id: role_id.clone(),
         completed_scenarios,
-        total_scenarios,
+        total_scenarios: scenes.len() as u32,
         use_in_wild_count,
         skipped_steps,
         score: 0.0,
+        breakdown: None,
     };
 
-    coherence.calculate();
+    let engine = match STORE.lock().as_ref() {
+        Some(store) => {
+            add_speak_scores(store, &mut scenes)?;
+            store.get_coherence_config().map_err(|e| e.to_string())?
+        }
+        None => CoherenceEngine::default(),
+    };
+    coherence.calculate_with(&engine, &scenes);
     serde_json::to_string(&coherence).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn set_coherence_config(config_json: String) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let config: CoherenceEngine = serde_json::from_str(&config_json).map_err(|e| e.to_string())?;
+    store
+        .set_coherence_config(&config)
+        .map_err(|e| e.to_string())
+}
+
+/// Scenes of a loaded role in order, with their declared weights
+///
+/// Takes ROLES then SCRIPTS and releases both, so callers may lock STORE
+/// afterwards.
+fn role_scenes(role_id: &str) -> Option<Vec<SceneInput>> {
+    let roles = ROLES.lock();
+    let role = roles.get(role_id)?;
+    let scripts = SCRIPTS.lock();
+    Some(
+        role.scenario_ids
+            .iter()
+            .map(|id| SceneInput {
+                scene_id: id.clone(),
+                weight: scripts.get(id).and_then(|s| s.coherence_weight),
+                completed: false,
+                speak_score: None,
+            })
+            .collect(),
+    )
+}
+
+fn add_speak_scores(store: &Store, scenes: &mut [SceneInput]) -> Result<(), String> {
+    for scene in scenes.iter_mut() {
+        scene.speak_score = store
+            .get_speak_quality(&scene.scene_id)
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Rescore role progress with the role's scenes when the role is loaded
+fn score_progress(
+    store: &Store,
+    progress: &mut RoleProgress,
+    mut scenes: Vec<SceneInput>,
+) -> Result<(), String> {
+    add_speak_scores(store, &mut scenes)?;
+    let engine = store.get_coherence_config().map_err(|e| e.to_string())?;
+    progress.calculate_coherence_with(&engine, &scenes);
+    Ok(())
+}
+
 // ============================================================================
 // v1.1: Role Progress & Liminal Transitions
 // ============================================================================
 
 #[frb(sync)]
 pub fn start_role_progress(role_id: String, total_scenes: u32) -> Result<String, String> {
-    let progress = RoleProgress::new(role_id, total_scenes as usize);
+    let scenes = role_scenes(&role_id).unwrap_or_default();
+    let mut progress = RoleProgress::new(role_id, total_scenes as usize);
     let guard = STORE.lock();
     let store = guard
         .as_ref()
         .ok_or_else(|| "Storage not initialized".to_string())?;
+    progress.consecutive_days = store.get_streak().map_err(|e| e.to_string())?;
+    score_progress(store, &mut progress, scenes)?;
     store
         .save_role_progress(&progress)
         .map_err(|e| e.to_string())?;
@@ -276,6 +791,8 @@ pub fn complete_scene_with_emotion(
     tone: String,
     confidence: f32,
 ) -> Result<String, String> {
+    let scenes = role_scenes(&role_id).unwrap_or_default();
+    let paths = paths_with_role(&role_id);
     let guard = STORE.lock();
     let store = guard
         .as_ref()
@@ -286,15 +803,65 @@ pub fn complete_scene_with_emotion(
         .map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Role progress not found: {}", role_id))?;
 
+    progress.consecutive_days = store.get_streak().map_err(|e| e.to_string())?;
     let emotion = EmotionTag::new(scene_id, tone, confidence);
     progress.complete_scene(emotion);
+    score_progress(store, &mut progress, scenes)?;
 
     store
         .save_role_progress(&progress)
         .map_err(|e| e.to_string())?;
+    store
+        .unlock_next_roles(&paths, &progress)
+        .map_err(|e| e.to_string())?;
     serde_json::to_string(&progress).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn compare_scene_emotion(
+    script_id: String,
+    step_index: Option<u32>,
+    tone: String,
+) -> Result<Option<String>, String> {
+    let map = SCRIPTS.lock();
+    let script = map
+        .get(&script_id)
+        .ok_or_else(|| format!("Script not found: {}", script_id))?;
+
+    let step_tag = step_index
+        .and_then(|i| script.steps.get(i as usize))
+        .and_then(|step| step.emotion_tag.as_deref());
+    let Some(intended) = step_tag.or(script.emotion_wave.as_deref()) else {
+        return Ok(None);
+    };
+
+    EmotionMatch::compare(intended, Tone::parse(&tone))
+        .map(|m| serde_json::to_string(&m).map_err(|e| e.to_string()))
+        .transpose()
+}
+
+#[frb(sync)]
+pub fn get_emotion_trajectory_json(
+    role_id: Option<String>,
+    config_json: Option<String>,
+) -> Result<String, String> {
+    let config: TrajectoryConfig = match config_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string())?,
+        None => TrajectoryConfig::default(),
+    };
+    let scripts = SCRIPTS.lock();
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let history = store
+        .get_emotion_history(role_id.as_deref())
+        .map_err(|e| e.to_string())?;
+    let trajectory = emotion_trajectory(&history, &scripts, &config, chrono::Utc::now());
+    serde_json::to_string(&trajectory).map_err(|e| e.to_string())
+}
+
 #[frb(sync)]
 pub fn get_role_progress_json(role_id: String) -> Result<String, String> {
     let guard = STORE.lock();
@@ -312,6 +879,136 @@ pub fn get_role_progress_json(role_id: String) -> Result<String, String> {
 
 #[frb(sync)]
 pub fn get_liminal_transition_json(role_id: String) -> Result<String, String> {
+    let (progress, language) = {
+        let guard = STORE.lock();
+        let store = guard
+            .as_ref()
+            .ok_or_else(|| "Storage not initialized".to_string())?;
+        let progress = store
+            .load_role_progress(&role_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Role progress not found: {}", role_id))?;
+        let language = store.get_native_language().map_err(|e| e.to_string())?;
+        (progress, language)
+    };
+
+    let roles_map = ROLES.lock();
+    let role = roles_map
+        .get(&role_id)
+        .ok_or_else(|| format!("Role not found: {}", role_id))?;
+
+    let scripts = SCRIPTS.lock();
+    let scene = progress
+        .emotion_tags
+        .last()
+        .and_then(|tag| find_script(&scripts, &tag.scene_id));
+
+    let transition = resolve_transition(&progress, Some(role), scene, &language);
+    serde_json::to_string(&transition).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn issue_role_certificate(role_id: String) -> Result<String, String> {
+    let mut scenes = role_scenes(&role_id).unwrap_or_default();
+    let (role_title, role_scripts) = {
+        let roles = ROLES.lock();
+        let scripts = SCRIPTS.lock();
+        let role_title = roles
+            .get(&role_id)
+            .map_or_else(|| role_id.clone(), |r| r.title.clone());
+        let role_scripts: HashMap<String, Script> = roles
+            .get(&role_id)
+            .map(|r| scripts_for_role(r, &scripts))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|s| (s.id.clone(), s.clone()))
+            .collect();
+        (role_title, role_scripts)
+    };
+
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let mut progress = store
+        .load_role_progress(&role_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Role progress not found: {}", role_id))?;
+    add_speak_scores(store, &mut scenes)?;
+    let engine = store.get_coherence_config().map_err(|e| e.to_string())?;
+    let breakdown = progress.calculate_coherence_with(&engine, &scenes);
+    let scene_ids: Vec<String> = scenes.iter().map(|s| s.scene_id.clone()).collect();
+    let use_in_wild_count = store
+        .get_use_in_wild_count_for_scripts(&scene_ids)
+        .map_err(|e| e.to_string())?;
+
+    let record = CompletionRecord::from_progress(
+        &progress,
+        &role_title,
+        breakdown,
+        |id| find_script(&role_scripts, id).map(|s| s.title.clone()),
+        use_in_wild_count,
+        chrono::Utc::now(),
+    )
+    .map_err(|e| e.to_string())?;
+    let certificate = store
+        .certificate_signer()
+        .and_then(|signer| signer.sign(record))
+        .map_err(|e| e.to_string())?;
+    store
+        .save_certificate(&certificate)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&certificate).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_certificates_json(role_id: Option<String>) -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let certificates = store
+        .get_certificates(role_id.as_deref())
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&certificates).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_certificate_public_key() -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .certificate_signer()
+        .map(|signer| signer.public_key_hex())
+        .map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn verify_certificate(certificate: String, public_key: Option<String>) -> Result<(), String> {
+    SignedCertificate::parse(&certificate)
+        .and_then(|cert| cert.verify(public_key.as_deref()))
+        .map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn render_certificate_svg(certificate_json: String) -> Result<String, String> {
+    let certificate: SignedCertificate =
+        serde_json::from_str(&certificate_json).map_err(|e| e.to_string())?;
+    Ok(certificate.to_svg())
+}
+
+#[frb(sync)]
+pub fn render_certificate_html(certificate_json: String) -> Result<String, String> {
+    let certificate: SignedCertificate =
+        serde_json::from_str(&certificate_json).map_err(|e| e.to_string())?;
+    certificate.to_html().map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_coherence_history_json(role_id: String) -> Result<String, String> {
     let guard = STORE.lock();
     let store = guard
         .as_ref()
@@ -322,17 +1019,24 @@ pub fn get_liminal_transition_json(role_id: String) -> Result<String, String> {
         .map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Role progress not found: {}", role_id))?;
 
-    let roles_map = ROLES.lock();
-    let role = roles_map
-        .get(&role_id)
-        .ok_or_else(|| format!("Role not found: {}", role_id))?;
+    serde_json::to_string(&progress.coherence_history).map_err(|e| e.to_string())
+}
 
-    let transition = liminal_transition(&progress, &role.title);
-    serde_json::to_string(&transition).map_err(|e| e.to_string())
+#[frb(sync)]
+pub fn set_native_language(language: String) -> Result<(), String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    store
+        .set_native_language(&language)
+        .map_err(|e| e.to_string())
 }
 
 #[frb(sync)]
-pub fn update_consecutive_days(role_id: String, days: u32) -> Result<String, String> {
+pub fn refresh_consecutive_days(role_id: String) -> Result<String, String> {
+    let scenes = role_scenes(&role_id).unwrap_or_default();
+    let paths = paths_with_role(&role_id);
     let guard = STORE.lock();
     let store = guard
         .as_ref()
@@ -343,15 +1047,85 @@ pub fn update_consecutive_days(role_id: String, days: u32) -> Result<String, Str
         .map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Role progress not found: {}", role_id))?;
 
-    progress.consecutive_days = days;
-    progress.calculate_coherence();
+    progress.consecutive_days = store.get_streak().map_err(|e| e.to_string())?;
+    score_progress(store, &mut progress, scenes)?;
 
     store
         .save_role_progress(&progress)
         .map_err(|e| e.to_string())?;
+    store
+        .unlock_next_roles(&paths, &progress)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_string(&progress).map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// Role Paths
+// ============================================================================
+
+#[frb(sync)]
+pub fn load_paths_from_dir(dir: String) -> Result<u32, String> {
+    let mut library = ContentLibrary::new();
+    library
+        .load_paths_dir(Path::new(&dir))
+        .map_err(|e| e.to_string())?;
+
+    let mut map = PATHS.lock();
+    map.clear();
+    map.extend(library.paths);
+    Ok(map.len() as u32)
+}
+
+#[frb(sync)]
+pub fn get_path_ids() -> Vec<String> {
+    PATHS.lock().keys().cloned().collect()
+}
+
+#[frb(sync)]
+pub fn get_path_json(path_id: String) -> Result<String, String> {
+    let map = PATHS.lock();
+    let path = map
+        .get(&path_id)
+        .ok_or_else(|| format!("Path not found: {}", path_id))?;
+    serde_json::to_string(path).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_path_progress_json(path_id: String) -> Result<String, String> {
+    let path = PATHS
+        .lock()
+        .get(&path_id)
+        .cloned()
+        .ok_or_else(|| format!("Path not found: {}", path_id))?;
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+
+    let progress = store.get_path_progress(&path).map_err(|e| e.to_string())?;
     serde_json::to_string(&progress).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn get_unlocked_roles_json() -> Result<String, String> {
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let unlocked = store.get_unlocked_roles().map_err(|e| e.to_string())?;
+    serde_json::to_string(&unlocked).map_err(|e| e.to_string())
+}
+
+/// Loaded paths that include `role_id`; PATHS is released before returning
+fn paths_with_role(role_id: &str) -> Vec<RolePath> {
+    PATHS
+        .lock()
+        .values()
+        .filter(|path| path.role_ids.iter().any(|id| id == role_id))
+        .cloned()
+        .collect()
+}
+
 // ============================================================================
 // v1.1: Social Resonance
 // ============================================================================
@@ -627,6 +1401,72 @@ pub fn check_content_access(
     serde_json::to_string(&entitlement).map_err(|e| e.to_string())
 }
 
+#[frb(sync)]
+pub fn check_role_access(user_id: String, role_id: String) -> Result<String, String> {
+    // Copy the access level out so ROLES is not held while STORE is locked
+    let access_level = ROLES
+        .lock()
+        .get(&role_id)
+        .map(|role| role.access_level.clone())
+        .ok_or_else(|| format!("Role not found: {}", role_id))?;
+
+    let guard = STORE.lock();
+    let store = guard
+        .as_ref()
+        .ok_or_else(|| "Storage not initialized".to_string())?;
+    let entitlement = store
+        .check_entitlement(
+            &user_id,
+            &access_level,
+            Some(&ContentType::Role),
+            Some(&role_id),
+        )
+        .map_err(|e| e.to_string())?;
+
+    serde_json::to_string(&entitlement).map_err(|e| e.to_string())
+}
+
+#[frb(sync)]
+pub fn get_recommendations_json(
+    user_id: String,
+    context_json: Option<String>,
+    config_json: Option<String>,
+) -> Result<String, String> {
+    let context: Option<PracticeContext> = context_json
+        .map(|json| serde_json::from_str(&json))
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let config: RecommenderConfig = match config_json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string())?,
+        None => RecommenderConfig::default(),
+    };
+
+    // Copy content out so no content lock is held while STORE is locked
+    let roles = ROLES.lock().clone();
+    let scripts = SCRIPTS.lock().clone();
+    let paths: Vec<RolePath> = PATHS.lock().values().cloned().collect();
+
+    let learner = {
+        let guard = STORE.lock();
+        let store = guard
+            .as_ref()
+            .ok_or_else(|| "Storage not initialized".to_string())?;
+        store
+            .learner_state(&user_id, &roles)
+            .map_err(|e| e.to_string())?
+    };
+
+    let recommendations = recommend(
+        &roles,
+        &scripts,
+        &paths,
+        &learner,
+        context.as_ref(),
+        &config,
+    );
+    serde_json::to_string(&recommendations).map_err(|e| e.to_string())
+}
+
 #[frb(sync)]
 pub fn get_user_unlocks(user_id: String) -> Result<String, String> {
     let guard = STORE.lock();
@@ -1,4 +1,7 @@
-use liminal_english_core::{ContentValidator, Store};
+use liminal_english_core::{
+    recommend, simulate_retention, ContentLibrary, ContentValidator, ImportMode, LearnerProfile,
+    LeechReport, RecommenderConfig, Remediation, Script, SignedCertificate, Store, SCHEMA_VERSION,
+};
 use std::env;
 use std::path::Path;
 use std::process;
@@ -16,6 +19,10 @@ fn main() {
     match command.as_str() {
         "validate" => cmd_validate(&args[2..]),
         "db" => cmd_db(&args[2..]),
+        "leeches" => cmd_leeches(&args[2..]),
+        "simulate-retention" => cmd_simulate_retention(&args[2..]),
+        "verify-certificate" => cmd_verify_certificate(&args[2..]),
+        "recommend" => cmd_recommend(&args[2..]),
         "health" => cmd_health(),
         "help" | "--help" | "-h" => {
             print_usage();
@@ -38,8 +45,14 @@ USAGE:
     liminal_cli <COMMAND> [OPTIONS]
 
 COMMANDS:
-    validate        Validate all YAML scenarios and roles
+    validate        Validate all YAML scenarios, roles and role paths
     db              Database operations
+    leeches         List chronically failed phrases and how to fix them
+    simulate-retention
+                    Run rehearsal settings against a synthetic learner (CSV)
+    verify-certificate
+                    Check the signature of a role completion certificate
+    recommend       Suggest the next scenes and roles for a learner
     health          Check system health
     help            Show this help message
 
@@ -48,11 +61,33 @@ EXAMPLES:
     liminal_cli validate
 
     # Validate specific directories
-    liminal_cli validate --scripts assets/scripts --roles content/roles
+    liminal_cli validate --scripts assets/scripts --roles content/roles --paths content/paths
 
     # Check database
     liminal_cli db inspect liminal.db
 
+    # Back up and restore
+    liminal_cli db export liminal.db --out backup.json
+    liminal_cli db import restored.db backup.json [--replace]
+
+    # Upgrade a database to the current schema
+    liminal_cli db migrate liminal.db
+
+    # Leeches with remediation suggestions
+    liminal_cli leeches --db liminal.db --scripts assets/scripts --roles content/roles
+
+    # Compare rehearsal settings: daily pings, waves and workload as CSV
+    liminal_cli simulate-retention --script cafe-to-go-01 --profile casual --days 14
+    liminal_cli simulate-retention --profile regular --sessions 6 --out sim.csv
+    liminal_cli simulate-retention --trajectory > waves.csv
+
+    # Verify a certificate (JSON or the exported HTML page), optionally
+    # pinning the signer's public key
+    liminal_cli verify-certificate certificate.html --public-key <hex>
+
+    # What to practise next, with reasons
+    liminal_cli recommend --db liminal.db --user user-1 --limit 5
+
     # Health check
     liminal_cli health
 "#
@@ -67,6 +102,7 @@ fn cmd_validate(args: &[String]) {
     // Parse arguments for custom paths
     let scripts_dir = get_arg_value(args, "--scripts").unwrap_or("assets/scripts".to_string());
     let roles_dir = get_arg_value(args, "--roles").unwrap_or("content/roles".to_string());
+    let paths_dir = get_arg_value(args, "--paths").unwrap_or("content/paths".to_string());
 
     // Load scripts
     println!("📄 Loading scripts from: {}", scripts_dir);
@@ -88,6 +124,16 @@ fn cmd_validate(args: &[String]) {
         }
     }
 
+    // Load role paths
+    println!("🧭 Loading role paths from: {}", paths_dir);
+    match validator.load_paths(Path::new(&paths_dir)) {
+        Ok(_) => println!("   ✓ Paths loaded"),
+        Err(e) => {
+            eprintln!("   ✗ Failed to load paths: {}", e);
+            process::exit(1);
+        }
+    }
+
     // Validate
     let report = validator.validate();
     report.print();
@@ -100,7 +146,7 @@ fn cmd_validate(args: &[String]) {
 fn cmd_db(args: &[String]) {
     if args.is_empty() {
         eprintln!("Error: db command requires a subcommand");
-        eprintln!("Usage: liminal_cli db <inspect|export> <path>");
+        eprintln!("Usage: liminal_cli db <inspect|export|import|migrate> <path>");
         process::exit(1);
     }
 
@@ -121,7 +167,28 @@ fn cmd_db(args: &[String]) {
                 process::exit(1);
             }
             let db_path = &args[1];
-            db_export(db_path);
+            db_export(db_path, get_arg_value(&args[2..], "--out"));
+        }
+        "import" => {
+            if args.len() < 3 {
+                eprintln!("Error: import requires database path and backup file");
+                eprintln!("Usage: liminal_cli db import <path> <backup.json> [--replace]");
+                process::exit(1);
+            }
+            let mode = if args[3..].iter().any(|a| a == "--replace") {
+                ImportMode::Replace
+            } else {
+                ImportMode::Merge
+            };
+            db_import(&args[1], &args[2], mode);
+        }
+        "migrate" => {
+            if args.len() < 2 {
+                eprintln!("Error: migrate requires database path");
+                process::exit(1);
+            }
+            let db_path = &args[1];
+            db_migrate(db_path);
         }
         _ => {
             eprintln!("Unknown db subcommand: {}", subcommand);
@@ -136,8 +203,11 @@ fn db_inspect(db_path: &str) {
     match Store::open(db_path) {
         Ok(store) => {
             // Get statistics
-            match store.get_streak() {
-                Ok(streak) => println!("📊 Streak: {} days", streak),
+            match store.get_streak_summary(0) {
+                Ok(streak) => println!(
+                    "📊 Streak: {} days (longest {}, {} freeze tokens)",
+                    streak.current, streak.longest, streak.freeze_tokens
+                ),
                 Err(e) => eprintln!("   Error getting streak: {}", e),
             }
 
@@ -160,13 +230,77 @@ fn db_inspect(db_path: &str) {
     }
 }
 
-fn db_export(db_path: &str) {
+fn db_import(db_path: &str, backup_path: &str, mode: ImportMode) {
+    println!(
+        "📥 Importing {} into {} ({:?})\n",
+        backup_path, db_path, mode
+    );
+
+    let json = match std::fs::read_to_string(backup_path) {
+        Ok(json) => json,
+        Err(e) => {
+            eprintln!("❌ Failed to read backup: {}", e);
+            process::exit(1);
+        }
+    };
+
+    match Store::open(db_path) {
+        Ok(store) => match store.import_json(&json, mode) {
+            Ok(summary) => {
+                for (table, rows) in &summary.tables {
+                    println!("   {}: {} rows", table, rows);
+                }
+                println!(
+                    "\n✅ Imported {} rows ({} skipped)",
+                    summary.rows_imported, summary.rows_skipped
+                );
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to import: {}", e);
+                process::exit(1);
+            }
+        },
+        Err(e) => {
+            eprintln!("❌ Failed to open database: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn db_migrate(db_path: &str) {
+    println!("🔧 Migrating database: {}\n", db_path);
+
+    match Store::open(db_path) {
+        Ok(store) => match store.schema_version() {
+            Ok(version) => println!("✅ Schema version {} (latest {})", version, SCHEMA_VERSION),
+            Err(e) => {
+                eprintln!("❌ Failed to read schema version: {}", e);
+                process::exit(1);
+            }
+        },
+        Err(e) => {
+            eprintln!("❌ Failed to migrate database: {}", e);
+            process::exit(1);
+        }
+    }
+}
+
+fn db_export(db_path: &str, out: Option<String>) {
     println!("📦 Exporting database: {}\n", db_path);
 
     match Store::open(db_path) {
         Ok(store) => match store.export_json() {
             Ok(json) => {
-                println!("{}", json);
+                match out {
+                    Some(out) => {
+                        if let Err(e) = std::fs::write(&out, json) {
+                            eprintln!("❌ Failed to write {}: {}", out, e);
+                            process::exit(1);
+                        }
+                        println!("   Written to {}", out);
+                    }
+                    None => println!("{}", json),
+                }
                 println!("\n✅ Export complete");
             }
             Err(e) => {
@@ -181,6 +315,259 @@ fn db_export(db_path: &str) {
     }
 }
 
+fn cmd_leeches(args: &[String]) {
+    let db_path = match get_arg_value(args, "--db") {
+        Some(path) => path,
+        None => {
+            eprintln!("Error: leeches requires --db <path>");
+            process::exit(1);
+        }
+    };
+    let scripts_dir = get_arg_value(args, "--scripts").unwrap_or("assets/scripts".to_string());
+    let roles_dir = get_arg_value(args, "--roles").unwrap_or("content/roles".to_string());
+
+    let scripts = load_content(&scripts_dir, &roles_dir).scripts;
+
+    let store = match Store::open(&db_path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("❌ Failed to open database: {}", e);
+            process::exit(1);
+        }
+    };
+    let leeches = match store.get_leeches() {
+        Ok(leeches) => leeches,
+        Err(e) => {
+            eprintln!("❌ Failed to load leeches: {}", e);
+            process::exit(1);
+        }
+    };
+
+    if leeches.is_empty() {
+        println!("✅ No leeches");
+        return;
+    }
+
+    println!("🩹 {} leech(es)\n", leeches.len());
+    for link in &leeches {
+        let report = LeechReport::new(link, &scripts);
+        println!(
+            "• \"{}\" ({} failed / {} passed, {} in a row)",
+            report.phrase, report.fail_count, report.success_count, report.consecutive_fails
+        );
+        match report.remediation {
+            Some(Remediation::ContrastStep {
+                script_id,
+                step_index,
+                prompt,
+            }) => println!(
+                "    → redo contrast step {} of {}: {}",
+                step_index, script_id, prompt
+            ),
+            Some(Remediation::EasierScene { script_id, title }) => {
+                println!("    → try the easier scene {} ({})", script_id, title)
+            }
+            Some(Remediation::ReplayScenario { script_id, title }) => {
+                println!("    → replay {} ({})", script_id, title)
+            }
+            None => println!("    → no source scenario found"),
+        }
+    }
+}
+
+fn cmd_simulate_retention(args: &[String]) {
+    let scripts_dir = get_arg_value(args, "--scripts").unwrap_or("assets/scripts".to_string());
+    let roles_dir = get_arg_value(args, "--roles").unwrap_or("content/roles".to_string());
+    let profile_name = get_arg_value(args, "--profile").unwrap_or("regular".to_string());
+
+    let mut profile = match LearnerProfile::preset(&profile_name) {
+        Some(profile) => profile,
+        None => {
+            eprintln!(
+                "Error: unknown profile '{}' (use casual, regular or intensive)",
+                profile_name
+            );
+            process::exit(1);
+        }
+    };
+    if let Some(sessions) = parse_arg::<u32>(args, "--sessions") {
+        if sessions == 0 {
+            eprintln!("Error: --sessions must be at least 1");
+            process::exit(1);
+        }
+        profile.sessions_per_day = sessions;
+    }
+    let days = parse_arg::<u32>(args, "--days").unwrap_or(14);
+    let seed = parse_arg::<u64>(args, "--seed").unwrap_or(42);
+
+    let scripts = load_content(&scripts_dir, &roles_dir).scripts;
+
+    let mut selected: Vec<&Script> = match get_arg_value(args, "--script") {
+        Some(id) => match scripts.get(&id) {
+            Some(script) => vec![script],
+            None => {
+                eprintln!("Error: script not found: {}", id);
+                process::exit(1);
+            }
+        },
+        None => scripts.values().collect(),
+    };
+    selected.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let report = simulate_retention(&selected, &profile, days, seed);
+    let csv = if args.iter().any(|a| a == "--trajectory") {
+        report.trajectory_csv()
+    } else {
+        report.daily_csv()
+    };
+
+    match get_arg_value(args, "--out") {
+        Some(out) => {
+            if let Err(e) = std::fs::write(&out, csv) {
+                eprintln!("❌ Failed to write {}: {}", out, e);
+                process::exit(1);
+            }
+            let pings: u32 = report.days.iter().map(|d| d.pings).sum();
+            let minutes: f32 = report.days.iter().map(|d| d.workload_minutes).sum();
+            println!(
+                "✅ Simulated {} script(s) over {} day(s): {} pings, {:.0} min total",
+                selected.len(),
+                days,
+                pings,
+                minutes
+            );
+            println!("   Written to {}", out);
+        }
+        None => print!("{}", csv),
+    }
+}
+
+/// Standalone scripts plus role scenes; load problems are reported, not fatal
+fn load_content(scripts_dir: &str, roles_dir: &str) -> ContentLibrary {
+    let mut library = ContentLibrary::new();
+    if let Err(e) = library.load_scripts_dir(Path::new(scripts_dir)) {
+        eprintln!("⚠️  Failed to load scripts from {}: {}", scripts_dir, e);
+    }
+    if let Err(e) = library.load_roles_dir(Path::new(roles_dir)) {
+        eprintln!("⚠️  Failed to load roles from {}: {}", roles_dir, e);
+    }
+    library
+}
+
+fn cmd_verify_certificate(args: &[String]) {
+    let path = match args.first().filter(|a| !a.starts_with("--")) {
+        Some(path) => path,
+        None => {
+            eprintln!("Error: verify-certificate requires a certificate file");
+            process::exit(1);
+        }
+    };
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("❌ Failed to read {}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    let certificate = match SignedCertificate::parse(&text) {
+        Ok(certificate) => certificate,
+        Err(e) => {
+            eprintln!("❌ Not a certificate: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let expected_key = get_arg_value(args, "--public-key");
+    if let Err(e) = certificate.verify(expected_key.as_deref()) {
+        eprintln!("❌ Invalid certificate: {}", e);
+        process::exit(1);
+    }
+
+    let record = &certificate.record;
+    println!("✅ Valid certificate {}", record.certificate_id);
+    println!("   Role:      {} ({})", record.role_title, record.role_id);
+    println!(
+        "   Coherence: {:.0}%, {} of {} scenes",
+        record.coherence * 100.0,
+        record.scenes.len(),
+        record.total_scenes
+    );
+    println!(
+        "   Period:    {} – {}",
+        record.started_at.format("%Y-%m-%d"),
+        record.completed_at.format("%Y-%m-%d")
+    );
+    println!("   Signed by: {}", certificate.public_key);
+    if expected_key.is_none() {
+        println!("   (pass --public-key to check who signed it)");
+    }
+}
+
+fn cmd_recommend(args: &[String]) {
+    let db_path = match get_arg_value(args, "--db") {
+        Some(path) => path,
+        None => {
+            eprintln!("Error: recommend requires --db <path>");
+            process::exit(1);
+        }
+    };
+    let user_id = get_arg_value(args, "--user").unwrap_or("local".to_string());
+    let scripts_dir = get_arg_value(args, "--scripts").unwrap_or("assets/scripts".to_string());
+    let roles_dir = get_arg_value(args, "--roles").unwrap_or("content/roles".to_string());
+    let paths_dir = get_arg_value(args, "--paths").unwrap_or("content/paths".to_string());
+    let mut config = RecommenderConfig::default();
+    if let Some(limit) = parse_arg::<usize>(args, "--limit") {
+        config.limit = limit;
+    }
+
+    let mut library = load_content(&scripts_dir, &roles_dir);
+    if let Err(e) = library.load_paths_dir(Path::new(&paths_dir)) {
+        eprintln!("⚠️  Failed to load paths from {}: {}", paths_dir, e);
+    }
+
+    let store = match Store::open(&db_path) {
+        Ok(store) => store,
+        Err(e) => {
+            eprintln!("❌ Failed to open database: {}", e);
+            process::exit(1);
+        }
+    };
+    let learner = match store.learner_state(&user_id, &library.roles) {
+        Ok(learner) => learner,
+        Err(e) => {
+            eprintln!("❌ Failed to load learner state: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let paths: Vec<_> = library.paths.values().cloned().collect();
+    let recs = recommend(
+        &library.roles,
+        &library.scripts,
+        &paths,
+        &learner,
+        None,
+        &config,
+    );
+
+    if let Some(level) = recs.level {
+        println!("🎯 Level: {:?}\n", level);
+    }
+    for (heading, list) in [("Scenes", &recs.scenes), ("Roles", &recs.roles)] {
+        println!("{}:", heading);
+        if list.is_empty() {
+            println!("  (nothing to suggest)");
+        }
+        for rec in list {
+            println!("• {} ({}) — {:.2}", rec.title, rec.id, rec.score);
+            for reason in &rec.reasons {
+                println!("    → {}", reason);
+            }
+        }
+        println!();
+    }
+}
+
 fn cmd_health() {
     println!("🏥 System Health Check\n");
 
@@ -191,6 +578,17 @@ fn cmd_health() {
     println!("\n✅ All systems operational");
 }
 
+fn parse_arg<T: std::str::FromStr>(args: &[String], flag: &str) -> Option<T> {
+    let value = get_arg_value(args, flag)?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            eprintln!("Error: invalid value for {}: {}", flag, value);
+            process::exit(1);
+        }
+    }
+}
+
 fn get_arg_value(args: &[String], flag: &str) -> Option<String> {
     for i in 0..args.len() {
         if args[i] == flag && i + 1 < args.len() {
@@ -0,0 +1,397 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+use crate::coherence::CoherenceBreakdown;
+use crate::roles::RoleProgress;
+
+pub const CERTIFICATE_ALGORITHM: &str = "ed25519";
+
+/// Marks the embedded record in [`SignedCertificate::to_html`]
+const HTML_RECORD_TAG: &str = r#"<script type="application/json" id="liminal-certificate">"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateLevel {
+    /// Coherence reached the transition threshold
+    TransitionReady,
+    /// Every scene of the role was completed
+    Completed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CertificateScene {
+    pub scene_id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+/// What a certificate attests to; this is the signed payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompletionRecord {
+    pub certificate_id: String,
+    pub role_id: String,
+    pub role_title: String,
+    pub level: CertificateLevel,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub issued_at: DateTime<Utc>,
+    pub coherence: f32,
+    pub breakdown: CoherenceBreakdown,
+    /// Completed scenes in the order they were played
+    pub scenes: Vec<CertificateScene>,
+    pub total_scenes: usize,
+    pub use_in_wild_count: u32,
+}
+
+impl CompletionRecord {
+    /// Record for `progress`, or an error if the role is neither
+    /// transition-ready nor fully completed
+    ///
+    /// `scene_titles` maps scene ids to display titles where known.
+    pub fn from_progress(
+        progress: &RoleProgress,
+        role_title: &str,
+        breakdown: CoherenceBreakdown,
+        scene_titles: impl Fn(&str) -> Option<String>,
+        use_in_wild_count: u32,
+        now: DateTime<Utc>,
+    ) -> Result<Self> {
+        let level =
+            if progress.total_scenes > 0 && progress.current_scene_index >= progress.total_scenes {
+                CertificateLevel::Completed
+            } else if progress.is_transition_ready() {
+                CertificateLevel::TransitionReady
+            } else {
+                return Err(anyhow!(
+                    "Role '{}' is not ready for a certificate (coherence {:.0}%, {} of {} scenes)",
+                    progress.role_id,
+                    progress.coherence * 100.0,
+                    progress.current_scene_index,
+                    progress.total_scenes
+                ));
+            };
+
+        let mut scenes: Vec<CertificateScene> = Vec::new();
+        for tag in &progress.emotion_tags {
+            if !scenes.iter().any(|s| s.scene_id == tag.scene_id) {
+                scenes.push(CertificateScene {
+                    scene_id: tag.scene_id.clone(),
+                    title: scene_titles(&tag.scene_id),
+                });
+            }
+        }
+
+        Ok(Self {
+            certificate_id: uuid::Uuid::new_v4().to_string(),
+            role_id: progress.role_id.clone(),
+            role_title: role_title.to_string(),
+            level,
+            started_at: progress.created_at,
+            completed_at: progress.last_transition.unwrap_or(progress.updated_at),
+            issued_at: now,
+            coherence: progress.coherence,
+            breakdown,
+            scenes,
+            total_scenes: progress.total_scenes,
+            use_in_wild_count,
+        })
+    }
+
+    fn signed_bytes(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+}
+
+/// A completion record with the device's Ed25519 signature over its JSON
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignedCertificate {
+    pub record: CompletionRecord,
+    pub algorithm: String,
+    /// Hex-encoded 32-byte verifying key
+    pub public_key: String,
+    /// Hex-encoded 64-byte signature
+    pub signature: String,
+}
+
+/// On-device key that signs certificates
+pub struct CertificateSigner {
+    key: SigningKey,
+}
+
+impl CertificateSigner {
+    pub fn generate() -> Result<Self> {
+        let mut seed = [0u8; 32];
+        getrandom::fill(&mut seed).map_err(|e| anyhow!("No randomness for signing key: {}", e))?;
+        Ok(Self::from_seed(seed))
+    }
+
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            key: SigningKey::from_bytes(&seed),
+        }
+    }
+
+    pub fn from_hex(seed: &str) -> Result<Self> {
+        let bytes: [u8; 32] = decode_hex(seed)?
+            .try_into()
+            .map_err(|_| anyhow!("Signing key must be 32 bytes"))?;
+        Ok(Self::from_seed(bytes))
+    }
+
+    pub fn seed_hex(&self) -> String {
+        encode_hex(self.key.as_bytes())
+    }
+
+    pub fn public_key_hex(&self) -> String {
+        encode_hex(self.key.verifying_key().as_bytes())
+    }
+
+    pub fn sign(&self, record: CompletionRecord) -> Result<SignedCertificate> {
+        let signature = self.key.sign(&record.signed_bytes()?);
+        Ok(SignedCertificate {
+            record,
+            algorithm: CERTIFICATE_ALGORITHM.to_string(),
+            public_key: self.public_key_hex(),
+            signature: encode_hex(&signature.to_bytes()),
+        })
+    }
+}
+
+impl SignedCertificate {
+    /// Check the signature, and that it was made by `expected_key` if given
+    pub fn verify(&self, expected_key: Option<&str>) -> Result<()> {
+        if self.algorithm != CERTIFICATE_ALGORITHM {
+            return Err(anyhow!("Unsupported algorithm: {}", self.algorithm));
+        }
+        if let Some(expected) = expected_key {
+            if !expected.trim().eq_ignore_ascii_case(&self.public_key) {
+                return Err(anyhow!("Certificate was signed by a different key"));
+            }
+        }
+
+        let key_bytes: [u8; 32] = decode_hex(&self.public_key)?
+            .try_into()
+            .map_err(|_| anyhow!("Public key must be 32 bytes"))?;
+        let signature_bytes: [u8; 64] = decode_hex(&self.signature)?
+            .try_into()
+            .map_err(|_| anyhow!("Signature must be 64 bytes"))?;
+
+        VerifyingKey::from_bytes(&key_bytes)?
+            .verify(
+                &self.record.signed_bytes()?,
+                &Signature::from_bytes(&signature_bytes),
+            )
+            .map_err(|_| anyhow!("Signature does not match the certificate contents"))
+    }
+
+    /// Certificate JSON, or the HTML page produced by [`to_html`](Self::to_html)
+    pub fn parse(text: &str) -> Result<Self> {
+        let json = match text.find(HTML_RECORD_TAG) {
+            Some(start) => {
+                let rest = &text[start + HTML_RECORD_TAG.len()..];
+                let end = rest
+                    .find("</script>")
+                    .ok_or_else(|| anyhow!("Unterminated certificate data"))?;
+                &rest[..end]
+            }
+            None => text,
+        };
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let r = &self.record;
+        let level = match r.level {
+            CertificateLevel::Completed => "has completed the role",
+            CertificateLevel::TransitionReady => "has grown into the role",
+        };
+        format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="800" height="560" viewBox="0 0 800 560">
+  <rect width="800" height="560" fill="#FDFBF7"/>
+  <rect x="20" y="20" width="760" height="520" fill="none" stroke="#4A90E2" stroke-width="4"/>
+  <text x="400" y="110" text-anchor="middle" font-family="Georgia, serif" font-size="34" fill="#333">Certificate of Role Completion</text>
+  <text x="400" y="180" text-anchor="middle" font-family="Helvetica, sans-serif" font-size="18" fill="#555">English Liminal certifies that the learner {level}</text>
+  <text x="400" y="240" text-anchor="middle" font-family="Georgia, serif" font-size="40" fill="#4A90E2">{title}</text>
+  <text x="400" y="300" text-anchor="middle" font-family="Helvetica, sans-serif" font-size="18" fill="#333">Coherence {coherence:.0}% · {done} of {total} scenes · used in real life {wild} time(s)</text>
+  <text x="400" y="335" text-anchor="middle" font-family="Helvetica, sans-serif" font-size="16" fill="#555">{started} – {completed}</text>
+  <text x="400" y="470" text-anchor="middle" font-family="Menlo, monospace" font-size="11" fill="#888">Certificate {id}</text>
+  <text x="400" y="490" text-anchor="middle" font-family="Menlo, monospace" font-size="11" fill="#888">Ed25519 key {key}</text>
+  <text x="400" y="510" text-anchor="middle" font-family="Menlo, monospace" font-size="11" fill="#888">Verify: liminal_cli verify-certificate &lt;file&gt;</text>
+</svg>"##,
+            level = level,
+            title = escape_xml(&r.role_title),
+            coherence = r.coherence * 100.0,
+            done = r.scenes.len(),
+            total = r.total_scenes,
+            wild = r.use_in_wild_count,
+            started = r.started_at.format("%Y-%m-%d"),
+            completed = r.completed_at.format("%Y-%m-%d"),
+            id = escape_xml(&r.certificate_id),
+            key = escape_xml(&self.public_key),
+        )
+    }
+
+    /// Standalone page with the certificate image and the signed data
+    pub fn to_html(&self) -> Result<String> {
+        let scenes: String = self
+            .record
+            .scenes
+            .iter()
+            .map(|s| {
+                format!(
+                    "      <li>{}</li>\n",
+                    escape_xml(s.title.as_deref().unwrap_or(&s.scene_id))
+                )
+            })
+            .collect();
+        // `</` cannot appear inside the script element
+        let data = serde_json::to_string(self)?.replace("</", "<\\/");
+        Ok(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <title>{title} — Certificate</title>
+  </head>
+  <body>
+    {svg}
+    <h2>Scenes</h2>
+    <ul>
+{scenes}    </ul>
+    <p>Signed with Ed25519 key <code>{key}</code></p>
+    {tag}{data}</script>
+  </body>
+</html>
+"#,
+            title = escape_xml(&self.record.role_title),
+            svg = self.to_svg(),
+            scenes = scenes,
+            key = escape_xml(&self.public_key),
+            tag = HTML_RECORD_TAG,
+            data = data,
+        ))
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let hex = hex.trim();
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        return Err(anyhow!("Invalid hex string"));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| anyhow!("Invalid hex string")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coherence::CoherenceEngine;
+    use crate::roles::EmotionTag;
+
+    fn completed_progress() -> RoleProgress {
+        let mut progress = RoleProgress::new("qa_engineer_abroad".to_string(), 2);
+        progress.complete_scene(EmotionTag::new("qa_interview_01".to_string(), "Calm", 0.9));
+        progress.complete_scene(EmotionTag::new("qa_bugreport_02".to_string(), "Calm", 0.9));
+        progress
+    }
+
+    fn certificate() -> SignedCertificate {
+        let progress = completed_progress();
+        let breakdown = progress
+            .clone()
+            .calculate_coherence_with(&CoherenceEngine::default(), &[]);
+        let record = CompletionRecord::from_progress(
+            &progress,
+            "QA Engineer <Abroad>",
+            breakdown,
+            |id| (id == "qa_interview_01").then(|| "Tech Interview".to_string()),
+            3,
+            Utc::now(),
+        )
+        .unwrap();
+        CertificateSigner::from_seed([7; 32]).sign(record).unwrap()
+    }
+
+    #[test]
+    fn test_signed_certificate_verifies() {
+        let cert = certificate();
+        assert_eq!(cert.record.level, CertificateLevel::Completed);
+        assert_eq!(cert.record.scenes.len(), 2);
+        assert_eq!(
+            cert.record.scenes[0].title.as_deref(),
+            Some("Tech Interview")
+        );
+        assert!(cert.verify(None).is_ok());
+        assert!(cert.verify(Some(&cert.public_key.to_uppercase())).is_ok());
+
+        let other = CertificateSigner::from_seed([8; 32]);
+        assert!(cert.verify(Some(&other.public_key_hex())).is_err());
+
+        // Survives a JSON round trip
+        let json = serde_json::to_string(&cert).unwrap();
+        assert!(SignedCertificate::parse(&json)
+            .unwrap()
+            .verify(None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_tampering_is_detected() {
+        let mut cert = certificate();
+        cert.record.coherence = 1.0;
+        assert!(cert.verify(None).is_err());
+
+        let mut cert = certificate();
+        cert.signature = "00".repeat(64);
+        assert!(cert.verify(None).is_err());
+    }
+
+    #[test]
+    fn test_not_ready_roles_are_refused() {
+        let mut progress = RoleProgress::new("qa".to_string(), 5);
+        progress.complete_scene(EmotionTag::new("s1".to_string(), "Calm", 0.9));
+        let breakdown = progress
+            .clone()
+            .calculate_coherence_with(&CoherenceEngine::default(), &[]);
+        let err =
+            CompletionRecord::from_progress(&progress, "QA", breakdown, |_| None, 0, Utc::now())
+                .unwrap_err();
+        assert!(err.to_string().contains("not ready"));
+    }
+
+    #[test]
+    fn test_html_embeds_verifiable_data() {
+        let cert = certificate();
+        let html = cert.to_html().unwrap();
+        assert!(html.contains("QA Engineer &lt;Abroad&gt;"));
+        assert!(html.contains("<svg"));
+        assert!(cert.to_svg().contains("Coherence"));
+
+        let parsed = SignedCertificate::parse(&html).unwrap();
+        assert_eq!(parsed, cert);
+        assert!(parsed.verify(None).is_ok());
+    }
+
+    #[test]
+    fn test_signer_key_round_trip() {
+        let signer = CertificateSigner::generate().unwrap();
+        let restored = CertificateSigner::from_hex(&signer.seed_hex()).unwrap();
+        assert_eq!(restored.public_key_hex(), signer.public_key_hex());
+        assert!(CertificateSigner::from_hex("abc").is_err());
+    }
+}
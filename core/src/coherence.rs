@@ -0,0 +1,308 @@
+use serde::{Deserialize, Serialize};
+
+/// Tuning for [`CoherenceEngine::score`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CoherenceEngine {
+    /// How much weak speak_check scores can pull completion down (0..1)
+    pub speak_influence: f32,
+    pub wild_bonus_per_use: f32,
+    pub max_wild_bonus: f32,
+    pub skip_penalty_per_step: f32,
+    pub max_skip_penalty: f32,
+    /// Streak multipliers: 7+ days, 3-6 days, 1-2 days, no streak
+    pub week_streak_multiplier: f32,
+    pub short_streak_multiplier: f32,
+    pub daily_multiplier: f32,
+    pub sporadic_multiplier: f32,
+}
+
+impl Default for CoherenceEngine {
+    fn default() -> Self {
+        Self {
+            speak_influence: 0.3,
+            wild_bonus_per_use: 0.1,
+            max_wild_bonus: 0.5,
+            skip_penalty_per_step: 0.05,
+            max_skip_penalty: 0.3,
+            week_streak_multiplier: 1.5,
+            short_streak_multiplier: 1.2,
+            daily_multiplier: 1.0,
+            sporadic_multiplier: 0.8,
+        }
+    }
+}
+
+/// One scene of a role as seen by the scoring engine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneInput {
+    pub scene_id: String,
+    /// `coherence_weight` from the scene YAML, if declared
+    pub weight: Option<f32>,
+    pub completed: bool,
+    /// Mean best speak_check score (0..1) of the latest completed run
+    pub speak_score: Option<f32>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CoherenceInputs {
+    /// Every scene of the role, in order
+    pub scenes: Vec<SceneInput>,
+    pub use_in_wild_count: u32,
+    pub skipped_steps: u32,
+    /// `None` when the streak is unknown, which leaves the score unscaled
+    pub consecutive_days: Option<u32>,
+}
+
+impl CoherenceInputs {
+    /// `total` equally weighted scenes of which the first `completed` are done
+    pub fn uniform(total: usize, completed: usize) -> Self {
+        Self {
+            scenes: (0..total)
+                .map(|i| SceneInput {
+                    scene_id: format!("scene-{}", i + 1),
+                    weight: None,
+                    completed: i < completed,
+                    speak_score: None,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Scenes played in order: the first `completed` count as done
+    ///
+    /// Falls back to [`uniform`](Self::uniform) with `total` scenes when no
+    /// scene details are known.
+    pub fn sequential(scenes: &[SceneInput], total: usize, completed: usize) -> Self {
+        if scenes.is_empty() {
+            return Self::uniform(total, completed);
+        }
+        Self {
+            scenes: scenes
+                .iter()
+                .enumerate()
+                .map(|(i, scene)| SceneInput {
+                    completed: i < completed,
+                    ..scene.clone()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Coherence score with every component that went into it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoherenceBreakdown {
+    pub score: f32,
+    /// Weighted share of completed scenes (0..1)
+    pub completion: f32,
+    pub speak_quality: Option<f32>,
+    pub speak_factor: f32,
+    pub consistency: f32,
+    pub wild_bonus: f32,
+    pub skip_penalty: f32,
+    /// One line per component, for display
+    pub explanation: Vec<String>,
+}
+
+impl CoherenceEngine {
+    /// `(completion * speak_factor * consistency) + wild_bonus - skip_penalty`,
+    /// clamped to 0..1
+    ///
+    /// Scenes without a `coherence_weight` count as the mean of the declared
+    /// weights (or 1.0 when none are declared).
+    pub fn score(&self, inputs: &CoherenceInputs) -> CoherenceBreakdown {
+        let weights = scene_weights(&inputs.scenes);
+        let total: f32 = weights.iter().sum();
+        let done: f32 = inputs
+            .scenes
+            .iter()
+            .zip(&weights)
+            .filter(|(s, _)| s.completed)
+            .map(|(_, w)| w)
+            .sum();
+        let completion = if total > 0.0 { done / total } else { 0.0 };
+
+        let (speak_sum, speak_weight) = inputs
+            .scenes
+            .iter()
+            .zip(&weights)
+            .filter(|(s, _)| s.completed)
+            .filter_map(|(s, w)| s.speak_score.map(|q| (q.clamp(0.0, 1.0) * w, *w)))
+            .fold((0.0, 0.0), |(sum, n), (q, w)| (sum + q, n + w));
+        let speak_quality = (speak_weight > 0.0).then(|| speak_sum / speak_weight);
+        let speak_factor = speak_quality
+            .map(|q| 1.0 - self.speak_influence.clamp(0.0, 1.0) * (1.0 - q))
+            .unwrap_or(1.0);
+
+        let consistency = match inputs.consecutive_days {
+            None => 1.0,
+            Some(d) if d >= 7 => self.week_streak_multiplier,
+            Some(d) if d >= 3 => self.short_streak_multiplier,
+            Some(d) if d >= 1 => self.daily_multiplier,
+            Some(_) => self.sporadic_multiplier,
+        };
+
+        let wild_bonus =
+            (inputs.use_in_wild_count as f32 * self.wild_bonus_per_use).min(self.max_wild_bonus);
+        let skip_penalty =
+            (inputs.skipped_steps as f32 * self.skip_penalty_per_step).min(self.max_skip_penalty);
+
+        let score = if inputs.scenes.is_empty() {
+            0.0
+        } else {
+            (completion * speak_factor * consistency + wild_bonus - skip_penalty).clamp(0.0, 1.0)
+        };
+
+        let completed = inputs.scenes.iter().filter(|s| s.completed).count();
+        let mut explanation = vec![format!(
+            "Completion {:.0}% ({} of {} scenes, weighted)",
+            completion * 100.0,
+            completed,
+            inputs.scenes.len()
+        )];
+        if let Some(q) = speak_quality {
+            explanation.push(format!(
+                "Speaking quality {:.0}% (x{:.2})",
+                q * 100.0,
+                speak_factor
+            ));
+        }
+        if let Some(days) = inputs.consecutive_days {
+            explanation.push(format!("{}-day streak (x{:.1})", days, consistency));
+        }
+        if wild_bonus > 0.0 {
+            explanation.push(format!(
+                "Used in the wild {} time(s) (+{:.0}%)",
+                inputs.use_in_wild_count,
+                wild_bonus * 100.0
+            ));
+        }
+        if skip_penalty > 0.0 {
+            explanation.push(format!(
+                "Skipped {} step(s) (-{:.0}%)",
+                inputs.skipped_steps,
+                skip_penalty * 100.0
+            ));
+        }
+
+        CoherenceBreakdown {
+            score,
+            completion,
+            speak_quality,
+            speak_factor,
+            consistency,
+            wild_bonus,
+            skip_penalty,
+            explanation,
+        }
+    }
+}
+
+fn scene_weights(scenes: &[SceneInput]) -> Vec<f32> {
+    let declared: Vec<f32> = scenes
+        .iter()
+        .filter_map(|s| s.weight)
+        .filter(|w| *w > 0.0)
+        .collect();
+    let fallback = if declared.is_empty() {
+        1.0
+    } else {
+        declared.iter().sum::<f32>() / declared.len() as f32
+    };
+    scenes
+        .iter()
+        .map(|s| s.weight.filter(|w| *w > 0.0).unwrap_or(fallback))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scene(weight: Option<f32>, completed: bool, speak: Option<f32>) -> SceneInput {
+        SceneInput {
+            scene_id: "s".to_string(),
+            weight,
+            completed,
+            speak_score: speak,
+        }
+    }
+
+    #[test]
+    fn test_uniform_completion() {
+        let breakdown = CoherenceEngine::default().score(&CoherenceInputs::uniform(5, 3));
+        assert_eq!(breakdown.score, 0.6);
+        assert_eq!(breakdown.consistency, 1.0);
+        assert_eq!(breakdown.speak_factor, 1.0);
+        assert_eq!(breakdown.explanation.len(), 1);
+    }
+
+    #[test]
+    fn test_scene_weights_shift_completion() {
+        let inputs = CoherenceInputs {
+            scenes: vec![scene(Some(0.1), true, None), scene(Some(0.3), false, None)],
+            ..Default::default()
+        };
+        let breakdown = CoherenceEngine::default().score(&inputs);
+        assert!((breakdown.completion - 0.25).abs() < 1e-6);
+
+        // Undeclared weights count as the mean of the declared ones
+        let inputs = CoherenceInputs {
+            scenes: vec![
+                scene(Some(0.2), true, None),
+                scene(None, true, None),
+                scene(Some(0.2), false, None),
+            ],
+            ..Default::default()
+        };
+        let breakdown = CoherenceEngine::default().score(&inputs);
+        assert!((breakdown.completion - 2.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_weak_speaking_lowers_score() {
+        let strong = CoherenceInputs {
+            scenes: vec![scene(None, true, Some(1.0)), scene(None, false, None)],
+            ..Default::default()
+        };
+        let weak = CoherenceInputs {
+            scenes: vec![scene(None, true, Some(0.5)), scene(None, false, None)],
+            ..Default::default()
+        };
+        let engine = CoherenceEngine::default();
+
+        assert_eq!(engine.score(&strong).score, 0.5);
+        let breakdown = engine.score(&weak);
+        assert!((breakdown.speak_factor - 0.85).abs() < 1e-6);
+        assert!((breakdown.score - 0.425).abs() < 1e-6);
+        assert_eq!(breakdown.speak_quality, Some(0.5));
+    }
+
+    #[test]
+    fn test_streak_wild_and_skips() {
+        let mut inputs = CoherenceInputs::uniform(4, 2);
+        inputs.consecutive_days = Some(0);
+        let engine = CoherenceEngine::default();
+        assert!((engine.score(&inputs).score - 0.4).abs() < 1e-6);
+
+        inputs.consecutive_days = Some(7);
+        inputs.use_in_wild_count = 2;
+        inputs.skipped_steps = 1;
+        let breakdown = engine.score(&inputs);
+        assert!((breakdown.score - (0.75 + 0.2 - 0.05)).abs() < 1e-6);
+        assert_eq!(breakdown.explanation.len(), 4);
+
+        inputs.use_in_wild_count = 20;
+        assert_eq!(engine.score(&inputs).score, 1.0);
+    }
+
+    #[test]
+    fn test_empty_role_scores_zero() {
+        let mut inputs = CoherenceInputs::uniform(0, 0);
+        inputs.use_in_wild_count = 3;
+        assert_eq!(CoherenceEngine::default().score(&inputs).score, 0.0);
+    }
+}
@@ -0,0 +1,359 @@
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::roles::{Role, RolePath};
+use crate::scripts::Script;
+
+/// Name of the role manifest inside `content/roles/<role>/`
+pub const ROLE_MANIFEST: &str = "_role.yaml";
+
+/// Scripts and roles loaded from disk, with role scenes resolved to script ids
+///
+/// Layout:
+///
+/// ```text
+/// assets/scripts/<scene>.yaml          standalone scenes
+/// content/roles/<role>/_role.yaml      role manifest, `scenes:` lists files
+/// content/roles/<role>/<scene>.yaml    scenes of that role
+/// content/paths/<path>.yaml            role paths, `roles:` lists role ids
+/// ```
+#[derive(Debug, Default)]
+pub struct ContentLibrary {
+    pub scripts: HashMap<String, Script>,
+    /// Roles with `scenario_ids` rewritten to the ids of resolved scripts
+    pub roles: HashMap<String, Role>,
+    pub paths: HashMap<String, RolePath>,
+    /// `(role_id, scene)` references that did not resolve to a script
+    pub unresolved: Vec<(String, String)>,
+    /// Ids defined by more than one file
+    pub duplicates: Vec<String>,
+    sources: HashMap<String, PathBuf>,
+}
+
+impl ContentLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every scene in `dir` (not recursive); `_`-prefixed files are skipped
+    pub fn load_scripts_dir(&mut self, dir: &Path) -> Result<usize> {
+        if !dir.exists() {
+            return Err(anyhow!("Scripts directory not found: {:?}", dir));
+        }
+
+        let mut count = 0;
+        for path in yaml_files(dir)? {
+            if is_meta(&path) {
+                continue;
+            }
+            let script = load_script(&path)?;
+            self.insert_script(script, path);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Load every role path in `dir` (not recursive)
+    pub fn load_paths_dir(&mut self, dir: &Path) -> Result<usize> {
+        if !dir.exists() {
+            return Err(anyhow!("Paths directory not found: {:?}", dir));
+        }
+
+        let mut count = 0;
+        for path in yaml_files(dir)? {
+            let role_path: RolePath = serde_yaml::from_str(&fs::read_to_string(&path)?)
+                .map_err(|e| anyhow!("Failed to load path {:?}: {}", path, e))?;
+            if self.paths.contains_key(&role_path.id) {
+                self.duplicates.push(role_path.id.clone());
+            }
+            self.paths.insert(role_path.id.clone(), role_path);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Walk a roles tree and load each `_role.yaml` together with its scenes
+    ///
+    /// Scenes that do not declare `role_id` are assigned to the role whose
+    /// folder they sit in.
+    pub fn load_roles_dir(&mut self, dir: &Path) -> Result<usize> {
+        if !dir.exists() {
+            return Err(anyhow!("Roles directory not found: {:?}", dir));
+        }
+
+        let before = self.roles.len();
+        self.scan_roles_dir(dir)?;
+        Ok(self.roles.len() - before)
+    }
+
+    fn scan_roles_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        entries.sort();
+
+        for path in entries.iter().filter(|p| p.is_dir()) {
+            self.scan_roles_dir(path)?;
+        }
+
+        let manifest = dir.join(ROLE_MANIFEST);
+        if manifest.is_file() {
+            self.load_role_folder(dir, &manifest)?;
+        }
+        Ok(())
+    }
+
+    fn load_role_folder(&mut self, dir: &Path, manifest: &Path) -> Result<()> {
+        let mut role: Role = serde_yaml::from_str(&fs::read_to_string(manifest)?)
+            .map_err(|e| anyhow!("Failed to load role {:?}: {}", manifest, e))?;
+
+        // Scene file name -> script id for this folder
+        let mut by_file = HashMap::new();
+        for path in yaml_files(dir)? {
+            if is_meta(&path) {
+                continue;
+            }
+            let mut script = load_script(&path)?;
+            script.role_id.get_or_insert_with(|| role.id.clone());
+            if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                by_file.insert(name.to_string(), script.id.clone());
+            }
+            self.insert_script(script, path);
+        }
+
+        let mut resolved = Vec::with_capacity(role.scenario_ids.len());
+        for scene in &role.scenario_ids {
+            match self.resolve_scene(scene, &by_file) {
+                Some(id) => resolved.push(id),
+                None => self.unresolved.push((role.id.clone(), scene.clone())),
+            }
+        }
+        role.scenario_ids = resolved;
+
+        if self.roles.contains_key(&role.id) {
+            self.duplicates.push(role.id.clone());
+        }
+        self.roles.insert(role.id.clone(), role);
+        Ok(())
+    }
+
+    /// A `scenes:` entry is a file in the role folder, or a script id
+    fn resolve_scene(&self, scene: &str, by_file: &HashMap<String, String>) -> Option<String> {
+        if let Some(id) = by_file.get(scene) {
+            return Some(id.clone());
+        }
+        self.find_script(scene).map(|s| s.id.clone())
+    }
+
+    /// Script a scene reference points at; see [`find_script`]
+    pub fn find_script(&self, scene: &str) -> Option<&Script> {
+        find_script(&self.scripts, scene)
+    }
+
+    fn insert_script(&mut self, script: Script, path: PathBuf) {
+        if self.sources.get(&script.id).is_some_and(|p| *p != path) {
+            self.duplicates.push(script.id.clone());
+        }
+        self.sources.insert(script.id.clone(), path);
+        self.scripts.insert(script.id.clone(), script);
+    }
+
+    /// Scripts of a role in manifest order
+    pub fn scripts_for_role(&self, role_id: &str) -> Vec<&Script> {
+        self.roles
+            .get(role_id)
+            .map(|role| scripts_for_role(role, &self.scripts))
+            .unwrap_or_default()
+    }
+}
+
+/// Scripts of `role` in manifest order, skipping ids that are not loaded
+pub fn scripts_for_role<'a>(role: &Role, scripts: &'a HashMap<String, Script>) -> Vec<&'a Script> {
+    role.scenario_ids
+        .iter()
+        .filter_map(|id| scripts.get(id))
+        .collect()
+}
+
+/// Script a scene reference points at
+///
+/// Scene references are script ids, or file names as written in role
+/// manifests and recorded on older emotion tags: `qa-interview-01.yaml`
+/// and `qa-interview-01` both find `qa_interview_01`.
+pub fn find_script<'a>(scripts: &'a HashMap<String, Script>, scene: &str) -> Option<&'a Script> {
+    let stem = scene.strip_suffix(".yaml").unwrap_or(scene);
+    scripts
+        .get(stem)
+        .or_else(|| scripts.get(&stem.replace('-', "_")))
+}
+
+fn yaml_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("yaml") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn is_meta(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| name.starts_with('_'))
+}
+
+fn load_script(path: &Path) -> Result<Script> {
+    serde_yaml::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| anyhow!("Failed to load script {:?}: {}", path, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = "title: t\nsteps:\n  - type: listen\n    prompt: a\n";
+
+    fn write(dir: &Path, name: &str, body: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(name), body).unwrap();
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("liminal_{}_{}", name, std::process::id()));
+        fs::remove_dir_all(&dir).ok();
+        dir
+    }
+
+    #[test]
+    fn test_find_script_accepts_file_names() {
+        let script: Script =
+            serde_yaml::from_str(&format!("id: qa_interview_01\n{}", SCENE)).unwrap();
+        let scripts = HashMap::from([(script.id.clone(), script)]);
+
+        for scene in ["qa_interview_01", "qa-interview-01", "qa-interview-01.yaml"] {
+            assert_eq!(find_script(&scripts, scene).unwrap().id, "qa_interview_01");
+        }
+        assert!(find_script(&scripts, "qa-interview-02").is_none());
+    }
+
+    #[test]
+    fn test_resolves_scene_files_in_manifest_order() {
+        let root = temp_dir("content_roles");
+        let role = root.join("qa_abroad");
+        write(
+            &role,
+            ROLE_MANIFEST,
+            "id: qa\ntitle: QA\ndescription: d\nscenes:\n  - qa-standup-02.yaml\n  - qa-interview-01.yaml\n  - missing-03.yaml\n",
+        );
+        write(
+            &role,
+            "qa-interview-01.yaml",
+            &format!("id: qa_interview_01\n{}", SCENE),
+        );
+        write(
+            &role,
+            "qa-standup-02.yaml",
+            &format!("id: qa_standup_02\nrole_id: qa\n{}", SCENE),
+        );
+
+        let mut library = ContentLibrary::new();
+        assert_eq!(library.load_roles_dir(&root).unwrap(), 1);
+
+        let ids: Vec<&str> = library
+            .scripts_for_role("qa")
+            .iter()
+            .map(|s| s.id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["qa_standup_02", "qa_interview_01"]);
+        assert_eq!(
+            library.scripts["qa_interview_01"].role_id.as_deref(),
+            Some("qa")
+        );
+        assert_eq!(
+            library.unresolved,
+            vec![("qa".to_string(), "missing-03.yaml".to_string())]
+        );
+        assert!(library.scripts_for_role("other").is_empty());
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_scenes_may_reference_standalone_scripts() {
+        let root = temp_dir("content_mixed");
+        write(
+            &root.join("scripts"),
+            "cafe-to-go-01.yaml",
+            &format!("id: cafe-to-go-01\n{}", SCENE),
+        );
+        write(&root.join("scripts"), "_meta.yaml", "not: a script\n");
+        write(
+            &root.join("roles/traveller"),
+            ROLE_MANIFEST,
+            "id: traveller\ntitle: T\ndescription: d\nscenes:\n  - cafe-to-go-01.yaml\n",
+        );
+
+        let mut library = ContentLibrary::new();
+        assert_eq!(library.load_scripts_dir(&root.join("scripts")).unwrap(), 1);
+        library.load_roles_dir(&root.join("roles")).unwrap();
+
+        assert_eq!(
+            library.roles["traveller"].scenario_ids,
+            vec!["cafe-to-go-01"]
+        );
+        assert!(library.unresolved.is_empty());
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_reports_duplicate_ids() {
+        let root = temp_dir("content_dupes");
+        write(&root, "a.yaml", &format!("id: same\n{}", SCENE));
+        write(&root, "b.yaml", &format!("id: same\n{}", SCENE));
+
+        let mut library = ContentLibrary::new();
+        library.load_scripts_dir(&root).unwrap();
+        assert_eq!(library.duplicates, vec!["same"]);
+
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_repo_content_resolves() {
+        let mut library = ContentLibrary::new();
+        library
+            .load_scripts_dir(Path::new("../assets/scripts"))
+            .unwrap();
+        library
+            .load_roles_dir(Path::new("../content/roles"))
+            .unwrap();
+
+        assert!(library.unresolved.is_empty(), "{:?}", library.unresolved);
+        let qa = library.scripts_for_role("qa_engineer_abroad");
+        assert_eq!(qa.len(), 5);
+        assert_eq!(qa[0].id, "qa_interview_01");
+        assert!(library
+            .scripts_for_role("remote_developer")
+            .iter()
+            .all(|s| s.role_id.as_deref() == Some("remote_developer")));
+
+        library
+            .load_paths_dir(Path::new("../content/paths"))
+            .unwrap();
+        for path in library.paths.values() {
+            for role_id in &path.role_ids {
+                assert!(library.roles.contains_key(role_id), "{}", role_id);
+            }
+        }
+        assert_eq!(
+            library.paths["work_abroad"].next_role("visa_journey"),
+            Some("qa_engineer_abroad")
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use chrono::{NaiveTime, Timelike, Weekday};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::scripts::Script;
+
+/// Coarse part of the day a scenario fits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+    Night,
+    #[default]
+    Any,
+}
+
+impl TimeOfDay {
+    /// Bucket a local wall-clock time
+    ///
+    /// Morning 05-12, afternoon 12-17, evening 17-22, night 22-05
+    pub fn from_time(time: NaiveTime) -> Self {
+        match time.hour() {
+            5..=11 => TimeOfDay::Morning,
+            12..=16 => TimeOfDay::Afternoon,
+            17..=21 => TimeOfDay::Evening,
+            _ => TimeOfDay::Night,
+        }
+    }
+}
+
+/// Coarse place category reported by the app (never a precise location)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaceCategory {
+    Home,
+    Work,
+    Cafe,
+    Commute,
+    Kindergarten,
+    Social,
+    #[default]
+    Any,
+    /// Unknown category in content; never matches a concrete place
+    #[serde(other)]
+    Other,
+}
+
+/// When and where a script is a good fit
+///
+/// Content declares triggers either as a map or as a list of single-key
+/// maps; both shapes deserialize into this struct.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextTriggers {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<TimeOfDay>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<PlaceCategory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_max_sec: Option<u32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weekdays: Vec<Weekday>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawTriggers {
+    Map(ContextTriggers),
+    List(Vec<ContextTriggers>),
+}
+
+impl ContextTriggers {
+    fn merge(mut self, other: ContextTriggers) -> Self {
+        self.time = other.time.or(self.time);
+        self.location = other.location.or(self.location);
+        self.duration_max_sec = other.duration_max_sec.or(self.duration_max_sec);
+        self.weekdays.extend(other.weekdays);
+        self
+    }
+
+    /// Score how well these triggers fit `ctx`
+    ///
+    /// Returns `None` when the script cannot be done right now (it needs
+    /// more time than is available). Otherwise returns 0.0-1.0 together
+    /// with the reasons that contributed.
+    pub fn evaluate(&self, ctx: &PracticeContext) -> Option<(f32, Vec<String>)> {
+        let mut reasons = Vec::new();
+
+        if let (Some(max_sec), Some(minutes)) = (self.duration_max_sec, ctx.available_minutes) {
+            if max_sec > minutes.saturating_mul(60) {
+                return None;
+            }
+            reasons.push(format!("fits in {} min", minutes));
+        }
+
+        let now = TimeOfDay::from_time(ctx.local_time);
+        let time_score = match self.time.unwrap_or_default() {
+            TimeOfDay::Any => 0.5,
+            t if t == now => {
+                reasons.push(format!("good for the {:?}", now).to_lowercase());
+                1.0
+            }
+            _ => 0.0,
+        };
+
+        let place_score = match (self.location.unwrap_or_default(), ctx.place) {
+            (PlaceCategory::Any, _) | (_, None) => 0.5,
+            (wanted, Some(here)) if wanted == here => {
+                reasons.push(format!("you're at {:?}", here).to_lowercase());
+                1.0
+            }
+            _ => 0.0,
+        };
+
+        let day_score = if self.weekdays.is_empty() {
+            0.5
+        } else if self.weekdays.contains(&ctx.weekday) {
+            reasons.push(format!("scheduled for {}", ctx.weekday));
+            1.0
+        } else {
+            0.0
+        };
+
+        Some(((time_score + place_score + day_score) / 3.0, reasons))
+    }
+}
+
+/// Deserialize triggers from either shape used in content files
+pub(crate) fn deserialize_triggers<'de, D>(deserializer: D) -> Result<ContextTriggers, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw: Option<RawTriggers> = Option::deserialize(deserializer)?;
+    Ok(match raw {
+        None => ContextTriggers::default(),
+        Some(RawTriggers::Map(triggers)) => triggers,
+        Some(RawTriggers::List(items)) => items
+            .into_iter()
+            .fold(ContextTriggers::default(), ContextTriggers::merge),
+    })
+}
+
+/// The learner's current situation as reported by the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PracticeContext {
+    /// Local wall-clock time ("HH:MM:SS")
+    pub local_time: NaiveTime,
+    #[serde(default)]
+    pub place: Option<PlaceCategory>,
+    #[serde(default)]
+    pub available_minutes: Option<u32>,
+    pub weekday: Weekday,
+}
+
+/// A script that fits the current context
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerMatch {
+    pub script_id: String,
+    pub title: String,
+    pub score: f32, // 0.0 - 1.0
+    pub reasons: Vec<String>,
+}
+
+/// Rank scripts by how well they fit `ctx`, best first
+///
+/// Scripts that need more time than is available are left out.
+pub fn rank_scripts<'a, I>(scripts: I, ctx: &PracticeContext) -> Vec<TriggerMatch>
+where
+    I: IntoIterator<Item = &'a Script>,
+{
+    let mut matches: Vec<TriggerMatch> = scripts
+        .into_iter()
+        .filter_map(|script| {
+            let (score, reasons) = script.context_triggers.evaluate(ctx)?;
+            Some(TriggerMatch {
+                script_id: script.id.clone(),
+                title: script.title.clone(),
+                score,
+                reasons,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.script_id.cmp(&b.script_id))
+    });
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script_with(id: &str, triggers_yaml: &str) -> Script {
+        let yaml = format!(
+            "id: {}\ntitle: \"{}\"\ncontext_triggers:\n{}\nsteps: []\n",
+            id, id, triggers_yaml
+        );
+        serde_yaml::from_str(&yaml).unwrap()
+    }
+
+    fn morning_at_cafe(minutes: u32) -> PracticeContext {
+        PracticeContext {
+            local_time: NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+            place: Some(PlaceCategory::Cafe),
+            available_minutes: Some(minutes),
+            weekday: Weekday::Mon,
+        }
+    }
+
+    #[test]
+    fn test_parse_list_and_map_forms() {
+        let list = script_with(
+            "cafe",
+            "  - time: morning\n  - location: cafe\n  - duration_max_sec: 180",
+        );
+        let map = script_with(
+            "standup",
+            "  time: any\n  location: work\n  duration_max_sec: 300",
+        );
+
+        assert_eq!(list.context_triggers.time, Some(TimeOfDay::Morning));
+        assert_eq!(list.context_triggers.location, Some(PlaceCategory::Cafe));
+        assert_eq!(list.context_triggers.duration_max_sec, Some(180));
+
+        assert_eq!(map.context_triggers.time, Some(TimeOfDay::Any));
+        assert_eq!(map.context_triggers.location, Some(PlaceCategory::Work));
+        assert_eq!(map.context_triggers.duration_max_sec, Some(300));
+    }
+
+    #[test]
+    fn test_missing_triggers_default() {
+        let script: Script = serde_yaml::from_str("id: x\ntitle: x\nsteps: []\n").unwrap();
+        assert_eq!(script.context_triggers, ContextTriggers::default());
+    }
+
+    #[test]
+    fn test_unknown_location_parses_as_other() {
+        let script = script_with("park", "  location: park");
+        assert_eq!(script.context_triggers.location, Some(PlaceCategory::Other));
+    }
+
+    #[test]
+    fn test_time_of_day_buckets() {
+        let at = |h| TimeOfDay::from_time(NaiveTime::from_hms_opt(h, 0, 0).unwrap());
+        assert_eq!(at(7), TimeOfDay::Morning);
+        assert_eq!(at(13), TimeOfDay::Afternoon);
+        assert_eq!(at(19), TimeOfDay::Evening);
+        assert_eq!(at(23), TimeOfDay::Night);
+        assert_eq!(at(3), TimeOfDay::Night);
+    }
+
+    #[test]
+    fn test_rank_prefers_matching_context() {
+        let scripts = vec![
+            script_with(
+                "standup",
+                "  time: any\n  location: work\n  duration_max_sec: 240",
+            ),
+            script_with(
+                "cafe",
+                "  - time: morning\n  - location: cafe\n  - duration_max_sec: 180",
+            ),
+            script_with("anytime", "  time: any"),
+        ];
+
+        let ranked = rank_scripts(&scripts, &morning_at_cafe(5));
+
+        assert_eq!(ranked.len(), 3);
+        assert_eq!(ranked[0].script_id, "cafe");
+        assert!(ranked[0].reasons.iter().any(|r| r.contains("cafe")));
+        assert_eq!(ranked[2].script_id, "standup");
+    }
+
+    #[test]
+    fn test_rank_excludes_scripts_that_do_not_fit_time_budget() {
+        let scripts = vec![
+            script_with("long", "  duration_max_sec: 600"),
+            script_with("short", "  duration_max_sec: 120"),
+        ];
+
+        let ranked = rank_scripts(&scripts, &morning_at_cafe(3));
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].script_id, "short");
+    }
+
+    #[test]
+    fn test_weekday_filter() {
+        let triggers = ContextTriggers {
+            weekdays: vec![Weekday::Sat, Weekday::Sun],
+            ..Default::default()
+        };
+        let mut ctx = morning_at_cafe(10);
+
+        let (weekday_score, _) = triggers.evaluate(&ctx).unwrap();
+        ctx.weekday = Weekday::Sat;
+        let (weekend_score, reasons) = triggers.evaluate(&ctx).unwrap();
+
+        assert!(weekend_score > weekday_score);
+        assert!(reasons.iter().any(|r| r.contains("Sat")));
+    }
+
+    #[test]
+    fn test_large_time_budget_does_not_overflow() {
+        let script = script_with("long", "  duration_max_sec: 600");
+        let ranked = rank_scripts([&script], &morning_at_cafe(u32::MAX));
+        assert_eq!(ranked.len(), 1);
+    }
+}
@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+/// Expressed tone can be called a match for the intended one within this
+/// distance in the valence/arousal plane
+pub const EMOTION_MATCH_DISTANCE: f32 = 0.5;
+
+/// Tone the learner expressed, as labelled by tone analysis
+///
+/// Deserializes leniently: legacy labels stored in `emotion_tags` and
+/// compound YAML tags such as `calm_confident` map to the closest tone,
+/// anything unrecognised to `Neutral`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
+#[serde(from = "String")]
+pub enum Tone {
+    Calm,
+    Relaxed,
+    Confident,
+    Clear,
+    Friendly,
+    Curious,
+    Excited,
+    Energetic,
+    #[default]
+    Neutral,
+    Uncertain,
+    Nervous,
+    Rushed,
+}
+
+/// A point in the valence (unpleasant..pleasant) / arousal (low..high)
+/// plane, both in -1..1
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Affect {
+    pub valence: f32,
+    pub arousal: f32,
+}
+
+impl Affect {
+    pub fn distance(&self, other: &Affect) -> f32 {
+        ((self.valence - other.valence).powi(2) + (self.arousal - other.arousal).powi(2)).sqrt()
+    }
+
+    /// Mean affect of the words in a tag like `calm_confident`
+    ///
+    /// Returns `None` when no word is recognised.
+    pub fn of_tag(tag: &str) -> Option<Affect> {
+        let tones: Vec<Tone> = tag_words(tag).filter_map(Tone::from_word).collect();
+        if tones.is_empty() {
+            return None;
+        }
+        let n = tones.len() as f32;
+        Some(Affect {
+            valence: tones.iter().map(|t| t.valence()).sum::<f32>() / n,
+            arousal: tones.iter().map(|t| t.arousal()).sum::<f32>() / n,
+        })
+    }
+}
+
+impl Tone {
+    pub const ALL: [Tone; 12] = [
+        Tone::Calm,
+        Tone::Relaxed,
+        Tone::Confident,
+        Tone::Clear,
+        Tone::Friendly,
+        Tone::Curious,
+        Tone::Excited,
+        Tone::Energetic,
+        Tone::Neutral,
+        Tone::Uncertain,
+        Tone::Nervous,
+        Tone::Rushed,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tone::Calm => "Calm",
+            Tone::Relaxed => "Relaxed",
+            Tone::Confident => "Confident",
+            Tone::Clear => "Clear",
+            Tone::Friendly => "Friendly",
+            Tone::Curious => "Curious",
+            Tone::Excited => "Excited",
+            Tone::Energetic => "Energetic",
+            Tone::Neutral => "Neutral",
+            Tone::Uncertain => "Uncertain",
+            Tone::Nervous => "Nervous",
+            Tone::Rushed => "Rushed",
+        }
+    }
+
+    pub fn valence(&self) -> f32 {
+        self.affect().valence
+    }
+
+    pub fn arousal(&self) -> f32 {
+        self.affect().arousal
+    }
+
+    pub fn affect(&self) -> Affect {
+        let (valence, arousal) = match self {
+            Tone::Calm => (0.5, -0.2),
+            Tone::Relaxed => (0.6, -0.4),
+            Tone::Confident => (0.7, 0.2),
+            Tone::Clear => (0.4, 0.2),
+            Tone::Friendly => (0.7, 0.3),
+            Tone::Curious => (0.3, 0.4),
+            Tone::Excited => (0.8, 0.8),
+            Tone::Energetic => (0.5, 0.8),
+            Tone::Neutral => (0.0, 0.0),
+            Tone::Uncertain => (-0.4, 0.1),
+            Tone::Nervous => (-0.6, 0.6),
+            Tone::Rushed => (-0.3, 0.8),
+        };
+        Affect { valence, arousal }
+    }
+
+    /// Pleasant and not keyed up: the tones that read as self-assured
+    pub fn is_composed(&self) -> bool {
+        self.valence() >= 0.3 && self.arousal() <= 0.5
+    }
+
+    /// Lenient parse: a tone name in any case, a known synonym, or the
+    /// first recognised word of a compound tag; `Neutral` otherwise
+    pub fn parse(value: &str) -> Tone {
+        tag_words(value)
+            .find_map(Tone::from_word)
+            .unwrap_or_default()
+    }
+
+    fn from_word(word: &str) -> Option<Tone> {
+        let tone = match word.to_lowercase().as_str() {
+            "calm" | "composed" | "steady" | "honest" => Tone::Calm,
+            "relaxed" | "casual" | "easygoing" => Tone::Relaxed,
+            "confident" | "assertive" | "decisive" | "prepared" | "professional" => Tone::Confident,
+            "clear" | "precise" | "brief" | "analytical" | "practical" | "diplomatic"
+            | "respectful" | "polite" | "critical" => Tone::Clear,
+            "friendly" | "warm" | "open" | "grateful" | "hopeful" | "collaborative"
+            | "attentive" => Tone::Friendly,
+            "curious" | "engaged" | "interested" => Tone::Curious,
+            "excited" | "enthusiastic" => Tone::Excited,
+            "energetic" | "lively" => Tone::Energetic,
+            "neutral" => Tone::Neutral,
+            "uncertain" | "hesitant" | "unsure" | "cautious" | "concerned" => Tone::Uncertain,
+            "nervous" | "anxious" | "stressed" | "tense" => Tone::Nervous,
+            "rushed" | "hurried" | "urgent" => Tone::Rushed,
+            _ => return None,
+        };
+        Some(tone)
+    }
+}
+
+impl From<String> for Tone {
+    fn from(value: String) -> Self {
+        Tone::parse(&value)
+    }
+}
+
+impl From<&str> for Tone {
+    fn from(value: &str) -> Self {
+        Tone::parse(value)
+    }
+}
+
+impl std::fmt::Display for Tone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+fn tag_words(tag: &str) -> impl Iterator<Item = &str> {
+    tag.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+}
+
+/// Intended emotion of a scene or step against the tone the learner used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionMatch {
+    /// Tag from the YAML, e.g. `calm_confident`
+    pub intended_tag: String,
+    pub intended: Affect,
+    pub expressed: Tone,
+    pub distance: f32,
+    pub matched: bool,
+}
+
+impl EmotionMatch {
+    /// `None` when the intended tag has no recognised words
+    pub fn compare(intended_tag: &str, expressed: Tone) -> Option<EmotionMatch> {
+        let intended = Affect::of_tag(intended_tag)?;
+        let distance = intended.distance(&expressed.affect());
+        Some(EmotionMatch {
+            intended_tag: intended_tag.to_string(),
+            intended,
+            expressed,
+            distance,
+            matched: distance <= EMOTION_MATCH_DISTANCE,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lenient_parsing() {
+        assert_eq!(Tone::parse("Calm"), Tone::Calm);
+        assert_eq!(Tone::parse("nervous"), Tone::Nervous);
+        assert_eq!(Tone::parse("Professional"), Tone::Confident);
+        assert_eq!(Tone::parse("calm_confident"), Tone::Calm);
+        assert_eq!(Tone::parse("mysterious"), Tone::Neutral);
+
+        let tone: Tone = serde_json::from_str("\"Excited\"").unwrap();
+        assert_eq!(tone, Tone::Excited);
+        assert_eq!(serde_json::to_string(&Tone::Rushed).unwrap(), "\"Rushed\"");
+        for tone in Tone::ALL {
+            assert_eq!(Tone::parse(tone.as_str()), tone);
+        }
+    }
+
+    #[test]
+    fn test_compound_tags_average_their_words() {
+        let affect = Affect::of_tag("calm_confident").unwrap();
+        assert!((affect.valence - 0.6).abs() < 1e-6);
+        assert!(affect.arousal.abs() < 1e-6);
+        assert!(Affect::of_tag("mysterious").is_none());
+    }
+
+    #[test]
+    fn test_compare_intended_and_expressed() {
+        let close = EmotionMatch::compare("calm_confident", Tone::Confident).unwrap();
+        assert!(close.matched);
+
+        let far = EmotionMatch::compare("calm_confident", Tone::Nervous).unwrap();
+        assert!(!far.matched);
+        assert!(far.distance > close.distance);
+
+        assert!(EmotionMatch::compare("", Tone::Calm).is_none());
+    }
+
+    #[test]
+    fn test_composed_tones() {
+        let composed: Vec<Tone> = Tone::ALL.into_iter().filter(Tone::is_composed).collect();
+        assert!(composed.contains(&Tone::Calm));
+        assert!(composed.contains(&Tone::Clear));
+        assert!(!composed.contains(&Tone::Excited));
+        assert!(!composed.contains(&Tone::Nervous));
+    }
+}
@@ -0,0 +1,390 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::content::find_script;
+use crate::emotion::{Affect, EmotionMatch, Tone};
+use crate::roles::EmotionTag;
+use crate::scripts::Script;
+
+/// Valence change per week below which a trend counts as steady
+const STEADY_SLOPE_PER_WEEK: f32 = 0.05;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrajectoryConfig {
+    /// Tags in the trailing moving average
+    pub moving_average_window: usize,
+    /// "Recent" means the last this many days; earlier tags are the baseline
+    pub compare_days: i64,
+    /// How many scenes to list in `most_nervous`
+    pub most_nervous: usize,
+}
+
+impl Default for TrajectoryConfig {
+    fn default() -> Self {
+        Self {
+            moving_average_window: 3,
+            compare_days: 30,
+            most_nervous: 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trend {
+    /// Valence rising: more at ease over time
+    Improving,
+    Steady,
+    Declining,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmotionPoint {
+    pub role_id: String,
+    pub scene_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub tone: Tone,
+    pub confidence: f32,
+    pub affect: Affect,
+    /// Trailing mean over the last `moving_average_window` points
+    pub moving_average: Affect,
+    /// Against the scene's `emotion_wave`, when it declares one
+    pub intended: Option<EmotionMatch>,
+}
+
+/// All tags of one scene
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneEmotion {
+    pub scene_id: String,
+    pub samples: usize,
+    pub mean: Affect,
+    /// `(arousal - valence) / 2`: 1 is tense and unhappy, -1 at ease
+    pub nervousness: f32,
+    pub intended_tag: Option<String>,
+    /// Share of tags close to the intended emotion
+    pub match_rate: Option<f32>,
+}
+
+/// A scene's baseline against the last `compare_days` days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SceneChange {
+    pub scene_id: String,
+    pub earlier: Affect,
+    pub recent: Affect,
+    pub calmer: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoleTrend {
+    pub role_id: String,
+    pub samples: usize,
+    pub mean: Affect,
+    pub trend: Trend,
+    pub valence_slope_per_week: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmotionTrajectory {
+    /// Oldest first, across every role in the input
+    pub points: Vec<EmotionPoint>,
+    pub roles: Vec<RoleTrend>,
+    pub trend: Option<Trend>,
+    pub valence_slope_per_week: f32,
+    /// Most nervous first
+    pub scenes: Vec<SceneEmotion>,
+    pub most_nervous: Vec<String>,
+    pub changes: Vec<SceneChange>,
+}
+
+/// Analyse stored `(role_id, tag)` pairs
+///
+/// Scene ids are looked up in `scripts` as stored and with `-` read as `_`,
+/// so tags recorded under scene file names still find their `emotion_wave`.
+pub fn emotion_trajectory(
+    history: &[(String, EmotionTag)],
+    scripts: &HashMap<String, Script>,
+    config: &TrajectoryConfig,
+    now: DateTime<Utc>,
+) -> EmotionTrajectory {
+    let mut history: Vec<&(String, EmotionTag)> = history.iter().collect();
+    history.sort_by_key(|(_, tag)| tag.timestamp);
+    if history.is_empty() {
+        return EmotionTrajectory::default();
+    }
+
+    let window = config.moving_average_window.max(1);
+    let mut points: Vec<EmotionPoint> = Vec::with_capacity(history.len());
+    for (i, (role_id, tag)) in history.iter().enumerate() {
+        let from = (i + 1).saturating_sub(window);
+        let moving_average = mean_affect(history[from..=i].iter().map(|(_, t)| t));
+        points.push(EmotionPoint {
+            role_id: role_id.clone(),
+            scene_id: tag.scene_id.clone(),
+            timestamp: tag.timestamp,
+            tone: tag.tone,
+            confidence: tag.confidence,
+            affect: tag.tone.affect(),
+            moving_average,
+            intended: intended_tag(scripts, &tag.scene_id).and_then(|wave| tag.compare_to(wave)),
+        });
+    }
+
+    let mut by_role: BTreeMap<&str, Vec<&EmotionTag>> = BTreeMap::new();
+    let mut by_scene: BTreeMap<&str, Vec<&EmotionTag>> = BTreeMap::new();
+    for (role_id, tag) in &history {
+        by_role.entry(role_id).or_default().push(tag);
+        by_scene.entry(&tag.scene_id).or_default().push(tag);
+    }
+
+    let roles = by_role
+        .iter()
+        .map(|(role_id, tags)| {
+            let slope = valence_slope_per_week(tags);
+            RoleTrend {
+                role_id: role_id.to_string(),
+                samples: tags.len(),
+                mean: mean_affect(tags.iter().copied()),
+                trend: trend(slope),
+                valence_slope_per_week: slope,
+            }
+        })
+        .collect();
+
+    let mut scenes: Vec<SceneEmotion> = by_scene
+        .iter()
+        .map(|(scene_id, tags)| {
+            let mean = mean_affect(tags.iter().copied());
+            let intended = intended_tag(scripts, scene_id);
+            let match_rate = intended.and_then(|wave| {
+                let matches: Vec<bool> = tags
+                    .iter()
+                    .filter_map(|t| t.compare_to(wave))
+                    .map(|m| m.matched)
+                    .collect();
+                (!matches.is_empty())
+                    .then(|| matches.iter().filter(|m| **m).count() as f32 / matches.len() as f32)
+            });
+            SceneEmotion {
+                scene_id: scene_id.to_string(),
+                samples: tags.len(),
+                nervousness: nervousness(&mean),
+                mean,
+                intended_tag: intended.map(str::to_string),
+                match_rate,
+            }
+        })
+        .collect();
+    scenes.sort_by(|a, b| {
+        b.nervousness
+            .partial_cmp(&a.nervousness)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let most_nervous = scenes
+        .iter()
+        .filter(|s| s.nervousness > 0.0)
+        .take(config.most_nervous)
+        .map(|s| s.scene_id.clone())
+        .collect();
+
+    let cutoff = now - Duration::days(config.compare_days.max(1));
+    let changes = by_scene
+        .iter()
+        .filter_map(|(scene_id, tags)| {
+            let (recent, earlier): (Vec<&EmotionTag>, Vec<&EmotionTag>) =
+                tags.iter().partition(|t| t.timestamp >= cutoff);
+            if recent.is_empty() || earlier.is_empty() {
+                return None;
+            }
+            let earlier = mean_affect(earlier.into_iter());
+            let recent = mean_affect(recent.into_iter());
+            Some(SceneChange {
+                scene_id: scene_id.to_string(),
+                calmer: nervousness(&recent) < nervousness(&earlier),
+                earlier,
+                recent,
+            })
+        })
+        .collect();
+
+    let all: Vec<&EmotionTag> = history.iter().map(|(_, tag)| tag).collect();
+    let slope = valence_slope_per_week(&all);
+    EmotionTrajectory {
+        points,
+        roles,
+        trend: (all.len() >= 2).then(|| trend(slope)),
+        valence_slope_per_week: slope,
+        scenes,
+        most_nervous,
+        changes,
+    }
+}
+
+fn intended_tag<'a>(scripts: &'a HashMap<String, Script>, scene_id: &str) -> Option<&'a str> {
+    find_script(scripts, scene_id).and_then(|s| s.emotion_wave.as_deref())
+}
+
+fn nervousness(affect: &Affect) -> f32 {
+    (affect.arousal - affect.valence) / 2.0
+}
+
+/// Confidence-weighted mean; tags without confidence count fully
+fn mean_affect<'a>(tags: impl Iterator<Item = &'a EmotionTag>) -> Affect {
+    let (mut valence, mut arousal, mut weight) = (0.0, 0.0, 0.0);
+    for tag in tags {
+        let w = if tag.confidence > 0.0 {
+            tag.confidence
+        } else {
+            1.0
+        };
+        let affect = tag.tone.affect();
+        valence += affect.valence * w;
+        arousal += affect.arousal * w;
+        weight += w;
+    }
+    if weight == 0.0 {
+        return Affect {
+            valence: 0.0,
+            arousal: 0.0,
+        };
+    }
+    Affect {
+        valence: valence / weight,
+        arousal: arousal / weight,
+    }
+}
+
+/// Least-squares slope of valence over time
+fn valence_slope_per_week(tags: &[&EmotionTag]) -> f32 {
+    if tags.len() < 2 {
+        return 0.0;
+    }
+    let origin = tags[0].timestamp;
+    let xs: Vec<f32> = tags
+        .iter()
+        .map(|t| (t.timestamp - origin).num_seconds() as f32 / (7.0 * 86_400.0))
+        .collect();
+    let ys: Vec<f32> = tags.iter().map(|t| t.tone.valence()).collect();
+    let n = xs.len() as f32;
+    let mean_x = xs.iter().sum::<f32>() / n;
+    let mean_y = ys.iter().sum::<f32>() / n;
+    let cov: f32 = xs
+        .iter()
+        .zip(&ys)
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum();
+    let var: f32 = xs.iter().map(|x| (x - mean_x).powi(2)).sum();
+    if var == 0.0 {
+        0.0
+    } else {
+        cov / var
+    }
+}
+
+fn trend(slope_per_week: f32) -> Trend {
+    if slope_per_week > STEADY_SLOPE_PER_WEEK {
+        Trend::Improving
+    } else if slope_per_week < -STEADY_SLOPE_PER_WEEK {
+        Trend::Declining
+    } else {
+        Trend::Steady
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(scene: &str, tone: Tone, days_ago: i64, now: DateTime<Utc>) -> EmotionTag {
+        EmotionTag {
+            scene_id: scene.to_string(),
+            tone,
+            confidence: 0.9,
+            timestamp: now - Duration::days(days_ago),
+        }
+    }
+
+    fn scripts() -> HashMap<String, Script> {
+        let script: Script = serde_yaml::from_str(
+            "id: qa_standup_03\ntitle: Stand-up\nemotion_wave: calm_confident\nsteps:\n  - type: listen\n    prompt: a\n",
+        )
+        .unwrap();
+        HashMap::from([(script.id.clone(), script)])
+    }
+
+    #[test]
+    fn test_calmer_in_standups_than_a_month_ago() {
+        let now = Utc::now();
+        let history = vec![
+            (
+                "qa".to_string(),
+                tag("qa-standup-03", Tone::Nervous, 45, now),
+            ),
+            (
+                "qa".to_string(),
+                tag("qa-standup-03", Tone::Uncertain, 40, now),
+            ),
+            ("qa".to_string(), tag("qa-review-04", Tone::Rushed, 20, now)),
+            ("qa".to_string(), tag("qa-standup-03", Tone::Calm, 5, now)),
+            (
+                "qa".to_string(),
+                tag("qa-standup-03", Tone::Confident, 1, now),
+            ),
+        ];
+        let report = emotion_trajectory(&history, &scripts(), &TrajectoryConfig::default(), now);
+
+        assert_eq!(report.points.len(), 5);
+        assert_eq!(report.trend, Some(Trend::Improving));
+        assert_eq!(report.roles[0].trend, Trend::Improving);
+
+        let standup = &report.changes[0];
+        assert_eq!(standup.scene_id, "qa-standup-03");
+        assert!(standup.calmer);
+        assert_eq!(report.changes.len(), 1);
+
+        // Scene file names resolve to the script's emotion_wave
+        let scene = report
+            .scenes
+            .iter()
+            .find(|s| s.scene_id == "qa-standup-03")
+            .unwrap();
+        assert_eq!(scene.intended_tag.as_deref(), Some("calm_confident"));
+        assert_eq!(scene.match_rate, Some(0.5));
+        assert!(report.points[4].intended.as_ref().unwrap().matched);
+
+        assert_eq!(report.most_nervous[0], "qa-review-04");
+    }
+
+    #[test]
+    fn test_moving_average_window() {
+        let now = Utc::now();
+        let history: Vec<(String, EmotionTag)> = [Tone::Nervous, Tone::Calm, Tone::Calm]
+            .into_iter()
+            .enumerate()
+            .map(|(i, tone)| ("r".to_string(), tag("s", tone, 10 - i as i64, now)))
+            .collect();
+        let config = TrajectoryConfig {
+            moving_average_window: 2,
+            ..Default::default()
+        };
+        let report = emotion_trajectory(&history, &HashMap::new(), &config, now);
+
+        assert_eq!(report.points[0].moving_average, Tone::Nervous.affect());
+        assert!((report.points[1].moving_average.valence - (-0.6 + 0.5) / 2.0).abs() < 1e-6);
+        assert_eq!(report.points[2].moving_average, Tone::Calm.affect());
+        assert!(report.points[0].intended.is_none());
+    }
+
+    #[test]
+    fn test_empty_history() {
+        let report = emotion_trajectory(
+            &[],
+            &HashMap::new(),
+            &TrajectoryConfig::default(),
+            Utc::now(),
+        );
+        assert!(report.points.is_empty());
+        assert_eq!(report.trend, None);
+    }
+}
@@ -0,0 +1,191 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::scheduler::Grade;
+
+/// Fewer reviews than this say more about noise than about the learner
+pub const MIN_FIT_SAMPLES: usize = 20;
+
+/// One graded review as recorded in `review_log`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLogEntry {
+    pub phrase: String,
+    pub reviewed_at: DateTime<Utc>,
+    pub grade: Grade,
+    pub recalled: bool,
+    /// Seconds since the phrase was last seen
+    pub elapsed_secs: i64,
+    /// Wave right after the previous review, before any decay
+    pub wave_before: f32,
+    pub decay_alpha: f32,
+}
+
+impl ReviewLogEntry {
+    /// Recall probability the wave model predicts for this review
+    pub fn predicted(&self, time_constant_secs: f32) -> f32 {
+        let decay = self
+            .decay_alpha
+            .powf(self.elapsed_secs.max(0) as f32 / time_constant_secs);
+        (self.wave_before * decay).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationBucket {
+    /// Predicted recall range, e.g. 0.2..0.4
+    pub from: f32,
+    pub to: f32,
+    pub reviews: usize,
+    pub mean_predicted: f32,
+    pub observed: f32,
+}
+
+/// Result of fitting the wave time constant to a learner's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FitReport {
+    pub samples: usize,
+    pub time_constant_secs: f32,
+    pub previous_time_constant_secs: f32,
+    /// Mean log loss with the previous and the fitted constant
+    pub log_loss_before: f32,
+    pub log_loss_after: f32,
+    /// Predicted vs observed recall with the fitted constant
+    pub calibration: Vec<CalibrationBucket>,
+}
+
+/// Fit the personal time constant `T` of `wave * alpha^(dt/T)` by
+/// minimising log loss over a log-spaced grid (1 minute to 60 days)
+///
+/// `alpha` and `T` only enter the model as `ln(alpha) / T`, so the per-script
+/// `decay_alpha` is kept as authored (it encodes relative difficulty) and
+/// only `T` is personalised. Returns `None` with fewer than
+/// [`MIN_FIT_SAMPLES`] reviews.
+pub fn fit_time_constant(entries: &[ReviewLogEntry], current_secs: f32) -> Option<FitReport> {
+    if entries.len() < MIN_FIT_SAMPLES {
+        return None;
+    }
+
+    let (lo, hi) = (60f32.ln(), (60.0 * 86_400.0f32).ln());
+    let steps = 240;
+    let best = (0..=steps)
+        .map(|i| (lo + (hi - lo) * i as f32 / steps as f32).exp())
+        .map(|t| (t, log_loss(entries, t)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+
+    Some(FitReport {
+        samples: entries.len(),
+        time_constant_secs: best.0,
+        previous_time_constant_secs: current_secs,
+        log_loss_before: log_loss(entries, current_secs),
+        log_loss_after: best.1,
+        calibration: calibration(entries, best.0, 5),
+    })
+}
+
+fn log_loss(entries: &[ReviewLogEntry], time_constant_secs: f32) -> f32 {
+    let total: f32 = entries
+        .iter()
+        .map(|e| {
+            let p = e.predicted(time_constant_secs).clamp(0.01, 0.99);
+            if e.recalled {
+                -p.ln()
+            } else {
+                -(1.0 - p).ln()
+            }
+        })
+        .sum();
+    total / entries.len().max(1) as f32
+}
+
+/// Group reviews into equal-width predicted-recall buckets
+pub fn calibration(
+    entries: &[ReviewLogEntry],
+    time_constant_secs: f32,
+    buckets: usize,
+) -> Vec<CalibrationBucket> {
+    (0..buckets)
+        .filter_map(|b| {
+            let from = b as f32 / buckets as f32;
+            let to = (b + 1) as f32 / buckets as f32;
+            let in_bucket: Vec<(f32, bool)> = entries
+                .iter()
+                .map(|e| (e.predicted(time_constant_secs), e.recalled))
+                .filter(|(p, _)| *p >= from && (*p < to || (b + 1 == buckets && *p <= to)))
+                .collect();
+            if in_bucket.is_empty() {
+                return None;
+            }
+            let n = in_bucket.len() as f32;
+            Some(CalibrationBucket {
+                from,
+                to,
+                reviews: in_bucket.len(),
+                mean_predicted: in_bucket.iter().map(|(p, _)| p).sum::<f32>() / n,
+                observed: in_bucket.iter().filter(|(_, r)| *r).count() as f32 / n,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::retention::DEFAULT_TIME_CONSTANT_SECS;
+
+    /// Reviews of a learner who truly forgets with time constant `true_t`
+    fn history(true_t: f32, n: usize) -> Vec<ReviewLogEntry> {
+        let mut seed: u64 = 17;
+        (0..n)
+            .map(|i| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                let roll = (seed >> 40) as f32 / (1u64 << 24) as f32;
+                let elapsed_secs = 600 * (1 + (i as i64 % 48));
+                let p = 0.8f32.powf(elapsed_secs as f32 / true_t);
+                ReviewLogEntry {
+                    phrase: format!("p{}", i % 7),
+                    reviewed_at: Utc::now(),
+                    grade: if roll < p { Grade::Good } else { Grade::Again },
+                    recalled: roll < p,
+                    elapsed_secs,
+                    wave_before: 1.0,
+                    decay_alpha: 0.8,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_needs_enough_samples() {
+        assert!(fit_time_constant(&history(3600.0, MIN_FIT_SAMPLES - 1), 600.0).is_none());
+    }
+
+    #[test]
+    fn test_recovers_true_time_constant() {
+        let entries = history(6.0 * 3600.0, 600);
+        let report = fit_time_constant(&entries, DEFAULT_TIME_CONSTANT_SECS).unwrap();
+
+        let ratio = report.time_constant_secs / (6.0 * 3600.0);
+        assert!(
+            ratio > 0.7 && ratio < 1.4,
+            "fitted {}",
+            report.time_constant_secs
+        );
+        assert!(report.log_loss_after < report.log_loss_before);
+        assert_eq!(report.samples, 600);
+    }
+
+    #[test]
+    fn test_calibration_tracks_observed_recall() {
+        let entries = history(6.0 * 3600.0, 600);
+        let report = fit_time_constant(&entries, DEFAULT_TIME_CONSTANT_SECS).unwrap();
+
+        assert!(!report.calibration.is_empty());
+        for bucket in report.calibration.iter().filter(|b| b.reviews >= 50) {
+            assert!((bucket.mean_predicted - bucket.observed).abs() < 0.15);
+        }
+        let total: usize = report.calibration.iter().map(|b| b.reviews).sum();
+        assert_eq!(total, 600);
+    }
+}
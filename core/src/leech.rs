@@ -0,0 +1,226 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::retention::MemoryLink;
+use crate::scripts::{Script, StepType};
+
+/// When a phrase counts as a leech
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeechConfig {
+    /// Failures in a row that make a leech regardless of history
+    pub consecutive_fails: u32,
+    /// Minimum total failures before `fail_ratio` applies
+    pub min_fails: u32,
+    /// Share of failed reviews (0..1)
+    pub fail_ratio: f32,
+}
+
+impl Default for LeechConfig {
+    fn default() -> Self {
+        Self {
+            consecutive_fails: 3,
+            min_fails: 4,
+            fail_ratio: 0.6,
+        }
+    }
+}
+
+impl LeechConfig {
+    pub fn is_leech(&self, link: &MemoryLink) -> bool {
+        if link.consecutive_fails >= self.consecutive_fails {
+            return true;
+        }
+        let total = link.fail_count + link.success_count + link.use_in_wild_count;
+        link.fail_count >= self.min_fails
+            && link.fail_count as f32 >= self.fail_ratio * total as f32
+    }
+}
+
+/// What to practise instead of drilling a leech again
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Remediation {
+    /// Redo the contrast step of the scenario the phrase came from
+    ContrastStep {
+        script_id: String,
+        step_index: usize,
+        prompt: String,
+    },
+    /// Step back to an easier scene of the same role
+    EasierScene { script_id: String, title: String },
+    /// No better option: replay the originating scenario
+    ReplayScenario { script_id: String, title: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeechReport {
+    pub phrase: String,
+    pub script_id: Option<String>,
+    pub fail_count: u32,
+    pub success_count: u32,
+    pub consecutive_fails: u32,
+    pub leech_since: Option<DateTime<Utc>>,
+    pub remediation: Option<Remediation>,
+}
+
+impl LeechReport {
+    pub fn new(link: &MemoryLink, scripts: &HashMap<String, Script>) -> Self {
+        Self {
+            phrase: link.phrase.clone(),
+            script_id: link.script_id.clone(),
+            fail_count: link.fail_count,
+            success_count: link.success_count,
+            consecutive_fails: link.consecutive_fails,
+            leech_since: link.leech_since,
+            remediation: suggest_remediation(link, scripts),
+        }
+    }
+}
+
+/// Pick a remediation for a leech from the scenario it was harvested from
+///
+/// Prefers the contrast step closest to the phrase's step, then an easier
+/// scene of the same role, then replaying the scenario itself.
+pub fn suggest_remediation(
+    link: &MemoryLink,
+    scripts: &HashMap<String, Script>,
+) -> Option<Remediation> {
+    let script = scripts.get(link.script_id.as_deref()?)?;
+    let origin = link.step_index.unwrap_or(0) as usize;
+
+    let contrast = script
+        .steps
+        .iter()
+        .enumerate()
+        .filter(|(_, step)| matches!(step.r#type, StepType::Contrast))
+        .min_by_key(|(i, _)| i.abs_diff(origin));
+    if let Some((step_index, step)) = contrast {
+        return Some(Remediation::ContrastStep {
+            script_id: script.id.clone(),
+            step_index,
+            prompt: step.prompt.clone(),
+        });
+    }
+
+    let rank = |s: &Script| s.difficulty.unwrap_or_default() as u8;
+    let easier = script.role_id.as_ref().and_then(|role_id| {
+        scripts
+            .values()
+            .filter(|s| s.role_id.as_ref() == Some(role_id) && rank(s) < rank(script))
+            .max_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| b.id.cmp(&a.id)))
+    });
+    if let Some(sibling) = easier {
+        return Some(Remediation::EasierScene {
+            script_id: sibling.id.clone(),
+            title: sibling.title.clone(),
+        });
+    }
+
+    Some(Remediation::ReplayScenario {
+        script_id: script.id.clone(),
+        title: script.title.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link_with(fails: u32, successes: u32, in_a_row: u32) -> MemoryLink {
+        let mut link = MemoryLink::new("could you elaborate".to_string(), 0.8);
+        link.fail_count = fails;
+        link.success_count = successes;
+        link.consecutive_fails = in_a_row;
+        link
+    }
+
+    fn scene(id: &str, difficulty: &str, steps: &str) -> Script {
+        serde_yaml::from_str(&format!(
+            "id: {}\ntitle: \"{}\"\nrole_id: qa\ndifficulty: {}\nsteps:\n{}",
+            id, id, difficulty, steps
+        ))
+        .unwrap()
+    }
+
+    fn scripts(list: Vec<Script>) -> HashMap<String, Script> {
+        list.into_iter().map(|s| (s.id.clone(), s)).collect()
+    }
+
+    const LISTEN: &str = "  - type: listen\n    prompt: a\n";
+
+    #[test]
+    fn test_detection_rules() {
+        let config = LeechConfig::default();
+        assert!(config.is_leech(&link_with(3, 10, 3)));
+        assert!(config.is_leech(&link_with(6, 3, 0)));
+        assert!(!config.is_leech(&link_with(3, 0, 2)));
+        assert!(!config.is_leech(&link_with(5, 10, 1)));
+    }
+
+    #[test]
+    fn test_memory_link_tracks_consecutive_fails() {
+        let mut link = link_with(0, 0, 0);
+        link.weaken(Utc::now());
+        link.weaken(Utc::now());
+        assert_eq!(link.consecutive_fails, 2);
+
+        link.reinforce(Utc::now(), 0.1);
+        assert_eq!(link.consecutive_fails, 0);
+    }
+
+    #[test]
+    fn test_suggests_nearest_contrast_step() {
+        let steps = format!(
+            "{}  - type: contrast\n    prompt: pick one\n{}{}  - type: contrast\n    prompt: closer\n",
+            LISTEN, LISTEN, LISTEN
+        );
+        let all = scripts(vec![scene("review", "intermediate", &steps)]);
+        let mut link = link_with(5, 1, 3);
+        link.script_id = Some("review".to_string());
+        link.step_index = Some(3);
+
+        assert_eq!(
+            suggest_remediation(&link, &all),
+            Some(Remediation::ContrastStep {
+                script_id: "review".to_string(),
+                step_index: 4,
+                prompt: "closer".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_suggests_easier_sibling_scene() {
+        let all = scripts(vec![
+            scene("interview", "advanced", LISTEN),
+            scene("standup", "beginner", LISTEN),
+            scene("review", "intermediate", LISTEN),
+        ]);
+        let mut link = link_with(5, 1, 3);
+        link.script_id = Some("interview".to_string());
+
+        assert_eq!(
+            suggest_remediation(&link, &all),
+            Some(Remediation::EasierScene {
+                script_id: "review".to_string(),
+                title: "review".to_string(),
+            })
+        );
+
+        link.script_id = Some("standup".to_string());
+        assert!(matches!(
+            suggest_remediation(&link, &all),
+            Some(Remediation::ReplayScenario { .. })
+        ));
+    }
+
+    #[test]
+    fn test_no_remediation_without_source() {
+        assert_eq!(
+            suggest_remediation(&link_with(5, 0, 5), &HashMap::new()),
+            None
+        );
+    }
+}
@@ -1,29 +1,90 @@
 #![allow(unexpected_cfgs)]
 
+mod certificates;
+mod coherence;
+mod content;
+mod context;
+mod emotion;
+mod emotion_trends;
+mod fitting;
+mod leech;
+mod migrations;
 pub mod monetization;
+mod notifications;
+mod phrases;
+mod recommend;
 mod retention;
+mod review_queue;
 mod roles;
 mod runner;
+mod scheduler;
 mod scripts;
+mod simulation;
+mod speech;
 mod storage;
+mod streak;
 pub mod telemetry;
+mod transitions;
 pub mod validator;
 
 // Export FFI API
 pub mod api;
 
 // Re-export key types for internal use
+pub use certificates::{
+    CertificateLevel, CertificateScene, CertificateSigner, CompletionRecord, SignedCertificate,
+    CERTIFICATE_ALGORITHM,
+};
+pub use coherence::{CoherenceBreakdown, CoherenceEngine, CoherenceInputs, SceneInput};
+pub use content::{find_script, scripts_for_role, ContentLibrary, ROLE_MANIFEST};
+pub use context::{
+    rank_scripts, ContextTriggers, PlaceCategory, PracticeContext, TimeOfDay, TriggerMatch,
+};
+pub use emotion::{Affect, EmotionMatch, Tone, EMOTION_MATCH_DISTANCE};
+pub use emotion_trends::{
+    emotion_trajectory, EmotionPoint, EmotionTrajectory, RoleTrend, SceneChange, SceneEmotion,
+    TrajectoryConfig, Trend,
+};
+pub use fitting::{fit_time_constant, CalibrationBucket, FitReport, ReviewLogEntry};
+pub use leech::{suggest_remediation, LeechConfig, LeechReport, Remediation};
+pub use migrations::SCHEMA_VERSION;
 pub use monetization::{
     ContentAccess, ContentType, ContentUnlock, Entitlement, EntitlementReason, MonetizationConfig,
     Platform, Purchase, Subscription, SubscriptionStatus, SubscriptionTier,
 };
-pub use retention::{calculate_priority, next_ping_seconds, MemoryLink};
+pub use notifications::{
+    deep_link, plan_notifications, NotificationPlan, PingPayload, PlannerConfig, MAX_HORIZON_HOURS,
+};
+pub use phrases::{extract_phrases, phrase_key, ExtractedPhrase, PhraseSource};
+pub use recommend::{
+    recommend, LearnerState, Recommendation, RecommendationKind, Recommendations, RecommenderConfig,
+};
+pub use retention::{
+    calculate_priority, calculate_priority_with, next_ping_seconds, priority_for_recall,
+    MemoryLink, DEFAULT_TIME_CONSTANT_SECS,
+};
+pub use review_queue::{QueueConfig, QueueItem, ReviewQueue};
 pub use roles::{
-    liminal_transition, Difficulty, EmotionTag, LiminalTransition, Reflection, ResonanceTrace,
-    Role, RoleCoherenceScore, RolePath, RoleProgress,
+    liminal_transition, CoherenceSnapshot, Difficulty, EmotionTag, LiminalTransition, PathProgress,
+    PathRoleProgress, PathRoleStatus, Reflection, ResonanceTrace, Role, RoleCoherenceScore,
+    RolePath, RolePrerequisite, RoleProgress, TRANSITION_COHERENCE,
+};
+pub use runner::{AnswerFeedback, AnswerRecord, RunnerState};
+pub use scheduler::{
+    FsrsParams, FsrsScheduler, FsrsState, Grade, Scheduler, SchedulerKind, WaveScheduler,
+};
+pub use scripts::{Answer, EvalMode, Importance, Rehearsal, Script, Step, StepType};
+pub use simulation::{simulate_retention, DayStats, LearnerProfile, SimulationReport, WavePoint};
+pub use speech::{
+    normalize_words, score_speak_check, score_transcript, SpeakScore, WordResult, WordStatus,
 };
-pub use runner::RunnerState;
-pub use scripts::{Answer, Rehearsal, Script, Step, StepType};
-pub use storage::{Store, TelemetryStats};
+pub use storage::{
+    ImportMode, ImportSummary, Snapshot, Store, TelemetryStats, SNAPSHOT_FORMAT_VERSION,
+};
+pub use streak::{DayActivity, StreakSummary};
 pub use telemetry::{DeviceContext, EventBatch, EventType, TelemetryConfig, TelemetryEvent};
+pub use transitions::{
+    render_template, resolve_transition, CoherenceBand, LocalizedText, RoleTransitions,
+    TransitionStyle, DEFAULT_LANGUAGE,
+};
 pub use validator::{ContentValidator, ValidationReport};
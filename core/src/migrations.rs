@@ -0,0 +1,658 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection, Transaction};
+
+/// One forward step of the schema
+///
+/// Migrations are applied in order, each in its own transaction together
+/// with the `PRAGMA user_version` bump, so a failure leaves the database at
+/// the previous version.
+#[derive(Clone, Copy)]
+pub(crate) struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub apply: fn(&Transaction) -> Result<()>,
+}
+
+/// All migrations, oldest first; versions must be 1, 2, 3, ...
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "baseline schema",
+        apply: baseline_schema,
+    },
+    Migration {
+        version: 2,
+        description: "resumable runner sessions",
+        apply: session_state_columns,
+    },
+    Migration {
+        version: 3,
+        description: "settings and streak freezes",
+        apply: settings_and_streak_freezes,
+    },
+    Migration {
+        version: 4,
+        description: "FSRS memory state",
+        apply: fsrs_memory_state,
+    },
+    Migration {
+        version: 5,
+        description: "phrase sources",
+        apply: phrase_sources,
+    },
+    Migration {
+        version: 6,
+        description: "leech state",
+        apply: leech_state,
+    },
+    Migration {
+        version: 7,
+        description: "review log",
+        apply: review_log,
+    },
+    Migration {
+        version: 8,
+        description: "role unlocks",
+        apply: role_unlocks,
+    },
+    Migration {
+        version: 9,
+        description: "coherence history",
+        apply: coherence_history,
+    },
+    Migration {
+        version: 10,
+        description: "completion certificates",
+        apply: certificates,
+    },
+    Migration {
+        version: 11,
+        description: "device-local signing keys",
+        apply: signing_keys,
+    },
+];
+
+/// Schema version this build reads and writes
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
+pub(crate) fn user_version(conn: &Connection) -> Result<u32> {
+    Ok(conn.query_row("PRAGMA user_version", [], |row| row.get(0))?)
+}
+
+/// Bring the database up to `SCHEMA_VERSION`
+///
+/// Returns the version the database was at before migrating. Refuses to
+/// touch a database written by a newer app version.
+pub(crate) fn migrate(conn: &mut Connection) -> Result<u32> {
+    run(conn, MIGRATIONS)
+}
+
+fn run(conn: &mut Connection, migrations: &[Migration]) -> Result<u32> {
+    let from = user_version(conn)?;
+    let latest = migrations.last().map_or(0, |m| m.version);
+
+    if from > latest {
+        return Err(anyhow!(
+            "Database schema version {} is newer than supported version {}; update the app",
+            from,
+            latest
+        ));
+    }
+
+    for migration in migrations.iter().filter(|m| m.version > from) {
+        let tx = conn.transaction()?;
+        (migration.apply)(&tx).map_err(|e| {
+            anyhow!(
+                "Migration {} ({}) failed: {}",
+                migration.version,
+                migration.description,
+                e
+            )
+        })?;
+        tx.pragma_update(None, "user_version", migration.version)?;
+        tx.commit()?;
+    }
+
+    Ok(from)
+}
+
+fn add_column(tx: &Transaction, table: &str, column: &str, decl: &str) -> Result<()> {
+    // Databases created before versioning may already have the column
+    let mut stmt = tx.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+
+    if !exists {
+        tx.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, decl),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// Tables as shipped before schema versioning; `IF NOT EXISTS` lets
+/// unversioned databases pass through unchanged
+fn baseline_schema(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+    CREATE TABLE IF NOT EXISTS events(
+      id INTEGER PRIMARY KEY,
+      ts TEXT NOT NULL,
+      kind TEXT NOT NULL,
+      payload TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS memory_links(
+      id INTEGER PRIMARY KEY,
+      phrase TEXT UNIQUE NOT NULL,
+      last_seen TEXT NOT NULL,
+      wave REAL NOT NULL,
+      decay_alpha REAL NOT NULL,
+      success_count INTEGER DEFAULT 0,
+      fail_count INTEGER DEFAULT 0,
+      use_in_wild_count INTEGER DEFAULT 0
+    );
+
+    CREATE TABLE IF NOT EXISTS sessions(
+      id INTEGER PRIMARY KEY,
+      script_id TEXT NOT NULL,
+      started_at TEXT NOT NULL,
+      completed_at TEXT,
+      progress REAL DEFAULT 0.0
+    );
+
+    CREATE TABLE IF NOT EXISTS role_progress(
+      id INTEGER PRIMARY KEY,
+      role_id TEXT UNIQUE NOT NULL,
+      current_scene_index INTEGER NOT NULL,
+      total_scenes INTEGER NOT NULL,
+      coherence REAL NOT NULL,
+      last_transition TEXT,
+      consecutive_days INTEGER DEFAULT 0,
+      created_at TEXT NOT NULL,
+      updated_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS emotion_tags(
+      id INTEGER PRIMARY KEY,
+      role_id TEXT NOT NULL,
+      scene_id TEXT NOT NULL,
+      tone TEXT NOT NULL,
+      confidence REAL NOT NULL,
+      timestamp TEXT NOT NULL,
+      FOREIGN KEY(role_id) REFERENCES role_progress(role_id)
+    );
+
+    CREATE TABLE IF NOT EXISTS resonance_traces(
+      id TEXT PRIMARY KEY,
+      role_id TEXT NOT NULL,
+      scene_id TEXT NOT NULL,
+      message TEXT NOT NULL,
+      created_at TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS reflections(
+      id INTEGER PRIMARY KEY,
+      trace_id TEXT NOT NULL,
+      message TEXT NOT NULL,
+      created_at TEXT NOT NULL,
+      FOREIGN KEY(trace_id) REFERENCES resonance_traces(id)
+    );
+
+    CREATE TABLE IF NOT EXISTS telemetry_events(
+      id TEXT PRIMARY KEY,
+      event_type TEXT NOT NULL,
+      timestamp TEXT NOT NULL,
+      session_id TEXT,
+      user_id TEXT,
+      properties TEXT NOT NULL,
+      context TEXT NOT NULL,
+      status TEXT NOT NULL DEFAULT 'pending'
+    );
+
+    CREATE TABLE IF NOT EXISTS telemetry_batches(
+      batch_id TEXT PRIMARY KEY,
+      created_at TEXT NOT NULL,
+      sent_at TEXT,
+      size_bytes INTEGER NOT NULL,
+      event_count INTEGER NOT NULL,
+      status TEXT NOT NULL DEFAULT 'pending'
+    );
+
+    CREATE TABLE IF NOT EXISTS subscriptions(
+      id TEXT PRIMARY KEY,
+      user_id TEXT NOT NULL,
+      tier TEXT NOT NULL,
+      status TEXT NOT NULL,
+      started_at TEXT NOT NULL,
+      expires_at TEXT,
+      cancelled_at TEXT,
+      platform TEXT NOT NULL,
+      transaction_id TEXT
+    );
+
+    CREATE TABLE IF NOT EXISTS purchases(
+      id TEXT PRIMARY KEY,
+      user_id TEXT NOT NULL,
+      product_id TEXT NOT NULL,
+      platform TEXT NOT NULL,
+      transaction_id TEXT NOT NULL UNIQUE,
+      purchased_at TEXT NOT NULL,
+      price_cents INTEGER NOT NULL,
+      currency TEXT NOT NULL,
+      verified INTEGER NOT NULL DEFAULT 0,
+      metadata TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS content_unlocks(
+      id TEXT PRIMARY KEY,
+      user_id TEXT NOT NULL,
+      content_type TEXT NOT NULL,
+      content_id TEXT NOT NULL,
+      unlocked_at TEXT NOT NULL,
+      purchase_id TEXT,
+      FOREIGN KEY(purchase_id) REFERENCES purchases(id)
+    );
+
+    CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
+    CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind);
+    CREATE INDEX IF NOT EXISTS idx_memory_wave ON memory_links(wave);
+    CREATE INDEX IF NOT EXISTS idx_emotion_tags_role ON emotion_tags(role_id);
+    CREATE INDEX IF NOT EXISTS idx_resonance_traces_role ON resonance_traces(role_id);
+    CREATE INDEX IF NOT EXISTS idx_reflections_trace ON reflections(trace_id);
+    CREATE INDEX IF NOT EXISTS idx_telemetry_status ON telemetry_events(status);
+    CREATE INDEX IF NOT EXISTS idx_telemetry_timestamp ON telemetry_events(timestamp);
+    CREATE INDEX IF NOT EXISTS idx_batch_status ON telemetry_batches(status);
+    CREATE INDEX IF NOT EXISTS idx_subscriptions_user ON subscriptions(user_id);
+    CREATE INDEX IF NOT EXISTS idx_purchases_user ON purchases(user_id);
+    CREATE INDEX IF NOT EXISTS idx_content_unlocks_user ON content_unlocks(user_id);
+    CREATE INDEX IF NOT EXISTS idx_content_unlocks_content ON content_unlocks(content_type, content_id);
+    "#,
+    )?;
+    Ok(())
+}
+
+fn session_state_columns(tx: &Transaction) -> Result<()> {
+    add_column(tx, "sessions", "current_index", "INTEGER DEFAULT 0")?;
+    add_column(tx, "sessions", "state", "TEXT")?;
+    add_column(tx, "sessions", "updated_at", "TEXT")?;
+    Ok(())
+}
+
+fn settings_and_streak_freezes(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        r#"
+    CREATE TABLE IF NOT EXISTS settings(
+      key TEXT PRIMARY KEY,
+      value TEXT NOT NULL
+    );
+
+    CREATE TABLE IF NOT EXISTS streak_freezes(
+      day TEXT PRIMARY KEY,
+      applied_at TEXT NOT NULL
+    );
+    "#,
+    )?;
+    Ok(())
+}
+
+fn fsrs_memory_state(tx: &Transaction) -> Result<()> {
+    add_column(tx, "memory_links", "stability", "REAL")?;
+    add_column(tx, "memory_links", "difficulty", "REAL")?;
+    add_column(tx, "memory_links", "due_at", "TEXT")?;
+    add_column(tx, "memory_links", "reps", "INTEGER DEFAULT 0")?;
+    add_column(tx, "memory_links", "lapses", "INTEGER DEFAULT 0")?;
+
+    // Seed a memory state for every phrase already practised with the wave
+    // model. The formula and FSRS weights are frozen as of this version so
+    // later scheduler changes do not alter the migration.
+    let links = {
+        let mut stmt = tx.prepare(
+            "SELECT id, last_seen, wave, success_count, fail_count, use_in_wild_count
+             FROM memory_links",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f32>(2)?,
+                row.get::<_, u32>(3)?,
+                row.get::<_, u32>(4)?,
+                row.get::<_, u32>(5)?,
+            ))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+
+    for (id, last_seen, wave, success_count, fail_count, use_in_wild_count) in links {
+        // Rows with an unreadable date keep no state and are seeded on review
+        let Ok(last_seen) = DateTime::parse_from_rfc3339(&last_seen) else {
+            continue;
+        };
+        let successes = success_count + use_in_wild_count;
+        let fail_rate = (fail_count as f32 + 1.0) / ((successes + fail_count) as f32 + 2.0);
+        let stability =
+            (3.7145 * 2f32.powi(successes.min(8) as i32) * wave.clamp(0.1, 1.0)).min(365.0);
+        // At 90% desired retention the FSRS interval equals the stability
+        let interval_days = stability.clamp(1.0 / 24.0, 365.0);
+        let due = last_seen.with_timezone(&Utc)
+            + Duration::seconds((interval_days * 86_400.0).round() as i64);
+
+        tx.execute(
+            "UPDATE memory_links SET stability = ?1, difficulty = ?2, due_at = ?3, reps = ?4, lapses = ?5
+             WHERE id = ?6",
+            params![
+                stability,
+                (1.0 + 9.0 * fail_rate).clamp(1.0, 10.0),
+                due.to_rfc3339(),
+                success_count + fail_count,
+                fail_count,
+                id
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+fn phrase_sources(tx: &Transaction) -> Result<()> {
+    add_column(tx, "memory_links", "script_id", "TEXT")?;
+    add_column(tx, "memory_links", "step_index", "INTEGER")?;
+    tx.execute_batch("CREATE INDEX IF NOT EXISTS idx_memory_script ON memory_links(script_id);")?;
+    Ok(())
+}
+
+fn leech_state(tx: &Transaction) -> Result<()> {
+    add_column(tx, "memory_links", "consecutive_fails", "INTEGER DEFAULT 0")?;
+    add_column(tx, "memory_links", "leech_since", "TEXT")?;
+
+    // Flag existing leeches with the default thresholds (4+ fails, 60%+ failed)
+    tx.execute(
+        "UPDATE memory_links SET leech_since = last_seen
+         WHERE fail_count >= 4
+           AND fail_count >= 0.6 * (fail_count + success_count + use_in_wild_count)",
+        [],
+    )?;
+    Ok(())
+}
+
+fn review_log(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS review_log (
+            id INTEGER PRIMARY KEY,
+            phrase TEXT NOT NULL,
+            reviewed_at TEXT NOT NULL,
+            grade TEXT NOT NULL,
+            recalled INTEGER NOT NULL,
+            elapsed_secs INTEGER NOT NULL,
+            wave_before REAL NOT NULL,
+            decay_alpha REAL NOT NULL,
+            scheduler TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_review_log_phrase ON review_log(phrase);
+        CREATE INDEX IF NOT EXISTS idx_review_log_time ON review_log(reviewed_at);",
+    )?;
+    Ok(())
+}
+
+fn role_unlocks(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS role_unlocks (
+            role_id TEXT PRIMARY KEY,
+            path_id TEXT NOT NULL,
+            unlocked_at TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+fn coherence_history(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS coherence_history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            role_id TEXT NOT NULL,
+            scene_index INTEGER NOT NULL,
+            coherence REAL NOT NULL,
+            recorded_at TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_coherence_history_role ON coherence_history(role_id);",
+    )?;
+    Ok(())
+}
+
+fn certificates(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS certificates (
+            id TEXT PRIMARY KEY,
+            role_id TEXT NOT NULL,
+            issued_at TEXT NOT NULL,
+            certificate TEXT NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_certificates_role ON certificates(role_id);",
+    )?;
+    Ok(())
+}
+
+/// Signing seeds move out of `settings`, which backups include
+fn signing_keys(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS signing_keys (
+            name TEXT PRIMARY KEY,
+            seed TEXT NOT NULL
+        );
+        INSERT OR IGNORE INTO signing_keys(name, seed)
+            SELECT 'certificate', value FROM settings WHERE key = 'certificate_signing_key';
+        DELETE FROM settings WHERE key = 'certificate_signing_key';",
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(conn: &Connection, table: &str) -> Vec<String> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .unwrap();
+        stmt.query_map([], |row| row.get::<_, String>(1))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_versions_are_sequential() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1, "{}", migration.description);
+        }
+    }
+
+    #[test]
+    fn test_fresh_database_reaches_latest() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), 0);
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(columns(&conn, "sessions").contains(&"state".to_string()));
+        assert!(!columns(&conn, "settings").is_empty());
+
+        // Running again is a no-op
+        assert_eq!(migrate(&mut conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_unversioned_database_is_upgraded_in_place() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE sessions(id INTEGER PRIMARY KEY, script_id TEXT NOT NULL, started_at TEXT NOT NULL, completed_at TEXT, progress REAL DEFAULT 0.0);
+             INSERT INTO sessions(script_id, started_at) VALUES('cafe-to-go-01', '2025-03-01T08:00:00Z');",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(columns(&conn, "sessions").contains(&"current_index".to_string()));
+        let script_id: String = conn
+            .query_row("SELECT script_id FROM sessions", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(script_id, "cafe-to-go-01");
+    }
+
+    #[test]
+    fn test_upgrade_from_each_older_version() {
+        for start in 1..SCHEMA_VERSION {
+            let mut conn = Connection::open_in_memory().unwrap();
+            run(&mut conn, &MIGRATIONS[..start as usize]).unwrap();
+            assert_eq!(user_version(&conn).unwrap(), start);
+
+            assert_eq!(migrate(&mut conn).unwrap(), start);
+            assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+        }
+    }
+
+    #[test]
+    fn test_memory_links_are_seeded_with_fsrs_state() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, &MIGRATIONS[..3]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, success_count, fail_count)
+             VALUES('to go, please', '2025-03-01T08:00:00+00:00', 0.9, 0.8, 4, 0),
+                   ('could I get', '2025-03-01T08:00:00+00:00', 0.3, 0.8, 1, 3);",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let seeded: Vec<(String, f32, f32, u32)> = conn
+            .prepare("SELECT phrase, stability, difficulty, lapses FROM memory_links ORDER BY id")
+            .unwrap()
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        assert_eq!(seeded.len(), 2);
+        assert!(seeded[0].1 > seeded[1].1);
+        assert!(seeded[0].2 < seeded[1].2);
+        assert_eq!(seeded[1].3, 3);
+    }
+
+    #[test]
+    fn test_unreadable_last_seen_does_not_block_fsrs_seeding() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, &MIGRATIONS[..3]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, success_count, fail_count)
+             VALUES('broken', 'yesterday', 0.9, 0.8, 4, 0),
+                   ('fine', '2025-03-01T08:00:00+00:00', 0.9, 0.8, 4, 0);",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        let seeded: Vec<(String, Option<f32>)> = conn
+            .prepare("SELECT phrase, stability FROM memory_links ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(seeded[0], ("broken".to_string(), None));
+        assert!(seeded[1].1.is_some());
+    }
+
+    #[test]
+    fn test_existing_leeches_are_flagged() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, &MIGRATIONS[..5]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, success_count, fail_count)
+             VALUES('leech', '2025-03-01T08:00:00+00:00', 0.2, 0.8, 2, 5),
+                   ('fine', '2025-03-01T08:00:00+00:00', 0.8, 0.8, 10, 4);",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let flagged: Vec<String> = conn
+            .prepare("SELECT phrase FROM memory_links WHERE leech_since IS NOT NULL")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(flagged, vec!["leech"]);
+    }
+
+    #[test]
+    fn test_signing_seed_leaves_settings() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn, &MIGRATIONS[..10]).unwrap();
+        conn.execute_batch(
+            "INSERT INTO settings(key, value) VALUES('certificate_signing_key', 'abcd'), ('utc_offset_minutes', '60');",
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        let seed: String = conn
+            .query_row(
+                "SELECT seed FROM signing_keys WHERE name = 'certificate'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(seed, "abcd");
+        let settings: Vec<String> = conn
+            .prepare("SELECT key FROM settings")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(settings, vec!["utc_offset_minutes"]);
+    }
+
+    #[test]
+    fn test_newer_database_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1)
+            .unwrap();
+
+        let err = migrate(&mut conn).unwrap_err();
+        assert!(err.to_string().contains("newer"));
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION + 1);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        fn broken(tx: &Transaction) -> Result<()> {
+            tx.execute_batch("CREATE TABLE half_done(id INTEGER); NOT VALID SQL;")?;
+            Ok(())
+        }
+        let mut migrations = MIGRATIONS.to_vec();
+        migrations.push(Migration {
+            version: SCHEMA_VERSION + 1,
+            description: "broken",
+            apply: broken,
+        });
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert!(run(&mut conn, &migrations).is_err());
+
+        assert_eq!(user_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(columns(&conn, "half_done").is_empty());
+    }
+}
@@ -160,10 +160,11 @@ impl Purchase {
 }
 
 /// Content access level
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ContentAccess {
     /// Free - available to all users
+    #[default]
     Free,
     /// Premium - requires active premium subscription
     Premium,
@@ -0,0 +1,400 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+use crate::retention::{priority_for_recall, MemoryLink};
+use crate::scheduler::Scheduler;
+use crate::scripts::Rehearsal;
+
+/// Longest `horizon_hours` the planner accepts
+pub const MAX_HORIZON_HOURS: u32 = 7 * 24;
+
+/// Limits for reminder scheduling, in the learner's local time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlannerConfig {
+    /// No reminders from `quiet_start` until `quiet_end` (may wrap midnight)
+    pub quiet_start: NaiveTime,
+    pub quiet_end: NaiveTime,
+    pub max_per_day: u32,
+    pub min_spacing_minutes: u32,
+    pub horizon_hours: u32,
+}
+
+impl Default for PlannerConfig {
+    fn default() -> Self {
+        Self {
+            quiet_start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            quiet_end: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            max_per_day: 6,
+            min_spacing_minutes: 45,
+            horizon_hours: 24,
+        }
+    }
+}
+
+impl PlannerConfig {
+    fn is_quiet(&self, time: NaiveTime) -> bool {
+        if self.quiet_start <= self.quiet_end {
+            time >= self.quiet_start && time < self.quiet_end
+        } else {
+            time >= self.quiet_start || time < self.quiet_end
+        }
+    }
+}
+
+/// One reminder for the OS notifier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PingPayload {
+    pub fire_at: DateTime<Utc>,
+    pub phrase: String,
+    pub script_id: Option<String>,
+    pub deep_link: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationPlan {
+    /// Earliest first, at most one per phrase
+    pub slots: Vec<PingPayload>,
+    /// Phrases that wanted a reminder but did not fit in the horizon
+    pub skipped: usize,
+}
+
+/// Plan reminders for the next `horizon_hours`
+///
+/// Each phrase asks for a reminder at its next review under `scheduler`,
+/// using its script's ping range where the scheduler has one. Requests are
+/// served earliest first (higher priority on ties) and pushed later as
+/// needed to leave quiet hours, keep `min_spacing_minutes` between
+/// reminders and stay within `max_per_day` per local day. Fails when
+/// `horizon_hours` is above [`MAX_HORIZON_HOURS`].
+pub fn plan_notifications<'a, F>(
+    links: &[MemoryLink],
+    rehearsal_for: F,
+    scheduler: &dyn Scheduler,
+    now: DateTime<Utc>,
+    offset: FixedOffset,
+    config: &PlannerConfig,
+) -> Result<NotificationPlan>
+where
+    F: Fn(&str) -> Option<&'a Rehearsal>,
+{
+    if config.horizon_hours > MAX_HORIZON_HOURS {
+        return Err(anyhow!(
+            "horizon_hours must be at most {}, got {}",
+            MAX_HORIZON_HOURS,
+            config.horizon_hours
+        ));
+    }
+    let horizon = now
+        .checked_add_signed(Duration::hours(config.horizon_hours as i64))
+        .ok_or_else(|| anyhow!("Planning horizon is out of range"))?;
+    let spacing = Duration::minutes(config.min_spacing_minutes as i64);
+    let default_rehearsal = Rehearsal::default();
+
+    let mut seen = HashSet::new();
+    let mut requests: Vec<(DateTime<Utc>, f32, &MemoryLink)> = links
+        .iter()
+        .filter(|link| seen.insert(link.phrase.to_lowercase()))
+        .map(|link| {
+            let rehearsal = link
+                .script_id
+                .as_deref()
+                .and_then(&rehearsal_for)
+                .unwrap_or(&default_rehearsal);
+            let min_s = rehearsal.next_ping_sec_min;
+            let wanted = scheduler
+                .next_review_in_range(link, min_s, rehearsal.next_ping_sec_max.max(min_s))
+                .max(now);
+            let priority = priority_for_recall(link, scheduler.retrievability(link, now), now);
+            (wanted, priority, link)
+        })
+        .collect();
+
+    requests.sort_by(|a, b| {
+        a.0.cmp(&b.0)
+            .then_with(|| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal))
+            .then_with(|| a.2.phrase.cmp(&b.2.phrase))
+    });
+
+    let mut slots: Vec<PingPayload> = Vec::new();
+    let mut per_day: BTreeMap<NaiveDate, u32> = BTreeMap::new();
+    let mut skipped = 0;
+
+    for (wanted, _, link) in requests {
+        let mut at = match slots.last() {
+            Some(last) => wanted.max(last.fire_at + spacing),
+            None => wanted,
+        };
+
+        // Leave quiet hours and full days until the slot settles
+        loop {
+            let local = at.with_timezone(&offset);
+            if config.is_quiet(local.time()) {
+                at = next_quiet_end(at, offset, config);
+            } else if per_day.get(&local.date_naive()).copied().unwrap_or(0) >= config.max_per_day {
+                at = next_quiet_end(
+                    start_of_next_day(at, offset).unwrap_or(horizon),
+                    offset,
+                    config,
+                );
+            } else {
+                break;
+            }
+            if at > horizon {
+                break;
+            }
+        }
+
+        if at > horizon {
+            skipped += 1;
+            continue;
+        }
+
+        *per_day
+            .entry(at.with_timezone(&offset).date_naive())
+            .or_insert(0) += 1;
+        slots.push(PingPayload {
+            fire_at: at,
+            phrase: link.phrase.clone(),
+            script_id: link.script_id.clone(),
+            deep_link: deep_link(&link.phrase, link.script_id.as_deref()),
+        });
+    }
+
+    Ok(NotificationPlan { slots, skipped })
+}
+
+/// First moment at or after `at` that is outside quiet hours
+fn next_quiet_end(at: DateTime<Utc>, offset: FixedOffset, config: &PlannerConfig) -> DateTime<Utc> {
+    let local = at.with_timezone(&offset);
+    if !config.is_quiet(local.time()) {
+        return at;
+    }
+    let mut date = local.date_naive();
+    if local.time() >= config.quiet_end {
+        date = date.succ_opt().unwrap_or(date);
+    }
+    date.and_time(config.quiet_end)
+        .and_local_timezone(offset)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or(at)
+}
+
+fn start_of_next_day(at: DateTime<Utc>, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    at.with_timezone(&offset)
+        .date_naive()
+        .succ_opt()?
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(offset)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// `liminal://ping` link that opens the Ping screen on this phrase
+pub fn deep_link(phrase: &str, script_id: Option<&str>) -> String {
+    let mut link = format!("liminal://ping?phrase={}", percent_encode(phrase));
+    if let Some(script_id) = script_id {
+        link.push_str("&script=");
+        link.push_str(&percent_encode(script_id));
+    }
+    link
+}
+
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{FsrsScheduler, Grade, WaveScheduler};
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn link(phrase: &str, last_seen: &str, wave: f32) -> MemoryLink {
+        let mut link = MemoryLink::new(phrase.to_string(), 0.8);
+        link.last_seen = utc(last_seen);
+        link.wave = wave;
+        link.script_id = Some("cafe-to-go-01".to_string());
+        link
+    }
+
+    fn plan(links: &[MemoryLink], now: &str, config: &PlannerConfig) -> NotificationPlan {
+        plan_notifications(
+            links,
+            |_| None,
+            &WaveScheduler::default(),
+            utc(now),
+            FixedOffset::east_opt(0).unwrap(),
+            config,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_deep_link_is_percent_encoded() {
+        assert_eq!(
+            deep_link("to go, please", Some("cafe-to-go-01")),
+            "liminal://ping?phrase=to%20go%2C%20please&script=cafe-to-go-01"
+        );
+        assert_eq!(deep_link("hi", None), "liminal://ping?phrase=hi");
+    }
+
+    #[test]
+    fn test_slots_respect_spacing_and_dedupe_phrases() {
+        let links = vec![
+            link("a", "2025-03-10T09:00:00Z", 0.0),
+            link("b", "2025-03-10T09:00:00Z", 0.0),
+            link("A", "2025-03-10T09:00:00Z", 0.0),
+        ];
+        let result = plan(&links, "2025-03-10T10:00:00Z", &PlannerConfig::default());
+
+        assert_eq!(result.slots.len(), 2);
+        assert_eq!(result.slots[0].fire_at, utc("2025-03-10T10:00:00Z"));
+        assert_eq!(result.slots[1].fire_at, utc("2025-03-10T10:45:00Z"));
+    }
+
+    #[test]
+    fn test_quiet_hours_push_to_morning() {
+        let links = vec![link("late", "2025-03-10T22:30:00Z", 0.0)];
+        let result = plan(&links, "2025-03-10T22:30:00Z", &PlannerConfig::default());
+
+        assert_eq!(result.slots[0].fire_at, utc("2025-03-11T08:00:00Z"));
+    }
+
+    #[test]
+    fn test_quiet_hours_use_local_time() {
+        let links = vec![link("x", "2025-03-10T20:00:00Z", 0.0)];
+        // 20:00 UTC is 23:00 in UTC+3
+        let result = plan_notifications(
+            &links,
+            |_| None,
+            &WaveScheduler::default(),
+            utc("2025-03-10T20:00:00Z"),
+            FixedOffset::east_opt(3 * 3600).unwrap(),
+            &PlannerConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.slots[0].fire_at, utc("2025-03-11T05:00:00Z"));
+    }
+
+    #[test]
+    fn test_daily_budget_and_horizon() {
+        let links: Vec<MemoryLink> = (0..10)
+            .map(|i| link(&format!("p{}", i), "2025-03-10T08:00:00Z", 0.0))
+            .collect();
+        let config = PlannerConfig {
+            max_per_day: 3,
+            ..Default::default()
+        };
+        let result = plan(&links, "2025-03-10T08:00:00Z", &config);
+
+        // Three today; tomorrow only the 08:00 slot is inside the 24h horizon
+        assert_eq!(result.slots.len(), 4);
+        assert_eq!(result.slots[3].fire_at, utc("2025-03-11T08:00:00Z"));
+        assert_eq!(result.skipped, 6);
+    }
+
+    #[test]
+    fn test_script_ping_range_is_used() {
+        let rehearsal = Rehearsal {
+            next_ping_sec_min: 7200,
+            next_ping_sec_max: 7200,
+            ..Default::default()
+        };
+        let links = vec![link("slow", "2025-03-10T10:00:00Z", 0.5)];
+        let result = plan_notifications(
+            &links,
+            |_| Some(&rehearsal),
+            &WaveScheduler::default(),
+            utc("2025-03-10T10:00:00Z"),
+            FixedOffset::east_opt(0).unwrap(),
+            &PlannerConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.slots[0].fire_at, utc("2025-03-10T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_inverted_ping_range_does_not_panic() {
+        let rehearsal = Rehearsal {
+            next_ping_sec_min: 3600,
+            next_ping_sec_max: 60,
+            ..Default::default()
+        };
+        let links = vec![link("odd", "2025-03-10T10:00:00Z", 0.5)];
+        let result = plan_notifications(
+            &links,
+            |_| Some(&rehearsal),
+            &WaveScheduler::default(),
+            utc("2025-03-10T10:00:00Z"),
+            FixedOffset::east_opt(0).unwrap(),
+            &PlannerConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(result.slots[0].fire_at, utc("2025-03-10T11:00:00Z"));
+    }
+
+    #[test]
+    fn test_oversized_horizon_is_an_error() {
+        let links = vec![link("a", "2025-03-10T09:00:00Z", 0.0)];
+        for horizon_hours in [MAX_HORIZON_HOURS + 1, u32::MAX] {
+            let config = PlannerConfig {
+                horizon_hours,
+                ..Default::default()
+            };
+            let result = plan_notifications(
+                &links,
+                |_| None,
+                &WaveScheduler::default(),
+                utc("2025-03-10T10:00:00Z"),
+                FixedOffset::east_opt(0).unwrap(),
+                &config,
+            );
+            assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn test_fsrs_reminds_at_due_date() {
+        let fsrs = FsrsScheduler::default();
+        let mut reviewed = link("reviewed", "2025-03-10T09:00:00Z", 1.0);
+        fsrs.review(&mut reviewed, Grade::Good, utc("2025-03-10T09:00:00Z"));
+        let due = reviewed.fsrs.as_ref().unwrap().due;
+        let config = PlannerConfig {
+            horizon_hours: MAX_HORIZON_HOURS,
+            quiet_start: NaiveTime::MIN,
+            quiet_end: NaiveTime::MIN,
+            ..Default::default()
+        };
+
+        let result = plan_notifications(
+            &[reviewed],
+            |_| None,
+            &fsrs,
+            utc("2025-03-10T10:00:00Z"),
+            FixedOffset::east_opt(0).unwrap(),
+            &config,
+        )
+        .unwrap();
+
+        assert!(due > utc("2025-03-11T10:00:00Z"));
+        assert_eq!(result.slots[0].fire_at, due);
+    }
+}
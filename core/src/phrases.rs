@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::retention::MemoryLink;
+use crate::scripts::Script;
+use crate::speech::normalize_words;
+
+/// Shortest and longest phrases worth reviewing on their own
+const MIN_WORDS: usize = 2;
+const MAX_WORDS: usize = 14;
+
+/// Which step field a phrase was harvested from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PhraseSource {
+    RefText,
+    Content,
+    Answer,
+    Example,
+}
+
+/// A reviewable phrase found in a script
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExtractedPhrase {
+    pub phrase: String,
+    pub script_id: String,
+    pub step_index: usize,
+    pub source: PhraseSource,
+}
+
+impl ExtractedPhrase {
+    /// New memory link seeded with the script's rehearsal settings
+    pub fn to_memory_link(&self, decay_alpha: f32) -> MemoryLink {
+        let mut link = MemoryLink::new(self.phrase.clone(), decay_alpha);
+        link.script_id = Some(self.script_id.clone());
+        link.step_index = Some(self.step_index as u32);
+        link
+    }
+}
+
+/// Key two phrases share when they only differ in case, punctuation or
+/// contractions
+pub fn phrase_key(phrase: &str) -> String {
+    normalize_words(phrase).join(" ")
+}
+
+/// Harvest reviewable phrases from a script, first occurrence wins
+///
+/// Text is split into sentences; sentences that are too short, too long or
+/// not in English (e.g. Russian instructions) are skipped. Phrases that only
+/// differ in case, punctuation or contractions are kept once.
+pub fn extract_phrases(script: &Script) -> Vec<ExtractedPhrase> {
+    let mut seen = HashSet::new();
+    let mut phrases = Vec::new();
+
+    for (step_index, step) in script.steps.iter().enumerate() {
+        let mut sources: Vec<(PhraseSource, &str)> = Vec::new();
+        sources.extend(step.ref_text.as_deref().map(|t| (PhraseSource::RefText, t)));
+        sources.extend(step.content.as_deref().map(|t| (PhraseSource::Content, t)));
+        for answer in step.answers.iter().flatten().filter(|a| a.is_correct()) {
+            sources.push((PhraseSource::Answer, answer.text()));
+        }
+        for example in step.examples.iter().flatten() {
+            sources.push((PhraseSource::Example, example));
+        }
+
+        for (source, text) in sources {
+            for sentence in split_sentences(text) {
+                let Some(phrase) = clean_phrase(sentence) else {
+                    continue;
+                };
+                if seen.insert(phrase_key(&phrase)) {
+                    phrases.push(ExtractedPhrase {
+                        phrase,
+                        script_id: script.id.clone(),
+                        step_index,
+                        source,
+                    });
+                }
+            }
+        }
+    }
+
+    phrases
+}
+
+/// Split on sentence punctuation followed by whitespace, and on " / " lists
+fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let boundary = match c {
+            '\n' | ';' | '/' => true,
+            '.' | '?' | '!' => chars.peek().is_none_or(|(_, next)| next.is_whitespace()),
+            _ => false,
+        };
+        if boundary {
+            sentences.push(&text[start..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+        }
+    }
+    sentences.push(&text[start..]);
+    sentences
+}
+
+/// Trim quotes and end punctuation; `None` if the phrase should be skipped
+fn clean_phrase(sentence: &str) -> Option<String> {
+    let phrase = sentence
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| "\"'.,;:!?/ ".contains(c))
+        .to_string();
+
+    let words = phrase.split_whitespace().count();
+    // Latin letters including Latin-1 accents ("café", "naïve")
+    let english = phrase
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .all(|c| c.is_ascii_alphabetic() || ('\u{C0}'..='\u{FF}').contains(&c));
+
+    (english && (MIN_WORDS..=MAX_WORDS).contains(&words)).then_some(phrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cafe_script() -> Script {
+        serde_yaml::from_str(
+            r#"
+id: cafe-to-go-01
+title: "Coffee Shop"
+steps:
+  - type: listen
+    prompt: "Listen:"
+    content: "Can I get a large latte to go, please?"
+  - type: speak_check
+    prompt: "Now order:"
+    content: "Can I get a medium cappuccino to go?"
+    ref_text: "Can I get a medium cappuccino to go"
+  - type: contrast
+    prompt: "Which is natural?"
+    answers:
+      - text: "Can I get a coffee to go?"
+        correct: true
+      - text: "Give me one coffee please"
+        correct: false
+  - type: apply_to_life
+    prompt: "Закажи свой напиток"
+    examples:
+      - "Can I get a small black coffee to go?"
+      - "Хочу капучино"
+rehearsal:
+  decay_alpha: 0.85
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_extracts_all_sources() {
+        let phrases = extract_phrases(&cafe_script());
+        let texts: Vec<&str> = phrases.iter().map(|p| p.phrase.as_str()).collect();
+
+        assert_eq!(
+            texts,
+            vec![
+                "Can I get a large latte to go, please",
+                "Can I get a medium cappuccino to go",
+                "Can I get a coffee to go",
+                "Can I get a small black coffee to go",
+            ]
+        );
+        assert_eq!(phrases[1].source, PhraseSource::RefText);
+        assert_eq!(phrases[2].source, PhraseSource::Answer);
+        assert_eq!(phrases[3].step_index, 3);
+        assert!(phrases.iter().all(|p| p.script_id == "cafe-to-go-01"));
+    }
+
+    #[test]
+    fn test_wrong_answers_and_non_english_are_skipped() {
+        let phrases = extract_phrases(&cafe_script());
+        assert!(!phrases.iter().any(|p| p.phrase.contains("Give me")));
+        assert!(!phrases.iter().any(|p| p.phrase.contains("капучино")));
+    }
+
+    #[test]
+    fn test_split_sentences_and_slash_lists() {
+        let parts: Vec<String> =
+            split_sentences("Nice weather today, isn't it? / Going up? / How's your day going?")
+                .into_iter()
+                .filter_map(clean_phrase)
+                .collect();
+        assert_eq!(
+            parts,
+            vec![
+                "Nice weather today, isn't it",
+                "Going up",
+                "How's your day going"
+            ]
+        );
+
+        // Decimal points are not sentence ends
+        assert_eq!(split_sentences("Version 2.5 is out").len(), 1);
+    }
+
+    #[test]
+    fn test_accented_latin_letters_are_english() {
+        assert_eq!(
+            clean_phrase("A café au lait, please.").as_deref(),
+            Some("A café au lait, please")
+        );
+        assert_eq!(
+            clean_phrase("That's a bit naïve"),
+            Some("That's a bit naïve".to_string())
+        );
+        assert!(clean_phrase("Один кофе, please").is_none());
+        assert_eq!(phrase_key("Can't stop!"), phrase_key("can not stop"));
+    }
+
+    #[test]
+    fn test_length_limits() {
+        assert_eq!(clean_phrase("Hello."), None);
+        assert!(clean_phrase(&"word ".repeat(MAX_WORDS + 1)).is_none());
+    }
+
+    #[test]
+    fn test_memory_link_is_linked_to_step() {
+        let phrase = &extract_phrases(&cafe_script())[2];
+        let link = phrase.to_memory_link(0.85);
+
+        assert_eq!(link.phrase, "Can I get a coffee to go");
+        assert_eq!(link.script_id.as_deref(), Some("cafe-to-go-01"));
+        assert_eq!(link.step_index, Some(2));
+        assert_eq!(link.decay_alpha, 0.85);
+    }
+}
@@ -0,0 +1,486 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+use crate::content::find_script;
+use crate::context::PracticeContext;
+use crate::emotion::EmotionMatch;
+use crate::retention::MemoryLink;
+use crate::roles::{
+    Difficulty, EmotionTag, PathRoleStatus, Role, RolePath, RoleProgress, TRANSITION_COHERENCE,
+};
+use crate::scripts::Script;
+
+/// Weights and limits for [`recommend`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecommenderConfig {
+    /// Scenes and roles returned per list
+    pub limit: usize,
+    /// Phrases with a wave below this count as weak
+    pub weak_wave: f32,
+    pub next_scene_weight: f32,
+    pub weakness_weight: f32,
+    pub emotion_weight: f32,
+    pub context_weight: f32,
+    pub difficulty_weight: f32,
+}
+
+impl Default for RecommenderConfig {
+    fn default() -> Self {
+        Self {
+            limit: 5,
+            weak_wave: 0.5,
+            next_scene_weight: 0.3,
+            weakness_weight: 0.3,
+            emotion_weight: 0.2,
+            context_weight: 0.1,
+            difficulty_weight: 0.1,
+        }
+    }
+}
+
+/// What the recommender knows about the learner
+#[derive(Debug, Clone, Default)]
+pub struct LearnerState {
+    /// Progress of every started role
+    pub progress: Vec<RoleProgress>,
+    /// `(role_id, tag)`, oldest first
+    pub emotion_history: Vec<(String, EmotionTag)>,
+    pub memory_links: Vec<MemoryLink>,
+    /// Roles the learner is entitled to open
+    pub accessible_roles: BTreeSet<String>,
+    /// Roles opened by a path
+    pub unlocked_roles: BTreeSet<String>,
+}
+
+impl LearnerState {
+    /// Level implied by progress so far: beginner until a role is
+    /// half-embodied, advanced after two transition-ready roles
+    pub fn level(&self) -> Difficulty {
+        let completed = self
+            .progress
+            .iter()
+            .filter(|p| p.coherence >= TRANSITION_COHERENCE)
+            .count();
+        let best = self
+            .progress
+            .iter()
+            .map(|p| p.coherence)
+            .fold(0.0, f32::max);
+        match completed {
+            0 if best < 0.5 => Difficulty::Beginner,
+            0 | 1 => Difficulty::Intermediate,
+            _ => Difficulty::Advanced,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationKind {
+    Scene,
+    Role,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub kind: RecommendationKind,
+    /// Script id for scenes, role id for roles
+    pub id: String,
+    pub title: String,
+    /// Role the scene belongs to
+    pub role_id: Option<String>,
+    pub score: f32,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Recommendations {
+    pub level: Option<Difficulty>,
+    pub scenes: Vec<Recommendation>,
+    pub roles: Vec<Recommendation>,
+}
+
+/// Rank what to practise next, best first
+///
+/// Scenes score on being next in a started role, weak or leeching phrases
+/// harvested from them, a tone that missed the scene's intended emotion,
+/// fit with `context` and difficulty against the learner's level. Roles
+/// score on being in progress or next on a path. Roles the learner is not
+/// entitled to or that a path still locks are left out, as are scenes that
+/// need more time than `context` allows.
+pub fn recommend(
+    roles: &HashMap<String, Role>,
+    scripts: &HashMap<String, Script>,
+    paths: &[RolePath],
+    learner: &LearnerState,
+    context: Option<&PracticeContext>,
+    config: &RecommenderConfig,
+) -> Recommendations {
+    let level = learner.level();
+    let coherences: HashMap<String, f32> = learner
+        .progress
+        .iter()
+        .map(|p| (p.role_id.clone(), p.coherence))
+        .collect();
+    let statuses = path_statuses(paths, &coherences, &learner.unlocked_roles);
+    let is_open = |role_id: &str| {
+        learner.accessible_roles.contains(role_id)
+            && statuses.get(role_id).is_none_or(|s| {
+                s.iter()
+                    .any(|(_, status)| *status != PathRoleStatus::Locked)
+            })
+    };
+
+    let mut scenes: Vec<Recommendation> = scripts
+        .values()
+        .filter(|script| script.role_id.as_deref().is_none_or(is_open))
+        .filter_map(|script| {
+            let role = script.role_id.as_deref().and_then(|id| roles.get(id));
+            score_scene(script, scripts, role, learner, level, context, config)
+        })
+        .collect();
+
+    let mut role_recs: Vec<Recommendation> = roles
+        .values()
+        .filter(|role| is_open(&role.id))
+        .filter_map(|role| {
+            let progress = learner.progress.iter().find(|p| p.role_id == role.id);
+            let next_on = statuses.get(role.id.as_str()).and_then(|s| {
+                s.iter()
+                    .find(|(_, status)| *status == PathRoleStatus::Unlocked)
+                    .map(|(path, _)| path.title.as_str())
+            });
+            score_role(role, progress, next_on, level, config)
+        })
+        .collect();
+
+    sort_and_truncate(&mut scenes, config.limit);
+    sort_and_truncate(&mut role_recs, config.limit);
+    Recommendations {
+        level: Some(level),
+        scenes,
+        roles: role_recs,
+    }
+}
+
+/// Status of each role on every path that lists it
+fn path_statuses<'a>(
+    paths: &'a [RolePath],
+    coherences: &HashMap<String, f32>,
+    unlocked: &BTreeSet<String>,
+) -> HashMap<&'a str, Vec<(&'a RolePath, PathRoleStatus)>> {
+    let mut statuses: HashMap<&str, Vec<(&RolePath, PathRoleStatus)>> = HashMap::new();
+    for path in paths {
+        let progress = path.progress_for(coherences, unlocked);
+        for (role_id, role) in path.role_ids.iter().zip(progress.roles) {
+            statuses
+                .entry(role_id.as_str())
+                .or_default()
+                .push((path, role.status));
+        }
+    }
+    statuses
+}
+
+fn score_scene(
+    script: &Script,
+    scripts: &HashMap<String, Script>,
+    role: Option<&Role>,
+    learner: &LearnerState,
+    level: Difficulty,
+    context: Option<&PracticeContext>,
+    config: &RecommenderConfig,
+) -> Option<Recommendation> {
+    let mut reasons = Vec::new();
+
+    let (context_fit, context_reasons) = match context {
+        Some(ctx) => script.context_triggers.evaluate(ctx)?,
+        None => (0.0, Vec::new()),
+    };
+
+    let next = role.map_or(0.0, |role| {
+        let position = role.scenario_ids.iter().position(|id| *id == script.id);
+        let progress = learner.progress.iter().find(|p| p.role_id == role.id);
+        match (position, progress) {
+            (Some(i), Some(p)) if i == p.current_scene_index => {
+                reasons.push(format!(
+                    "next scene in {} ({} of {})",
+                    role.title,
+                    i + 1,
+                    role.scenario_ids.len()
+                ));
+                1.0
+            }
+            (Some(0), None) => {
+                reasons.push(format!("first scene of {}", role.title));
+                0.5
+            }
+            _ => 0.0,
+        }
+    });
+
+    let weakest = learner
+        .memory_links
+        .iter()
+        .filter(|link| link.script_id.as_deref() == Some(script.id.as_str()))
+        .filter(|link| link.leech_since.is_some() || link.wave < config.weak_wave)
+        .min_by(|a, b| {
+            a.wave
+                .partial_cmp(&b.wave)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+    let weakness = weakest.map_or(0.0, |link| {
+        reasons.push(format!(
+            "you struggled with '{}' in {}",
+            link.phrase, script.title
+        ));
+        if link.leech_since.is_some() {
+            1.0
+        } else {
+            1.0 - link.wave.clamp(0.0, 1.0)
+        }
+    });
+
+    let last_tone = learner
+        .emotion_history
+        .iter()
+        .rev()
+        .find(|(_, tag)| find_script(scripts, &tag.scene_id).is_some_and(|s| s.id == script.id))
+        .map(|(_, tag)| tag.tone);
+    let emotion = match (script.emotion_wave.as_deref(), last_tone) {
+        (Some(wave), Some(tone)) => match EmotionMatch::compare(wave, tone) {
+            Some(m) if !m.matched => {
+                reasons.push(format!(
+                    "you sounded {} in {}; it calls for {}",
+                    tone.as_str().to_lowercase(),
+                    script.title,
+                    wave.replace('_', " ")
+                ));
+                (m.distance / 2.0).min(1.0)
+            }
+            _ => 0.0,
+        },
+        _ => 0.0,
+    };
+
+    if next == 0.0 && weakness == 0.0 && emotion == 0.0 && context_fit < 0.75 {
+        return None;
+    }
+
+    reasons.extend(context_reasons);
+    let difficulty = script.difficulty.or(role.map(|r| r.difficulty));
+    let fit = difficulty.map_or(0.5, |d| difficulty_fit(d, level));
+    if difficulty == Some(level) {
+        reasons.push(format!("matches your {} level", level_name(level)));
+    }
+
+    Some(Recommendation {
+        kind: RecommendationKind::Scene,
+        id: script.id.clone(),
+        title: script.title.clone(),
+        role_id: role.map(|r| r.id.clone()),
+        score: config.next_scene_weight * next
+            + config.weakness_weight * weakness
+            + config.emotion_weight * emotion
+            + config.context_weight * context_fit
+            + config.difficulty_weight * fit,
+        reasons,
+    })
+}
+
+fn score_role(
+    role: &Role,
+    progress: Option<&RoleProgress>,
+    next_on_path: Option<&str>,
+    level: Difficulty,
+    config: &RecommenderConfig,
+) -> Option<Recommendation> {
+    let target = role.total_coherence_target.unwrap_or(TRANSITION_COHERENCE);
+    let mut reasons = Vec::new();
+
+    let momentum = match progress {
+        Some(p) if p.coherence >= target => return None,
+        Some(p) => {
+            reasons.push(format!(
+                "continue: {} of {} scenes, {:.0}% coherence",
+                p.current_scene_index,
+                p.total_scenes,
+                p.coherence * 100.0
+            ));
+            0.5 + 0.5 * p.coherence
+        }
+        None => match next_on_path {
+            Some(path) => {
+                reasons.push(format!("next on the {} path", path));
+                0.6
+            }
+            None => {
+                reasons.push("a new role to start".to_string());
+                0.3
+            }
+        },
+    };
+
+    if role.difficulty == level {
+        reasons.push(format!("matches your {} level", level_name(level)));
+    }
+
+    Some(Recommendation {
+        kind: RecommendationKind::Role,
+        id: role.id.clone(),
+        title: role.title.clone(),
+        role_id: Some(role.id.clone()),
+        score: config.next_scene_weight * momentum
+            + config.difficulty_weight * difficulty_fit(role.difficulty, level),
+        reasons,
+    })
+}
+
+/// 1.0 at the learner's level, less for easier material, little for harder
+fn difficulty_fit(difficulty: Difficulty, level: Difficulty) -> f32 {
+    match (difficulty as i8) - (level as i8) {
+        0 => 1.0,
+        d if d < 0 => 0.6,
+        _ => 0.2,
+    }
+}
+
+fn level_name(level: Difficulty) -> &'static str {
+    match level {
+        Difficulty::Beginner => "beginner",
+        Difficulty::Intermediate => "intermediate",
+        Difficulty::Advanced => "advanced",
+    }
+}
+
+fn sort_and_truncate(recs: &mut Vec<Recommendation>, limit: usize) {
+    recs.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    recs.truncate(limit);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::content::ContentLibrary;
+    use chrono::{NaiveTime, Utc, Weekday};
+    use std::path::Path;
+
+    fn content() -> (
+        HashMap<String, Role>,
+        HashMap<String, Script>,
+        Vec<RolePath>,
+    ) {
+        let mut library = ContentLibrary::new();
+        library
+            .load_roles_dir(Path::new("../content/roles"))
+            .unwrap();
+        library
+            .load_paths_dir(Path::new("../content/paths"))
+            .unwrap();
+        (
+            library.roles,
+            library.scripts,
+            library.paths.into_values().collect(),
+        )
+    }
+
+    fn free_learner() -> LearnerState {
+        LearnerState {
+            accessible_roles: ["qa_engineer_abroad", "remote_developer", "visa_journey"]
+                .map(String::from)
+                .into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new_learner_starts_on_open_free_roles() {
+        let (roles, scripts, paths) = content();
+        let recs = recommend(
+            &roles,
+            &scripts,
+            &paths,
+            &free_learner(),
+            None,
+            &RecommenderConfig::default(),
+        );
+
+        assert_eq!(recs.level, Some(Difficulty::Beginner));
+        assert_eq!(recs.roles[0].id, "visa_journey");
+        assert!(recs.roles[0].reasons[0].starts_with("next on the"));
+        // qa_engineer_abroad waits for visa_journey on the work abroad path
+        assert!(recs.roles.iter().all(|r| r.id != "qa_engineer_abroad"));
+        // Premium roles are never suggested
+        assert!(recs.roles.iter().all(|r| r.id != "family_abroad"));
+        assert_eq!(recs.scenes[0].id, "visa_officer_01");
+    }
+
+    #[test]
+    fn test_weak_phrases_and_missed_emotions_surface_scenes() {
+        let (roles, scripts, paths) = content();
+        let mut learner = free_learner();
+        learner
+            .unlocked_roles
+            .insert("qa_engineer_abroad".to_string());
+
+        let mut progress = RoleProgress::new("qa_engineer_abroad".to_string(), 5);
+        progress.current_scene_index = 4;
+        progress.coherence = 0.5;
+        learner.progress.push(progress);
+
+        let mut link = MemoryLink::new("escalate".to_string(), 0.8);
+        link.script_id = Some("qa_review_04".to_string());
+        link.leech_since = Some(Utc::now());
+        learner.memory_links.push(link);
+        learner.emotion_history.push((
+            "qa_engineer_abroad".to_string(),
+            EmotionTag::new("qa-bugreport-02".to_string(), "Nervous", 0.9),
+        ));
+
+        let recs = recommend(
+            &roles,
+            &scripts,
+            &paths,
+            &learner,
+            None,
+            &RecommenderConfig::default(),
+        );
+        let scene = |id: &str| recs.scenes.iter().find(|r| r.id == id).unwrap();
+
+        assert_eq!(recs.scenes[0].id, "qa_relax_05");
+        assert!(scene("qa_relax_05").reasons[0].starts_with("next scene in"));
+        assert!(scene("qa_review_04").reasons[0].contains("'escalate'"));
+        assert!(scene("qa_bugreport_02").reasons[0].starts_with("you sounded nervous"));
+
+        let qa = recs.roles.iter().find(|r| r.id == "qa_engineer_abroad");
+        assert!(qa.unwrap().reasons[0].starts_with("continue: 4 of 5"));
+    }
+
+    #[test]
+    fn test_context_filters_scenes_that_do_not_fit() {
+        let (roles, scripts, paths) = content();
+        let ctx = PracticeContext {
+            local_time: NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+            place: None,
+            available_minutes: Some(0),
+            weekday: Weekday::Mon,
+        };
+        let recs = recommend(
+            &roles,
+            &scripts,
+            &paths,
+            &free_learner(),
+            Some(&ctx),
+            &RecommenderConfig::default(),
+        );
+        for rec in &recs.scenes {
+            assert!(scripts[&rec.id].context_triggers.duration_max_sec.is_none());
+        }
+    }
+}
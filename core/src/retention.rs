@@ -1,6 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::scheduler::FsrsState;
+
+/// Time constant `T` of the wave decay, in seconds
+pub const DEFAULT_TIME_CONSTANT_SECS: f32 = 600.0;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryLink {
     pub phrase: String,
@@ -10,6 +15,20 @@ pub struct MemoryLink {
     pub success_count: u32,
     pub fail_count: u32,
     pub use_in_wild_count: u32,
+    /// Script and step the phrase was harvested from
+    #[serde(default)]
+    pub script_id: Option<String>,
+    #[serde(default)]
+    pub step_index: Option<u32>,
+    /// Failed reviews since the last success
+    #[serde(default)]
+    pub consecutive_fails: u32,
+    /// Set while the phrase is flagged as a leech
+    #[serde(default)]
+    pub leech_since: Option<DateTime<Utc>>,
+    /// Set once the phrase has been reviewed with the FSRS scheduler
+    #[serde(default)]
+    pub fsrs: Option<FsrsState>,
 }
 
 impl MemoryLink {
@@ -22,15 +41,24 @@ impl MemoryLink {
             success_count: 0,
             fail_count: 0,
             use_in_wild_count: 0,
+            script_id: None,
+            step_index: None,
+            consecutive_fails: 0,
+            leech_since: None,
+            fsrs: None,
         }
     }
 
     /// Apply time-based decay to wave amplitude
     pub fn tick(&mut self, now: DateTime<Utc>) {
+        self.tick_with(now, DEFAULT_TIME_CONSTANT_SECS);
+    }
+
+    /// Like [`tick`](Self::tick) with a personal time constant
+    pub fn tick_with(&mut self, now: DateTime<Utc>, time_constant_secs: f32) {
         let dt_secs = (now - self.last_seen).num_seconds().max(0) as f32;
-        // Exponential decay: wave *= alpha^(dt/T), T=600s
-        let t = 600.0_f32;
-        let factor = self.decay_alpha.powf(dt_secs / t);
+        // Exponential decay: wave *= alpha^(dt/T)
+        let factor = self.decay_alpha.powf(dt_secs / time_constant_secs.max(1.0));
         self.wave = (self.wave * factor).clamp(0.0, 1.0);
     }
 
@@ -39,6 +67,7 @@ impl MemoryLink {
         self.wave = (self.wave + gain).min(1.0);
         self.last_seen = now;
         self.success_count += 1;
+        self.consecutive_fails = 0;
     }
 
     /// Weaken memory (failure case)
@@ -46,6 +75,7 @@ impl MemoryLink {
         self.wave = (self.wave * self.decay_alpha).clamp(0.0, 1.0);
         self.last_seen = now;
         self.fail_count += 1;
+        self.consecutive_fails += 1;
     }
 
     /// Mark as used in real life (strong positive signal)
@@ -53,15 +83,17 @@ impl MemoryLink {
         self.wave = (self.wave + 0.2).min(1.0);
         self.last_seen = now;
         self.use_in_wild_count += 1;
+        self.consecutive_fails = 0;
     }
 }
 
 /// Calculate next ping delay in seconds based on wave amplitude
 /// Lower wave → shorter delay (needs practice)
 /// Higher wave → longer delay (already strong)
+/// A range with `min_s > max_s` always pings after `min_s`
 pub fn next_ping_seconds(link: &MemoryLink, min_s: u32, max_s: u32) -> u32 {
     let w = link.wave.clamp(0.0, 1.0);
-    let span = (max_s - min_s) as f32;
+    let span = max_s.saturating_sub(min_s) as f32;
     // Inverse relationship: low wave = min delay, high wave = max delay
     let when = min_s as f32 + w * span;
     when.round() as u32
@@ -70,12 +102,27 @@ pub fn next_ping_seconds(link: &MemoryLink, min_s: u32, max_s: u32) -> u32 {
 /// Calculate priority for showing a memory link
 /// Returns 0.0-1.0 (higher = more urgent)
 pub fn calculate_priority(link: &MemoryLink, now: DateTime<Utc>) -> f32 {
+    calculate_priority_with(link, now, DEFAULT_TIME_CONSTANT_SECS)
+}
+
+/// Like [`calculate_priority`] with a personal time constant
+pub fn calculate_priority_with(
+    link: &MemoryLink,
+    now: DateTime<Utc>,
+    time_constant_secs: f32,
+) -> f32 {
     // Apply decay first
     let mut temp_link = link.clone();
-    temp_link.tick(now);
+    temp_link.tick_with(now, time_constant_secs);
+
+    priority_for_recall(link, temp_link.wave, now)
+}
 
-    // Base priority from inverse of wave
-    let mut priority = 1.0 - temp_link.wave;
+/// Priority of a link whose recall probability at `now` is `recall`, as
+/// estimated by any [`Scheduler`](crate::scheduler::Scheduler)
+pub fn priority_for_recall(link: &MemoryLink, recall: f32, now: DateTime<Utc>) -> f32 {
+    // Base priority from inverse of recall
+    let mut priority = 1.0 - recall.clamp(0.0, 1.0);
 
     // Boost for phrases not seen recently
     let days_since = (now - link.last_seen).num_days() as f32;
@@ -130,6 +177,9 @@ mod tests {
         let delay_high = next_ping_seconds(&link, 90, 3600);
 
         assert!(delay_low < delay_high);
+
+        // An inverted range does not underflow
+        assert_eq!(next_ping_seconds(&link, 600, 300), 600);
     }
 
     #[test]
@@ -231,6 +281,17 @@ mod tests {
         assert!(priority > 0.5);
     }
 
+    #[test]
+    fn test_calculate_priority_with_slower_decay() {
+        let mut link = MemoryLink::new("test".to_string(), 0.8);
+        let now = Utc::now();
+        link.last_seen = now - chrono::Duration::hours(1);
+
+        let default = calculate_priority(&link, now);
+        let slower = calculate_priority_with(&link, now, DEFAULT_TIME_CONSTANT_SECS * 10.0);
+        assert!(slower < default);
+    }
+
     #[test]
     fn test_calculate_priority_wild_boost() {
         let mut link = MemoryLink::new("test".to_string(), 0.8);
@@ -0,0 +1,282 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::retention::{priority_for_recall, MemoryLink};
+use crate::scheduler::Scheduler;
+
+/// Tuning for how the practice queue is assembled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QueueConfig {
+    /// Reviewed phrases are due at their scheduled review, or earlier once
+    /// their priority reaches this
+    pub min_priority: f32,
+    /// At most this many phrases from one script in a row
+    pub max_run_per_script: usize,
+    /// One new phrase after every this many overdue ones
+    pub new_every: usize,
+    /// Phrases per day, including the ones already reviewed today
+    pub daily_cap: u32,
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        Self {
+            min_priority: 0.5,
+            max_run_per_script: 2,
+            new_every: 3,
+            daily_cap: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueItem {
+    pub phrase: String,
+    pub script_id: Option<String>,
+    pub step_index: Option<u32>,
+    pub priority: f32,
+    /// Never reviewed yet
+    pub is_new: bool,
+}
+
+/// Phrases due for practice, in the order they should be shown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewQueue {
+    pub items: Vec<QueueItem>,
+    /// Due phrases left out because of the daily cap
+    pub deferred: usize,
+}
+
+impl ReviewQueue {
+    /// Build the queue from every memory link as of `now`
+    ///
+    /// Due-ness and priority come from `scheduler`: its next review date and
+    /// its recall estimate. Overdue phrases come first by priority with a
+    /// new phrase mixed in every `new_every` items; the order is then
+    /// adjusted so no script shows up more than `max_run_per_script` times
+    /// in a row.
+    pub fn build(
+        links: &[MemoryLink],
+        scheduler: &dyn Scheduler,
+        now: DateTime<Utc>,
+        reviewed_today: u32,
+        config: &QueueConfig,
+    ) -> Self {
+        let mut overdue = Vec::new();
+        let mut fresh = Vec::new();
+
+        for link in links {
+            let is_new = link.success_count + link.fail_count + link.use_in_wild_count == 0;
+            let priority = priority_for_recall(link, scheduler.retrievability(link, now), now);
+            let due = scheduler.next_review(link) <= now || priority >= config.min_priority;
+            if !is_new && !due {
+                continue;
+            }
+            let item = QueueItem {
+                phrase: link.phrase.clone(),
+                script_id: link.script_id.clone(),
+                step_index: link.step_index,
+                priority,
+                is_new,
+            };
+            if is_new {
+                fresh.push(item);
+            } else {
+                overdue.push(item);
+            }
+        }
+
+        let by_priority = |a: &QueueItem, b: &QueueItem| {
+            b.priority
+                .partial_cmp(&a.priority)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.phrase.cmp(&b.phrase))
+        };
+        overdue.sort_by(by_priority);
+        // New phrases keep content order: script, then step
+        fresh.sort_by(|a, b| {
+            (&a.script_id, a.step_index, &a.phrase).cmp(&(&b.script_id, b.step_index, &b.phrase))
+        });
+
+        let mixed = mix(overdue, fresh, config.new_every.max(1));
+        let mut items = interleave_scripts(mixed, config.max_run_per_script.max(1));
+
+        let allowed = config.daily_cap.saturating_sub(reviewed_today) as usize;
+        let deferred = items.len().saturating_sub(allowed);
+        items.truncate(allowed);
+
+        Self { items, deferred }
+    }
+
+    pub fn next(&self) -> Option<&QueueItem> {
+        self.items.first()
+    }
+
+    pub fn due_count(&self) -> usize {
+        self.items.len()
+    }
+}
+
+fn mix(overdue: Vec<QueueItem>, fresh: Vec<QueueItem>, new_every: usize) -> Vec<QueueItem> {
+    let mut out = Vec::with_capacity(overdue.len() + fresh.len());
+    let mut fresh = fresh.into_iter();
+    for (i, item) in overdue.into_iter().enumerate() {
+        out.push(item);
+        if (i + 1) % new_every == 0 {
+            out.extend(fresh.next());
+        }
+    }
+    out.extend(fresh);
+    out
+}
+
+fn interleave_scripts(mut remaining: Vec<QueueItem>, max_run: usize) -> Vec<QueueItem> {
+    let mut out: Vec<QueueItem> = Vec::with_capacity(remaining.len());
+
+    while !remaining.is_empty() {
+        let run_script = out.last().and_then(|i| i.script_id.clone());
+        let run = match &run_script {
+            Some(script) => out
+                .iter()
+                .rev()
+                .take_while(|i| i.script_id.as_ref() == Some(script))
+                .count(),
+            None => 0,
+        };
+
+        // Take the best item that does not extend an over-long run; fall back
+        // to the best item when only one script is left
+        let pick = remaining
+            .iter()
+            .position(|i| run < max_run || i.script_id != run_script)
+            .unwrap_or(0);
+        out.push(remaining.remove(pick));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{FsrsScheduler, Grade, WaveScheduler};
+    use chrono::Duration;
+
+    fn link(phrase: &str, script: &str, wave: f32, reviewed: bool) -> MemoryLink {
+        let mut link = MemoryLink::new(phrase.to_string(), 0.8);
+        link.script_id = Some(script.to_string());
+        link.wave = wave;
+        if reviewed {
+            link.success_count = 1;
+        }
+        link
+    }
+
+    #[test]
+    fn test_orders_overdue_by_priority_and_skips_fresh_reviews() {
+        let wave = WaveScheduler::default();
+        let links = vec![
+            link("a", "s1", 0.4, true),
+            link("b", "s2", 0.1, true),
+            link("c", "s3", 0.95, true),
+        ];
+        let queue = ReviewQueue::build(&links, &wave, Utc::now(), 0, &QueueConfig::default());
+
+        let phrases: Vec<&str> = queue.items.iter().map(|i| i.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["b", "a"]);
+        assert_eq!(queue.next().unwrap().phrase, "b");
+    }
+
+    #[test]
+    fn test_decay_makes_phrases_due() {
+        let wave = WaveScheduler::default();
+        let mut strong = link("a", "s1", 0.95, true);
+        let now = Utc::now();
+        strong.last_seen = now;
+
+        let config = QueueConfig::default();
+        assert_eq!(
+            ReviewQueue::build(&[strong.clone()], &wave, now, 0, &config).due_count(),
+            0
+        );
+        assert_eq!(
+            ReviewQueue::build(&[strong], &wave, now + Duration::hours(2), 0, &config).due_count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_new_phrases_are_mixed_in() {
+        let wave = WaveScheduler::default();
+        let links = vec![
+            link("o1", "s1", 0.1, true),
+            link("o2", "s2", 0.2, true),
+            link("o3", "s3", 0.3, true),
+            link("o4", "s4", 0.35, true),
+            link("n1", "s5", 1.0, false),
+            link("n2", "s6", 1.0, false),
+        ];
+        let config = QueueConfig {
+            new_every: 2,
+            ..Default::default()
+        };
+        let queue = ReviewQueue::build(&links, &wave, Utc::now(), 0, &config);
+
+        let phrases: Vec<&str> = queue.items.iter().map(|i| i.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["o1", "o2", "n1", "o3", "o4", "n2"]);
+        assert!(queue.items[2].is_new);
+    }
+
+    #[test]
+    fn test_limits_runs_from_one_script() {
+        let wave = WaveScheduler::default();
+        let links = vec![
+            link("a1", "a", 0.1, true),
+            link("a2", "a", 0.11, true),
+            link("a3", "a", 0.12, true),
+            link("b1", "b", 0.3, true),
+        ];
+        let queue = ReviewQueue::build(&links, &wave, Utc::now(), 0, &QueueConfig::default());
+
+        let phrases: Vec<&str> = queue.items.iter().map(|i| i.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["a1", "a2", "b1", "a3"]);
+    }
+
+    #[test]
+    fn test_daily_cap_counts_todays_reviews() {
+        let wave = WaveScheduler::default();
+        let links: Vec<MemoryLink> = (0..10)
+            .map(|i| link(&format!("p{}", i), &format!("s{}", i), 0.1, true))
+            .collect();
+        let config = QueueConfig {
+            daily_cap: 6,
+            ..Default::default()
+        };
+
+        let queue = ReviewQueue::build(&links, &wave, Utc::now(), 4, &config);
+        assert_eq!(queue.due_count(), 2);
+        assert_eq!(queue.deferred, 8);
+
+        let done = ReviewQueue::build(&links, &wave, Utc::now(), 6, &config);
+        assert!(done.next().is_none());
+    }
+
+    #[test]
+    fn test_fsrs_due_dates_drive_the_queue() {
+        let fsrs = FsrsScheduler::default();
+        let now = Utc::now();
+        let mut due = link("due", "s1", 1.0, false);
+        let mut later = link("later", "s2", 1.0, false);
+        fsrs.review(&mut due, Grade::Good, now - Duration::days(30));
+        fsrs.review(&mut later, Grade::Easy, now - Duration::hours(1));
+
+        // FSRS never touches the wave, so only its due date tells them apart
+        assert_eq!(due.wave, later.wave);
+        let queue = ReviewQueue::build(&[later, due], &fsrs, now, 0, &QueueConfig::default());
+
+        let phrases: Vec<&str> = queue.items.iter().map(|i| i.phrase.as_str()).collect();
+        assert_eq!(phrases, vec!["due"]);
+        assert!(queue.items[0].priority > 0.0);
+    }
+}
@@ -1,5 +1,11 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::coherence::{CoherenceBreakdown, CoherenceEngine, CoherenceInputs, SceneInput};
+use crate::emotion::{EmotionMatch, Tone};
+use crate::monetization::ContentAccess;
+use crate::transitions::{build_transition, RoleTransitions, DEFAULT_LANGUAGE};
 
 /// Role: A professional or life identity that user embodies through scenarios
 ///
@@ -23,9 +29,26 @@ pub struct Role {
     pub scenario_ids: Vec<String>,
     #[serde(default)]
     pub difficulty: Difficulty,
+    /// Who can open the role; roles without `access_level` are free
+    #[serde(default)]
+    pub access_level: ContentAccess,
+    #[serde(default)]
+    pub estimated_duration_weeks: Option<u32>,
+    /// Coherence (0..1) at which the role counts as embodied
+    #[serde(default)]
+    pub total_coherence_target: Option<f32>,
+    #[serde(default)]
+    pub themes: Vec<String>,
+    #[serde(default)]
+    pub target_audience: Vec<String>,
+    #[serde(default)]
+    pub emotional_arc: Option<String>,
+    /// Transition messages and styling for this role's scenes
+    #[serde(default)]
+    pub transitions: Option<RoleTransitions>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum Difficulty {
     Beginner,
@@ -36,11 +59,9 @@ pub enum Difficulty {
 
 /// Role Coherence Score: measures how naturally user embodies the role
 ///
-/// Calculation:
-/// - Base: % of scenarios completed in role
-/// - Bonus: use-in-wild mentions (+10% each, max +50%)
-/// - Penalty: skipped steps (-5% each)
-/// - Range: 0.0 - 1.0
+/// Scored by [`CoherenceEngine`]: weighted share of completed scenarios,
+/// scaled by speaking quality, plus use-in-wild bonus minus skip penalty.
+/// Range: 0.0 - 1.0
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoleCoherenceScore {
     pub role_id: String,
@@ -49,6 +70,8 @@ pub struct RoleCoherenceScore {
     pub use_in_wild_count: u32,
     pub skipped_steps: u32,
     pub score: f32,
+    #[serde(default)]
+    pub breakdown: Option<CoherenceBreakdown>,
 }
 
 impl RoleCoherenceScore {
@@ -60,29 +83,30 @@ impl RoleCoherenceScore {
             use_in_wild_count: 0,
             skipped_steps: 0,
             score: 0.0,
+            breakdown: None,
         }
     }
 
     /// Calculate coherence score based on user progress
     pub fn calculate(&mut self) {
-        if self.total_scenarios == 0 {
-            self.score = 0.0;
-            return;
-        }
-
-        // Base score: completion percentage
-        let base = self.completed_scenarios as f32 / self.total_scenarios as f32;
-
-        // Bonus: use-in-wild (real-world application)
-        // Each use-in-wild adds 10%, max +50%
-        let wild_bonus = (self.use_in_wild_count as f32 * 0.1).min(0.5);
+        self.calculate_with(&CoherenceEngine::default(), &[]);
+    }
 
-        // Penalty: skipped steps (shows lack of engagement)
-        // Each skip removes 5%, max -30%
-        let skip_penalty = (self.skipped_steps as f32 * 0.05).min(0.3);
+    /// Score with scene weights and speak scores, in role order
+    ///
+    /// The first `completed_scenarios` scenes count as done.
+    pub fn calculate_with(&mut self, engine: &CoherenceEngine, scenes: &[SceneInput]) {
+        let mut inputs = CoherenceInputs::sequential(
+            scenes,
+            self.total_scenarios as usize,
+            self.completed_scenarios as usize,
+        );
+        inputs.use_in_wild_count = self.use_in_wild_count;
+        inputs.skipped_steps = self.skipped_steps;
 
-        // Final score
-        self.score = (base + wild_bonus - skip_penalty).clamp(0.0, 1.0);
+        let breakdown = engine.score(&inputs);
+        self.score = breakdown.score;
+        self.breakdown = Some(breakdown);
     }
 
     /// Get human-readable coherence level
@@ -115,20 +139,77 @@ impl RoleCoherenceScore {
     }
 }
 
+/// Coherence at which a role is embodied and the next one can open
+pub const TRANSITION_COHERENCE: f32 = 0.75;
+
 /// Role Path: sequence of roles for progressive development
 ///
 /// Example path: "Work Abroad"
-/// 1. Visa Applicant
-/// 2. Job Seeker
-/// 3. QA Engineer
-/// 4. Team Collaborator
-/// 5. Mentor
+/// 1. Visa Journey
+/// 2. QA Engineer Abroad
+/// 3. Remote Developer
+///
+/// Loaded from `content/paths/<path>.yaml`, where `roles:` lists the role
+/// ids in order.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RolePath {
     pub id: String,
     pub title: String,
     pub description: String,
+    #[serde(alias = "roles")]
     pub role_ids: Vec<String>,
+    /// Extra conditions per role, on top of finishing the previous role
+    #[serde(default)]
+    pub prerequisites: BTreeMap<String, Vec<RolePrerequisite>>,
+}
+
+/// "coherence >= `min_coherence` in `role_id`"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RolePrerequisite {
+    pub role_id: String,
+    #[serde(default = "default_min_coherence")]
+    pub min_coherence: f32,
+}
+
+fn default_min_coherence() -> f32 {
+    TRANSITION_COHERENCE
+}
+
+impl RolePrerequisite {
+    pub fn is_met(&self, coherences: &HashMap<String, f32>) -> bool {
+        coherences
+            .get(&self.role_id)
+            .is_some_and(|c| *c >= self.min_coherence)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathRoleStatus {
+    Locked,
+    Unlocked,
+    InProgress,
+    /// Transition ready: coherence reached [`TRANSITION_COHERENCE`]
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathRoleProgress {
+    pub role_id: String,
+    pub status: PathRoleStatus,
+    pub coherence: Option<f32>,
+    pub unmet_prerequisites: Vec<RolePrerequisite>,
+}
+
+/// Where the learner stands on a path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathProgress {
+    pub path_id: String,
+    pub roles: Vec<PathRoleProgress>,
+    /// Share of completed roles (0..1)
+    pub progress: f32,
+    /// First open role that is not completed yet
+    pub current_role: Option<String>,
 }
 
 impl RolePath {
@@ -138,6 +219,88 @@ impl RolePath {
         self.role_ids.get(pos + 1).map(|s| s.as_str())
     }
 
+    /// Prerequisites of `role_id` not met by the given role coherences
+    pub fn unmet_prerequisites(
+        &self,
+        role_id: &str,
+        coherences: &HashMap<String, f32>,
+    ) -> Vec<RolePrerequisite> {
+        self.prerequisites
+            .get(role_id)
+            .map(|reqs| {
+                reqs.iter()
+                    .filter(|req| !req.is_met(coherences))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Role that opens now that `role_id` is transition ready, if its
+    /// prerequisites are met
+    pub fn role_to_unlock(&self, role_id: &str, coherences: &HashMap<String, f32>) -> Option<&str> {
+        self.next_role(role_id)
+            .filter(|next| self.unmet_prerequisites(next, coherences).is_empty())
+    }
+
+    /// Status of every role on the path
+    ///
+    /// `coherences` holds the stored coherence of each started role and
+    /// `unlocked` the roles opened earlier. A role is open once unlocked or
+    /// started, or when the role before it is completed (the first role
+    /// needs nothing) and its prerequisites are met.
+    pub fn progress_for(
+        &self,
+        coherences: &HashMap<String, f32>,
+        unlocked: &BTreeSet<String>,
+    ) -> PathProgress {
+        let mut roles: Vec<PathRoleProgress> = Vec::with_capacity(self.role_ids.len());
+        for role_id in &self.role_ids {
+            let coherence = coherences.get(role_id).copied();
+            let unmet = self.unmet_prerequisites(role_id, coherences);
+            let previous_done = roles
+                .last()
+                .is_none_or(|prev| prev.status == PathRoleStatus::Completed);
+
+            let status = match coherence {
+                Some(c) if c >= TRANSITION_COHERENCE => PathRoleStatus::Completed,
+                Some(_) => PathRoleStatus::InProgress,
+                None if unlocked.contains(role_id) || (previous_done && unmet.is_empty()) => {
+                    PathRoleStatus::Unlocked
+                }
+                None => PathRoleStatus::Locked,
+            };
+            roles.push(PathRoleProgress {
+                role_id: role_id.clone(),
+                status,
+                coherence,
+                unmet_prerequisites: unmet,
+            });
+        }
+
+        let completed: Vec<String> = roles
+            .iter()
+            .filter(|r| r.status == PathRoleStatus::Completed)
+            .map(|r| r.role_id.clone())
+            .collect();
+        let current_role = roles
+            .iter()
+            .find(|r| {
+                matches!(
+                    r.status,
+                    PathRoleStatus::Unlocked | PathRoleStatus::InProgress
+                )
+            })
+            .map(|r| r.role_id.clone());
+
+        PathProgress {
+            path_id: self.id.clone(),
+            progress: self.progress(&completed),
+            roles,
+            current_role,
+        }
+    }
+
     /// Calculate overall path progress
     pub fn progress(&self, completed_roles: &[String]) -> f32 {
         if self.role_ids.is_empty() {
@@ -172,6 +335,17 @@ pub struct RoleProgress {
     pub consecutive_days: u32,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Coherence after each completed scene count, oldest first
+    #[serde(default)]
+    pub coherence_history: Vec<CoherenceSnapshot>,
+}
+
+/// Coherence as it stood after `scene_index` scenes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoherenceSnapshot {
+    pub scene_index: usize,
+    pub coherence: f32,
+    pub recorded_at: DateTime<Utc>,
 }
 
 impl RoleProgress {
@@ -187,6 +361,7 @@ impl RoleProgress {
             consecutive_days: 0,
             created_at: now,
             updated_at: now,
+            coherence_history: Vec::new(),
         }
     }
 
@@ -199,30 +374,68 @@ impl RoleProgress {
         self.calculate_coherence();
     }
 
-    /// Calculate RoleFlow coherence
+    /// Calculate RoleFlow coherence: completion scaled by the streak
     pub fn calculate_coherence(&mut self) {
-        if self.total_scenes == 0 {
-            self.coherence = 0.0;
-            return;
-        }
-
-        // Base: completion percentage
-        let base = self.current_scene_index as f32 / self.total_scenes as f32;
+        self.calculate_coherence_with(&CoherenceEngine::default(), &[]);
+    }
 
-        // Consistency multiplier
-        let consistency = self.consistency_multiplier();
+    /// Like [`calculate_coherence`](Self::calculate_coherence) with scene
+    /// weights and speak scores of the role's scenes, in order
+    ///
+    /// `scenes` is ignored unless it has exactly `total_scenes` entries.
+    pub fn calculate_coherence_with(
+        &mut self,
+        engine: &CoherenceEngine,
+        scenes: &[SceneInput],
+    ) -> CoherenceBreakdown {
+        let scenes = if scenes.len() == self.total_scenes {
+            scenes
+        } else {
+            &[]
+        };
+        let mut inputs =
+            CoherenceInputs::sequential(scenes, self.total_scenes, self.current_scene_index);
+        inputs.consecutive_days = Some(self.consecutive_days);
+
+        let breakdown = engine.score(&inputs);
+        self.coherence = breakdown.score;
+        self.record_coherence();
+        breakdown
+    }
 
-        self.coherence = (base * consistency).clamp(0.0, 1.0);
+    /// Snapshot the current coherence for the current scene count
+    ///
+    /// Rescoring without completing a scene (e.g. a streak update)
+    /// replaces that scene count's snapshot rather than adding one.
+    fn record_coherence(&mut self) {
+        let snapshot = CoherenceSnapshot {
+            scene_index: self.current_scene_index,
+            coherence: self.coherence,
+            recorded_at: self.updated_at,
+        };
+        match self.coherence_history.last_mut() {
+            Some(last) if last.scene_index == snapshot.scene_index => *last = snapshot,
+            _ => self.coherence_history.push(snapshot),
+        }
     }
 
-    /// Calculate consistency multiplier based on engagement pattern
-    fn consistency_multiplier(&self) -> f32 {
-        match self.consecutive_days {
-            d if d >= 7 => 1.5, // Week+ streak
-            d if d >= 3 => 1.2, // 3-6 days
-            d if d >= 1 => 1.0, // Daily
-            _ => 0.8,           // Sporadic
+    /// Coherence before the latest scene was completed
+    ///
+    /// Taken from the history; progress saved before history was kept
+    /// falls back to scaling the current coherence back by one scene.
+    pub fn previous_coherence(&self) -> f32 {
+        if let Some(snapshot) = self
+            .coherence_history
+            .iter()
+            .rev()
+            .find(|s| s.scene_index < self.current_scene_index)
+        {
+            return snapshot.coherence;
+        }
+        if self.current_scene_index == 0 {
+            return self.coherence;
         }
+        self.coherence * (self.current_scene_index - 1) as f32 / self.current_scene_index as f32
     }
 
     /// Get emotion balance (confident vs nervous)
@@ -234,7 +447,7 @@ impl RoleProgress {
         let confident_count = self
             .emotion_tags
             .iter()
-            .filter(|e| matches!(e.tone.as_str(), "Calm" | "Confident" | "Clear"))
+            .filter(|e| e.tone.is_composed())
             .count();
 
         let total = self.emotion_tags.len();
@@ -243,7 +456,7 @@ impl RoleProgress {
 
     /// Check if ready for liminal transition (>= 75% coherence)
     pub fn is_transition_ready(&self) -> bool {
-        self.coherence >= 0.75
+        self.coherence >= TRANSITION_COHERENCE
     }
 }
 
@@ -253,16 +466,16 @@ impl RoleProgress {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmotionTag {
     pub scene_id: String,
-    pub tone: String,    // "Calm", "Nervous", "Confident", "Uncertain"
+    pub tone: Tone,
     pub confidence: f32, // 0.0 - 1.0
     pub timestamp: DateTime<Utc>,
 }
 
 impl EmotionTag {
-    pub fn new(scene_id: String, tone: String, confidence: f32) -> Self {
+    pub fn new(scene_id: String, tone: impl Into<Tone>, confidence: f32) -> Self {
         Self {
             scene_id,
-            tone,
+            tone: tone.into(),
             confidence,
             timestamp: Utc::now(),
         }
@@ -270,21 +483,24 @@ impl EmotionTag {
 
     /// Get color code for UI feedback
     pub fn color_hex(&self) -> &'static str {
-        match self.tone.as_str() {
-            "Calm" | "Confident" | "Clear" => "#7ED321",     // Green
-            "Nervous" | "Uncertain" | "Rushed" => "#F5A623", // Amber
-            _ => "#4A90E2",                                  // Blue (neutral)
+        if self.tone.is_composed() {
+            "#7ED321" // Green
+        } else if self.tone.valence() < 0.0 {
+            "#F5A623" // Amber
+        } else {
+            "#4A90E2" // Blue (neutral)
         }
     }
 
-    /// Get wave amplitude for visualization
+    /// Get wave amplitude for visualization: 1 (low arousal) to 9 (high)
     pub fn wave_amplitude(&self) -> u8 {
-        match self.tone.as_str() {
-            "Calm" => 3,
-            "Confident" | "Clear" => 5,
-            "Excited" | "Energetic" => 8,
-            _ => 4, // neutral
-        }
+        (4.0 + 5.0 * self.tone.arousal()).round().clamp(1.0, 9.0) as u8
+    }
+
+    /// How close the tone came to a scene's `emotion_wave` or a step's
+    /// `emotion_tag`
+    pub fn compare_to(&self, intended_tag: &str) -> Option<EmotionMatch> {
+        EmotionMatch::compare(intended_tag, self.tone)
     }
 }
 
@@ -352,39 +568,11 @@ impl Reflection {
 
 /// Trigger liminal transition when scene completes
 ///
-/// Returns transition message and animation config
+/// Returns the built-in English transition; use
+/// [`resolve_transition`](crate::transitions::resolve_transition) for
+/// content-defined and localized transitions.
 pub fn liminal_transition(progress: &RoleProgress, role_title: &str) -> LiminalTransition {
-    let prev_coherence = if progress.current_scene_index > 0 {
-        (progress.current_scene_index - 1) as f32 / progress.total_scenes as f32
-    } else {
-        0.0
-    };
-
-    let curr_coherence = progress.coherence;
-
-    let message = match curr_coherence {
-        c if c >= 0.9 => format!(
-            "You've mastered being {}. Ready for the next chapter?",
-            role_title
-        ),
-        c if c >= 0.75 => format!(
-            "You're one step closer to being the {} you want to be.",
-            role_title
-        ),
-        c if c >= 0.5 => format!("Half way there. The {} in you is emerging.", role_title),
-        c if c >= 0.25 => format!("You're on the path to becoming {}.", role_title),
-        _ => format!("First step taken as {}. Keep going.", role_title),
-    };
-
-    LiminalTransition {
-        message,
-        prev_coherence,
-        curr_coherence,
-        animation_duration_ms: 2500,
-        color_from: "#4A90E2".to_string(), // Blue
-        color_to: "#7ED321".to_string(),   // Green
-        sound: "chime_gentle.mp3".to_string(),
-    }
+    build_transition(progress, role_title, None, None, DEFAULT_LANGUAGE)
 }
 
 /// LiminalTransition: Configuration for transition animation
@@ -403,6 +591,28 @@ pub struct LiminalTransition {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_role_manifest_metadata() {
+        let role: Role = serde_yaml::from_str(
+            "id: visa_journey\ntitle: Visa Journey\ndescription: d\naccess_level: premium\nestimated_duration_weeks: 2\ntotal_coherence_target: 0.8\nthemes:\n  - Bureaucracy\ntarget_audience:\n  - Expats\nemotional_arc: |\n  From anxious to calm.\nscenes:\n  - visa-officer-01.yaml\n",
+        )
+        .unwrap();
+
+        assert_eq!(role.access_level, ContentAccess::Premium);
+        assert_eq!(role.estimated_duration_weeks, Some(2));
+        assert_eq!(role.total_coherence_target, Some(0.8));
+        assert_eq!(role.themes, vec!["Bureaucracy"]);
+        assert_eq!(role.target_audience, vec!["Expats"]);
+        assert_eq!(
+            role.emotional_arc.as_deref(),
+            Some("From anxious to calm.\n")
+        );
+
+        let minimal: Role = serde_yaml::from_str("id: r\ntitle: t\ndescription: d\n").unwrap();
+        assert_eq!(minimal.access_level, ContentAccess::Free);
+        assert!(minimal.themes.is_empty());
+    }
+
     #[test]
     fn test_coherence_score_calculation() {
         let mut score = RoleCoherenceScore::new("qa-engineer".to_string(), 5);
@@ -428,6 +638,36 @@ mod tests {
         assert!((score.score - 0.75).abs() < 0.01); // 80% - 5% = 75%
     }
 
+    #[test]
+    fn test_coherence_uses_scene_weights_and_speaking() {
+        let scene = |weight, speak| SceneInput {
+            scene_id: "s".to_string(),
+            weight: Some(weight),
+            completed: false,
+            speak_score: speak,
+        };
+        let scenes = vec![scene(0.1, Some(0.5)), scene(0.3, None)];
+        let engine = CoherenceEngine::default();
+
+        let mut score = RoleCoherenceScore::new("qa-engineer".to_string(), 2);
+        score.completed_scenarios = 1;
+        score.calculate_with(&engine, &scenes);
+        // 25% weighted completion, x0.85 for 50% speaking quality
+        assert!((score.score - 0.2125).abs() < 1e-6);
+        assert_eq!(score.breakdown.as_ref().unwrap().explanation.len(), 2);
+
+        let mut progress = RoleProgress::new("qa-engineer".to_string(), 2);
+        progress.current_scene_index = 1;
+        progress.consecutive_days = 3;
+        let breakdown = progress.calculate_coherence_with(&engine, &scenes);
+        assert!((progress.coherence - 0.2125 * 1.2).abs() < 1e-6);
+        assert_eq!(breakdown.consistency, 1.2);
+
+        // Scenes that do not match the progress are ignored
+        progress.calculate_coherence_with(&engine, &scenes[..1]);
+        assert!((progress.coherence - 0.6).abs() < 1e-6);
+    }
+
     #[test]
     fn test_role_path_progression() {
         let path = RolePath {
@@ -439,6 +679,7 @@ mod tests {
                 "job-seeker".to_string(),
                 "qa-engineer".to_string(),
             ],
+            prerequisites: BTreeMap::new(),
         };
 
         // Next role after visa-applicant
@@ -449,6 +690,44 @@ mod tests {
         assert!((path.progress(&completed) - 0.666).abs() < 0.01); // 2/3
     }
 
+    #[test]
+    fn test_role_path_prerequisites_and_unlocking() {
+        let path: RolePath = serde_yaml::from_str(
+            "id: work_abroad\ntitle: Work Abroad\ndescription: d\nroles:\n  - visa\n  - qa\n  - remote\nprerequisites:\n  remote:\n    - role_id: visa\n      min_coherence: 0.9\n",
+        )
+        .unwrap();
+        assert_eq!(path.role_ids, vec!["visa", "qa", "remote"]);
+
+        let mut coherences = HashMap::new();
+        let progress = path.progress_for(&coherences, &BTreeSet::new());
+        let statuses: Vec<PathRoleStatus> = progress.roles.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PathRoleStatus::Unlocked,
+                PathRoleStatus::Locked,
+                PathRoleStatus::Locked
+            ]
+        );
+        assert_eq!(progress.current_role.as_deref(), Some("visa"));
+
+        coherences.insert("visa".to_string(), 0.8);
+        assert_eq!(path.role_to_unlock("visa", &coherences), Some("qa"));
+        coherences.insert("qa".to_string(), 0.8);
+        // remote also needs 0.9 in visa
+        assert_eq!(path.role_to_unlock("qa", &coherences), None);
+        let progress = path.progress_for(&coherences, &BTreeSet::new());
+        assert_eq!(progress.roles[2].status, PathRoleStatus::Locked);
+        assert_eq!(progress.roles[2].unmet_prerequisites[0].min_coherence, 0.9);
+        assert!((progress.progress - 2.0 / 3.0).abs() < 1e-6);
+
+        // An earlier unlock sticks
+        let unlocked = BTreeSet::from(["remote".to_string()]);
+        let progress = path.progress_for(&coherences, &unlocked);
+        assert_eq!(progress.roles[2].status, PathRoleStatus::Unlocked);
+        assert_eq!(progress.current_role.as_deref(), Some("remote"));
+    }
+
     #[test]
     fn test_coherence_level_boundaries() {
         let mut score = RoleCoherenceScore::new("test".to_string(), 10);
@@ -511,6 +790,25 @@ mod tests {
         assert!((balance - 0.75).abs() < 0.01); // 3/4 = 0.75
     }
 
+    #[test]
+    fn test_emotion_visuals_follow_tone_coordinates() {
+        let tag = |tone: &str| EmotionTag::new("s".to_string(), tone, 0.8);
+
+        assert_eq!(tag("Calm").color_hex(), "#7ED321");
+        assert_eq!(tag("Rushed").color_hex(), "#F5A623");
+        assert_eq!(tag("Excited").color_hex(), "#4A90E2");
+        assert_eq!(tag("Calm").wave_amplitude(), 3);
+        assert_eq!(tag("Confident").wave_amplitude(), 5);
+        assert_eq!(tag("Energetic").wave_amplitude(), 8);
+        assert_eq!(tag("unknown").wave_amplitude(), 4);
+        assert!(
+            tag("Confident")
+                .compare_to("calm_confident")
+                .unwrap()
+                .matched
+        );
+    }
+
     #[test]
     fn test_liminal_transition() {
         let progress = RoleProgress::new("qa-engineer".to_string(), 5);
@@ -519,6 +817,38 @@ mod tests {
         assert!(transition.message.contains("QA Engineer"));
         assert_eq!(transition.prev_coherence, 0.0);
         assert_eq!(transition.animation_duration_ms, 2500);
+
+        let empty = RoleProgress::new("empty".to_string(), 0);
+        let transition = liminal_transition(&empty, "Empty");
+        assert_eq!(transition.prev_coherence, 0.0);
+    }
+
+    #[test]
+    fn test_transition_animates_between_stored_coherence() {
+        let mut progress = RoleProgress::new("qa".to_string(), 4);
+        progress.consecutive_days = 7;
+        progress.calculate_coherence();
+        progress.complete_scene(EmotionTag::new("s1".to_string(), "Calm", 0.9));
+        let after_first = progress.coherence;
+
+        // A streak drop rescoring the same scene count replaces its snapshot
+        progress.consecutive_days = 0;
+        progress.calculate_coherence();
+        assert_eq!(progress.coherence_history.len(), 2);
+
+        progress.consecutive_days = 7;
+        progress.complete_scene(EmotionTag::new("s2".to_string(), "Calm", 0.9));
+        let transition = liminal_transition(&progress, "QA");
+
+        assert_eq!(progress.coherence_history.len(), 3);
+        assert!((progress.coherence_history[1].coherence - 0.2).abs() < 1e-6);
+        assert!((transition.prev_coherence - 0.2).abs() < 1e-6);
+        assert!(transition.curr_coherence > transition.prev_coherence);
+        assert!(after_first > transition.prev_coherence);
+
+        // Progress saved before history was kept scales back by one scene
+        progress.coherence_history.clear();
+        assert!((progress.previous_coherence() - progress.coherence / 2.0).abs() < 1e-6);
     }
 
     #[test]
@@ -1,11 +1,68 @@
-use crate::scripts::{Script, StepType};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::scripts::{Script, StepType};
+use crate::speech::SpeakScore;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunnerState {
     pub script_id: String,
     pub current_index: usize,
     pub completed: bool,
+    /// Every answer submitted on a contrast step, in order
+    #[serde(default)]
+    pub answers: Vec<AnswerRecord>,
+    /// Block `next` on a contrast step until it has been answered correctly
+    #[serde(default = "default_require_correct")]
+    pub require_correct: bool,
+    /// Row in the `sessions` table once the run is persisted
+    #[serde(default)]
+    pub session_id: Option<i64>,
+    /// speak_check results, in order
+    #[serde(default)]
+    pub speak_scores: Vec<SpeakRecord>,
+    /// Step index of every revealed hint (a step appears once per hint)
+    #[serde(default)]
+    pub hints_revealed: Vec<usize>,
+    #[serde(default = "Utc::now")]
+    pub started_at: DateTime<Utc>,
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn default_require_correct() -> bool {
+    true
+}
+
+/// A single answer submitted on a contrast step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerRecord {
+    pub step_index: usize,
+    pub choice: usize,
+    pub correct: bool,
+    pub answered_at: DateTime<Utc>,
+}
+
+/// A scored speak_check attempt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakRecord {
+    pub step_index: usize,
+    pub score: f32,
+    pub passed: bool,
+    pub scored_at: DateTime<Utc>,
+}
+
+/// Result of grading one answer, for immediate UI feedback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnswerFeedback {
+    pub step_index: usize,
+    pub choice: usize,
+    pub correct: bool,
+    pub explanation: Option<String>,
+    /// Attempts on this step so far, including this one
+    pub attempts: u32,
+    pub can_advance: bool,
 }
 
 impl RunnerState {
@@ -14,18 +71,36 @@ impl RunnerState {
             script_id: script.id.clone(),
             current_index: 0,
             completed: false,
+            answers: Vec::new(),
+            require_correct: default_require_correct(),
+            session_id: None,
+            speak_scores: Vec::new(),
+            hints_revealed: Vec::new(),
+            started_at: Utc::now(),
+            updated_at: Utc::now(),
         }
     }
 
-    pub fn next(&mut self, script: &Script) {
+    /// Move to the next step, completing the run after the last one
+    ///
+    /// Fails without moving when `require_correct` holds the learner on an
+    /// unanswered contrast step.
+    pub fn next(&mut self, script: &Script) -> Result<()> {
         if self.completed {
-            return;
+            return Ok(());
+        }
+        if !self.can_advance(script) {
+            return Err(anyhow!(
+                "Step {} must be answered correctly before moving on",
+                self.current_index
+            ));
         }
         if self.current_index + 1 >= script.steps.len() {
             self.completed = true;
         } else {
             self.current_index += 1;
         }
+        Ok(())
     }
 
     pub fn prev(&mut self) {
@@ -49,23 +124,163 @@ impl RunnerState {
         script.steps.get(self.current_index)
     }
 
+    /// Grade `choice` on the contrast step at `step_index` and record it
+    ///
+    /// Steps whose answers mark none as correct are opinion questions:
+    /// every choice is accepted.
+    pub fn submit_answer(
+        &mut self,
+        script: &Script,
+        step_index: usize,
+        choice: usize,
+    ) -> Result<AnswerFeedback> {
+        let step = script
+            .steps
+            .get(step_index)
+            .ok_or_else(|| anyhow!("Step {} not found in '{}'", step_index, script.id))?;
+
+        if !matches!(step.r#type, StepType::Contrast) {
+            return Err(anyhow!("Step {} is not a contrast step", step_index));
+        }
+
+        let answers = step
+            .answers
+            .as_deref()
+            .ok_or_else(|| anyhow!("Step {} has no answers", step_index))?;
+        let answer = answers
+            .get(choice)
+            .ok_or_else(|| anyhow!("Answer {} not found in step {}", choice, step_index))?;
+
+        let graded = answers.iter().any(|a| a.is_correct());
+        let correct = !graded || answer.is_correct();
+
+        self.answers.push(AnswerRecord {
+            step_index,
+            choice,
+            correct,
+            answered_at: Utc::now(),
+        });
+
+        Ok(AnswerFeedback {
+            step_index,
+            choice,
+            correct,
+            explanation: answer.explanation().map(str::to_string),
+            attempts: self.attempts(step_index),
+            can_advance: !self.require_correct || self.answered_correctly(step_index),
+        })
+    }
+
+    /// Number of answers submitted for a step
+    pub fn attempts(&self, step_index: usize) -> u32 {
+        self.answers
+            .iter()
+            .filter(|a| a.step_index == step_index)
+            .count() as u32
+    }
+
+    pub fn answered_correctly(&self, step_index: usize) -> bool {
+        self.answers
+            .iter()
+            .any(|a| a.step_index == step_index && a.correct)
+    }
+
+    /// Whether `next` may leave the current step
+    pub fn can_advance(&self, script: &Script) -> bool {
+        if !self.require_correct {
+            return true;
+        }
+        match self.current_step_type(script) {
+            Some(StepType::Contrast) => self.answered_correctly(self.current_index),
+            _ => true,
+        }
+    }
+
+    /// Record a speak_check result for a step
+    pub fn record_speak_score(&mut self, step_index: usize, score: &SpeakScore) {
+        self.speak_scores.push(SpeakRecord {
+            step_index,
+            score: score.score,
+            passed: score.passed,
+            scored_at: Utc::now(),
+        });
+    }
+
+    /// Best speak_check score per attempted step
+    pub fn best_speak_score(&self, step_index: usize) -> Option<f32> {
+        self.speak_scores
+            .iter()
+            .filter(|s| s.step_index == step_index)
+            .map(|s| s.score)
+            .reduce(f32::max)
+    }
+
+    /// Mean of the best speak_check score of each attempted step
+    pub fn speak_quality(&self) -> Option<f32> {
+        let mut steps: Vec<usize> = self.speak_scores.iter().map(|s| s.step_index).collect();
+        steps.sort_unstable();
+        steps.dedup();
+        let best: Vec<f32> = steps
+            .iter()
+            .filter_map(|i| self.best_speak_score(*i))
+            .collect();
+        (!best.is_empty()).then(|| best.iter().sum::<f32>() / best.len() as f32)
+    }
+
+    /// Reveal the next unseen hint of the current step
+    ///
+    /// Returns `None` once every hint has been shown.
+    pub fn reveal_hint(&mut self, script: &Script) -> Option<String> {
+        let step = self.current_step(script)?;
+        let hints: Vec<&str> = match (&step.hints, &step.hint) {
+            (Some(list), _) => list.iter().map(String::as_str).collect(),
+            (None, Some(single)) => vec![single.as_str()],
+            (None, None) => Vec::new(),
+        };
+
+        let shown = self.hints_revealed_for(self.current_index) as usize;
+        let hint = hints.get(shown)?.to_string();
+        self.hints_revealed.push(self.current_index);
+        Some(hint)
+    }
+
+    pub fn hints_revealed_for(&self, step_index: usize) -> u32 {
+        self.hints_revealed
+            .iter()
+            .filter(|i| **i == step_index)
+            .count() as u32
+    }
+
+    /// Start over as a new run; the next save opens a new session row
+    /// so a finished run keeps its record
     pub fn reset(&mut self) {
         self.current_index = 0;
         self.completed = false;
+        self.answers.clear();
+        self.speak_scores.clear();
+        self.hints_revealed.clear();
+        self.session_id = None;
+        self.started_at = Utc::now();
+        self.updated_at = self.started_at;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scripts::{Rehearsal, Script, Step, StepType};
+    use crate::context::ContextTriggers;
+    use crate::scripts::{Answer, Rehearsal, Script, Step, StepType};
 
     fn mock_script() -> Script {
         Script {
             id: "test-01".to_string(),
+            role_id: None,
             title: "Test Script".to_string(),
             description: None,
-            context_triggers: serde_json::Value::Null,
+            difficulty: None,
+            emotion_wave: None,
+            context: None,
+            context_triggers: ContextTriggers::default(),
             goals: vec![],
             steps: vec![
                 Step {
@@ -76,6 +291,15 @@ mod tests {
                     question: None,
                     answers: None,
                     hints: None,
+                    hint: None,
+                    audio_hint: None,
+                    emotion_tag: None,
+                    difficulty: None,
+                    eval: None,
+                    target: None,
+                    instruction: None,
+                    examples: None,
+                    recording: false,
                 },
                 Step {
                     r#type: StepType::SpeakCheck,
@@ -85,12 +309,61 @@ mod tests {
                     question: None,
                     answers: None,
                     hints: None,
+                    hint: None,
+                    audio_hint: None,
+                    emotion_tag: None,
+                    difficulty: None,
+                    eval: None,
+                    target: None,
+                    instruction: None,
+                    examples: None,
+                    recording: false,
                 },
             ],
             rehearsal: Rehearsal::default(),
+            coherence_weight: None,
+            transition_message: None,
+        }
+    }
+
+    fn contrast_step() -> Step {
+        Step {
+            r#type: StepType::Contrast,
+            prompt: "Choose".to_string(),
+            content: None,
+            ref_text: None,
+            question: Some("Which one?".to_string()),
+            answers: Some(vec![
+                Answer::Rich {
+                    text: "an hour".to_string(),
+                    correct: true,
+                    explanation: Some("Silent h".to_string()),
+                },
+                Answer::Rich {
+                    text: "a hour".to_string(),
+                    correct: false,
+                    explanation: Some("Use 'an' before vowel sounds".to_string()),
+                },
+            ]),
+            hints: None,
+            hint: None,
+            audio_hint: None,
+            emotion_tag: None,
+            difficulty: None,
+            eval: None,
+            target: None,
+            instruction: None,
+            examples: None,
+            recording: false,
         }
     }
 
+    fn script_with_contrast() -> Script {
+        let mut script = mock_script();
+        script.steps.insert(0, contrast_step());
+        script
+    }
+
     #[test]
     fn test_runner_progress() {
         let script = mock_script();
@@ -101,11 +374,133 @@ mod tests {
         assert!(!runner.completed);
 
         // Move to step 1 (last step), which is 2/2 = 1.0
-        runner.next(&script);
+        runner.next(&script).unwrap();
         assert_eq!(runner.progress(&script), 1.0);
 
         // Move past last step, should complete
-        runner.next(&script);
+        runner.next(&script).unwrap();
         assert!(runner.completed);
     }
+
+    #[test]
+    fn test_submit_answer_feedback() {
+        let script = script_with_contrast();
+        let mut runner = RunnerState::new(&script);
+
+        let wrong = runner.submit_answer(&script, 0, 1).unwrap();
+        assert!(!wrong.correct);
+        assert_eq!(wrong.attempts, 1);
+        assert!(!wrong.can_advance);
+        assert_eq!(
+            wrong.explanation.as_deref(),
+            Some("Use 'an' before vowel sounds")
+        );
+
+        let right = runner.submit_answer(&script, 0, 0).unwrap();
+        assert!(right.correct);
+        assert_eq!(right.attempts, 2);
+        assert!(right.can_advance);
+        assert_eq!(runner.answers.len(), 2);
+    }
+
+    #[test]
+    fn test_next_blocked_until_correct() {
+        let script = script_with_contrast();
+        let mut runner = RunnerState::new(&script);
+
+        assert!(runner.next(&script).is_err());
+        assert_eq!(runner.current_index, 0);
+
+        runner.submit_answer(&script, 0, 0).unwrap();
+        runner.next(&script).unwrap();
+        assert_eq!(runner.current_index, 1);
+    }
+
+    #[test]
+    fn test_next_not_blocked_when_configured() {
+        let script = script_with_contrast();
+        let mut runner = RunnerState::new(&script);
+        runner.require_correct = false;
+
+        runner.next(&script).unwrap();
+        assert_eq!(runner.current_index, 1);
+    }
+
+    #[test]
+    fn test_submit_answer_rejects_invalid_input() {
+        let script = script_with_contrast();
+        let mut runner = RunnerState::new(&script);
+
+        assert!(runner.submit_answer(&script, 1, 0).is_err()); // listen step
+        assert!(runner.submit_answer(&script, 0, 5).is_err()); // no such answer
+        assert!(runner.submit_answer(&script, 9, 0).is_err()); // no such step
+        assert!(runner.answers.is_empty());
+    }
+
+    #[test]
+    fn test_ungraded_contrast_accepts_any_choice() {
+        let mut script = script_with_contrast();
+        script.steps[0].answers = Some(vec![
+            Answer::Simple("Tea".to_string()),
+            Answer::Simple("Coffee".to_string()),
+        ]);
+        let mut runner = RunnerState::new(&script);
+
+        let feedback = runner.submit_answer(&script, 0, 1).unwrap();
+        assert!(feedback.correct);
+        assert!(feedback.can_advance);
+    }
+
+    #[test]
+    fn test_legacy_state_json_defaults() {
+        let state: RunnerState =
+            serde_json::from_str(r#"{"script_id":"x","current_index":1,"completed":false}"#)
+                .unwrap();
+        assert!(state.answers.is_empty());
+        assert!(state.require_correct);
+        assert!(state.session_id.is_none());
+    }
+
+    #[test]
+    fn test_reveal_hints_in_order() {
+        let mut script = mock_script();
+        script.steps[1].hints = Some(vec!["First".to_string(), "Second".to_string()]);
+        let mut runner = RunnerState::new(&script);
+
+        assert_eq!(runner.reveal_hint(&script), None); // listen step has no hints
+
+        runner.next(&script).unwrap();
+        assert_eq!(runner.reveal_hint(&script).as_deref(), Some("First"));
+        assert_eq!(runner.reveal_hint(&script).as_deref(), Some("Second"));
+        assert_eq!(runner.reveal_hint(&script), None);
+        assert_eq!(runner.hints_revealed_for(1), 2);
+    }
+
+    #[test]
+    fn test_record_speak_score() {
+        let script = mock_script();
+        let mut runner = RunnerState::new(&script);
+
+        let weak = crate::speech::score_transcript("test", "tent", 0.75);
+        let strong = crate::speech::score_transcript("test", "test", 0.75);
+        runner.record_speak_score(1, &weak);
+        runner.record_speak_score(1, &strong);
+
+        assert_eq!(runner.speak_scores.len(), 2);
+        assert_eq!(runner.best_speak_score(1), Some(1.0));
+        assert_eq!(runner.best_speak_score(0), None);
+    }
+
+    #[test]
+    fn test_speak_quality_averages_best_per_step() {
+        let script = mock_script();
+        let mut runner = RunnerState::new(&script);
+        assert_eq!(runner.speak_quality(), None);
+
+        runner.record_speak_score(1, &crate::speech::score_transcript("test", "test", 0.75));
+        runner.record_speak_score(2, &crate::speech::score_transcript("a b", "a", 0.75));
+        runner.record_speak_score(2, &crate::speech::score_transcript("a b", "x", 0.75));
+
+        assert_eq!(runner.speak_quality(), Some(0.75));
+    }
 }
@@ -0,0 +1,426 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::retention::{next_ping_seconds, MemoryLink, DEFAULT_TIME_CONSTANT_SECS};
+
+/// How well a phrase was recalled during a review
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    fn value(self) -> f32 {
+        match self {
+            Grade::Again => 1.0,
+            Grade::Hard => 2.0,
+            Grade::Good => 3.0,
+            Grade::Easy => 4.0,
+        }
+    }
+}
+
+/// A memory model that decides when a phrase should come back
+pub trait Scheduler {
+    /// Probability (0..1) that the phrase is recalled at `now`
+    fn retrievability(&self, link: &MemoryLink, now: DateTime<Utc>) -> f32;
+
+    /// Update the link after a graded review at `now`
+    fn review(&self, link: &mut MemoryLink, grade: Grade, now: DateTime<Utc>);
+
+    /// When the phrase should next be practised
+    fn next_review(&self, link: &MemoryLink) -> DateTime<Utc>;
+
+    /// Like [`next_review`](Self::next_review) for a script that asks for
+    /// pings `min_s`..`max_s` seconds apart; models that schedule by their
+    /// own intervals ignore the range
+    fn next_review_in_range(&self, link: &MemoryLink, _min_s: u32, _max_s: u32) -> DateTime<Utc> {
+        self.next_review(link)
+    }
+}
+
+/// Which scheduler the learner uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerKind {
+    #[default]
+    Wave,
+    Fsrs,
+}
+
+impl SchedulerKind {
+    pub fn build(self) -> Box<dyn Scheduler + Send + Sync> {
+        match self {
+            SchedulerKind::Wave => Box::new(WaveScheduler::default()),
+            SchedulerKind::Fsrs => Box::new(FsrsScheduler::default()),
+        }
+    }
+}
+
+// ============================================================================
+// Wave model
+// ============================================================================
+
+/// The original wave model: exponential decay with time constant
+/// `time_constant_secs` and a ping delay interpolated between `min_s` and
+/// `max_s`
+#[derive(Debug, Clone)]
+pub struct WaveScheduler {
+    pub min_s: u32,
+    pub max_s: u32,
+    /// Fitted per learner from the review log, see [`crate::fitting`]
+    pub time_constant_secs: f32,
+}
+
+impl Default for WaveScheduler {
+    fn default() -> Self {
+        Self {
+            min_s: 90,
+            max_s: 3600,
+            time_constant_secs: DEFAULT_TIME_CONSTANT_SECS,
+        }
+    }
+}
+
+impl Scheduler for WaveScheduler {
+    fn retrievability(&self, link: &MemoryLink, now: DateTime<Utc>) -> f32 {
+        let mut decayed = link.clone();
+        decayed.tick_with(now, self.time_constant_secs);
+        decayed.wave
+    }
+
+    fn review(&self, link: &mut MemoryLink, grade: Grade, now: DateTime<Utc>) {
+        link.tick_with(now, self.time_constant_secs);
+        match grade {
+            Grade::Again => link.weaken(now),
+            Grade::Hard => link.reinforce(now, 0.1),
+            Grade::Good => link.reinforce(now, 0.2),
+            Grade::Easy => link.reinforce(now, 0.35),
+        }
+    }
+
+    fn next_review(&self, link: &MemoryLink) -> DateTime<Utc> {
+        self.next_review_in_range(link, self.min_s, self.max_s)
+    }
+
+    fn next_review_in_range(&self, link: &MemoryLink, min_s: u32, max_s: u32) -> DateTime<Utc> {
+        link.last_seen + Duration::seconds(next_ping_seconds(link, min_s, max_s) as i64)
+    }
+}
+
+// ============================================================================
+// FSRS model
+// ============================================================================
+
+const DECAY: f32 = -0.5;
+const FACTOR: f32 = 19.0 / 81.0;
+
+/// Memory state used by [`FsrsScheduler`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FsrsState {
+    /// Days until recall probability drops to 90%
+    pub stability: f32,
+    /// 1 (easy) - 10 (hard)
+    pub difficulty: f32,
+    pub due: DateTime<Utc>,
+    pub reps: u32,
+    pub lapses: u32,
+}
+
+/// FSRS (v4.5) parameters
+#[derive(Debug, Clone)]
+pub struct FsrsParams {
+    pub w: [f32; 17],
+    /// Target recall probability at the moment of review
+    pub desired_retention: f32,
+    pub maximum_interval_days: f32,
+}
+
+impl Default for FsrsParams {
+    fn default() -> Self {
+        Self {
+            w: [
+                0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367,
+                1.0461, 2.1072, 0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+            ],
+            desired_retention: 0.9,
+            maximum_interval_days: 365.0,
+        }
+    }
+}
+
+/// Stability/difficulty/retrievability scheduler after FSRS
+///
+/// Same-day reviews are treated like any other review; the learning steps
+/// of full FSRS are not modelled.
+#[derive(Debug, Clone, Default)]
+pub struct FsrsScheduler {
+    pub params: FsrsParams,
+}
+
+impl FsrsScheduler {
+    pub fn new(params: FsrsParams) -> Self {
+        Self { params }
+    }
+
+    /// Recall probability after `elapsed_days` at the given stability
+    pub fn forgetting_curve(elapsed_days: f32, stability: f32) -> f32 {
+        (1.0 + FACTOR * elapsed_days.max(0.0) / stability.max(0.01)).powf(DECAY)
+    }
+
+    /// Days until recall probability reaches `desired_retention`
+    pub fn interval_days(&self, stability: f32) -> f32 {
+        let r = self.params.desired_retention;
+        (stability / FACTOR * (r.powf(1.0 / DECAY) - 1.0))
+            .clamp(1.0 / 24.0, self.params.maximum_interval_days)
+    }
+
+    fn initial_difficulty(&self, grade: Grade) -> f32 {
+        let w = &self.params.w;
+        (w[4] - (grade.value() - 3.0) * w[5]).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f32, grade: Grade) -> f32 {
+        let w = &self.params.w;
+        let next = difficulty - w[6] * (grade.value() - 3.0);
+        // Mean reversion towards the difficulty of a first "Good"
+        (w[7] * self.initial_difficulty(Grade::Good) + (1.0 - w[7]) * next).clamp(1.0, 10.0)
+    }
+
+    fn recall_stability(&self, s: f32, d: f32, r: f32, grade: Grade) -> f32 {
+        let w = &self.params.w;
+        let hard_penalty = if grade == Grade::Hard { w[15] } else { 1.0 };
+        let easy_bonus = if grade == Grade::Easy { w[16] } else { 1.0 };
+        s * (w[8].exp()
+            * (11.0 - d)
+            * s.powf(-w[9])
+            * ((w[10] * (1.0 - r)).exp() - 1.0)
+            * hard_penalty
+            * easy_bonus
+            + 1.0)
+    }
+
+    fn forget_stability(&self, s: f32, d: f32, r: f32) -> f32 {
+        let w = &self.params.w;
+        (w[11] * d.powf(-w[12]) * ((s + 1.0).powf(w[13]) - 1.0) * (w[14] * (1.0 - r)).exp()).min(s)
+    }
+
+    /// Estimate a memory state for a link that has only been scheduled by
+    /// the wave model
+    ///
+    /// Stability grows with past successes and the current wave; difficulty
+    /// follows the (smoothed) failure rate.
+    pub fn seed_state(&self, link: &MemoryLink) -> FsrsState {
+        let successes = link.success_count + link.use_in_wild_count;
+        let fail_rate =
+            (link.fail_count as f32 + 1.0) / ((successes + link.fail_count) as f32 + 2.0);
+        let stability =
+            (self.params.w[2] * 2f32.powi(successes.min(8) as i32) * link.wave.clamp(0.1, 1.0))
+                .min(self.params.maximum_interval_days);
+
+        FsrsState {
+            stability,
+            difficulty: (1.0 + 9.0 * fail_rate).clamp(1.0, 10.0),
+            due: link.last_seen + days(self.interval_days(stability)),
+            reps: link.success_count + link.fail_count,
+            lapses: link.fail_count,
+        }
+    }
+
+    fn state_of(&self, link: &MemoryLink) -> Option<FsrsState> {
+        match &link.fsrs {
+            Some(state) => Some(state.clone()),
+            None if link.success_count + link.fail_count + link.use_in_wild_count > 0 => {
+                Some(self.seed_state(link))
+            }
+            None => None,
+        }
+    }
+}
+
+fn days(d: f32) -> Duration {
+    Duration::seconds((d * 86_400.0).round() as i64)
+}
+
+fn elapsed_days(from: DateTime<Utc>, to: DateTime<Utc>) -> f32 {
+    (to - from).num_seconds().max(0) as f32 / 86_400.0
+}
+
+impl Scheduler for FsrsScheduler {
+    fn retrievability(&self, link: &MemoryLink, now: DateTime<Utc>) -> f32 {
+        match self.state_of(link) {
+            Some(state) => {
+                Self::forgetting_curve(elapsed_days(link.last_seen, now), state.stability)
+            }
+            None => 0.0,
+        }
+    }
+
+    fn review(&self, link: &mut MemoryLink, grade: Grade, now: DateTime<Utc>) {
+        let state = match self.state_of(link) {
+            None => FsrsState {
+                stability: self.params.w[grade.value() as usize - 1],
+                difficulty: self.initial_difficulty(grade),
+                due: now,
+                reps: 1,
+                lapses: u32::from(grade == Grade::Again),
+            },
+            Some(prev) => {
+                let r = Self::forgetting_curve(elapsed_days(link.last_seen, now), prev.stability);
+                let stability = if grade == Grade::Again {
+                    self.forget_stability(prev.stability, prev.difficulty, r)
+                } else {
+                    self.recall_stability(prev.stability, prev.difficulty, r, grade)
+                };
+                FsrsState {
+                    stability,
+                    difficulty: self.next_difficulty(prev.difficulty, grade),
+                    due: now,
+                    reps: prev.reps + 1,
+                    lapses: prev.lapses + u32::from(grade == Grade::Again),
+                }
+            }
+        };
+
+        let due = now + days(self.interval_days(state.stability));
+        link.fsrs = Some(FsrsState { due, ..state });
+        link.last_seen = now;
+        if grade == Grade::Again {
+            link.fail_count += 1;
+            link.consecutive_fails += 1;
+        } else {
+            link.success_count += 1;
+            link.consecutive_fails = 0;
+        }
+    }
+
+    fn next_review(&self, link: &MemoryLink) -> DateTime<Utc> {
+        match self.state_of(link) {
+            Some(state) => state.due,
+            None => link.last_seen,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: i64) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-03-01T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::days(day)
+    }
+
+    fn fresh(phrase: &str) -> MemoryLink {
+        let mut link = MemoryLink::new(phrase.to_string(), 0.8);
+        link.last_seen = at(0);
+        link
+    }
+
+    #[test]
+    fn test_forgetting_curve_hits_ninety_percent_at_stability() {
+        let r = FsrsScheduler::forgetting_curve(10.0, 10.0);
+        assert!((r - 0.9).abs() < 0.001);
+        assert_eq!(FsrsScheduler::forgetting_curve(0.0, 10.0), 1.0);
+    }
+
+    #[test]
+    fn test_first_review_sets_initial_stability_by_grade() {
+        let fsrs = FsrsScheduler::default();
+        let mut again = fresh("a");
+        let mut easy = fresh("b");
+
+        fsrs.review(&mut again, Grade::Again, at(0));
+        fsrs.review(&mut easy, Grade::Easy, at(0));
+
+        let again = again.fsrs.unwrap();
+        let easy = easy.fsrs.unwrap();
+        assert!(again.stability < easy.stability);
+        assert!(again.difficulty > easy.difficulty);
+        assert_eq!(again.lapses, 1);
+        assert!(easy.due > at(10));
+    }
+
+    #[test]
+    fn test_successful_reviews_grow_intervals() {
+        let fsrs = FsrsScheduler::default();
+        let mut link = fresh("to go, please");
+        fsrs.review(&mut link, Grade::Good, at(0));
+
+        let mut last_interval = Duration::zero();
+        for _ in 0..4 {
+            let due = fsrs.next_review(&link);
+            let interval = due - link.last_seen;
+            assert!(interval > last_interval);
+            last_interval = interval;
+            fsrs.review(&mut link, Grade::Good, due);
+        }
+        assert_eq!(link.success_count, 5);
+    }
+
+    #[test]
+    fn test_lapse_shrinks_stability() {
+        let fsrs = FsrsScheduler::default();
+        let mut link = fresh("x");
+        fsrs.review(&mut link, Grade::Good, at(0));
+        fsrs.review(&mut link, Grade::Good, at(4));
+        let before = link.fsrs.clone().unwrap();
+
+        fsrs.review(&mut link, Grade::Again, at(30));
+        let after = link.fsrs.unwrap();
+
+        assert!(after.stability < before.stability);
+        assert!(after.difficulty > before.difficulty);
+        assert_eq!(after.lapses, 1);
+        assert_eq!(link.fail_count, 1);
+    }
+
+    #[test]
+    fn test_seed_state_from_wave_history() {
+        let fsrs = FsrsScheduler::default();
+        let mut strong = fresh("strong");
+        strong.success_count = 5;
+        strong.wave = 0.9;
+        let mut weak = fresh("weak");
+        weak.success_count = 1;
+        weak.fail_count = 4;
+        weak.wave = 0.3;
+
+        let strong = fsrs.seed_state(&strong);
+        let weak = fsrs.seed_state(&weak);
+
+        assert!(strong.stability > weak.stability);
+        assert!(strong.difficulty < weak.difficulty);
+        assert!(strong.due > weak.due);
+    }
+
+    #[test]
+    fn test_unreviewed_link_is_due_now() {
+        let fsrs = FsrsScheduler::default();
+        let link = fresh("new");
+        assert_eq!(fsrs.next_review(&link), link.last_seen);
+        assert_eq!(fsrs.retrievability(&link, at(1)), 0.0);
+    }
+
+    #[test]
+    fn test_wave_scheduler_matches_wave_model() {
+        let wave = WaveScheduler::default();
+        let mut link = fresh("y");
+        link.wave = 0.5;
+
+        assert_eq!(
+            wave.next_review(&link),
+            link.last_seen + Duration::seconds(next_ping_seconds(&link, 90, 3600) as i64)
+        );
+
+        wave.review(&mut link, Grade::Again, at(0));
+        assert_eq!(link.fail_count, 1);
+        assert!(link.fsrs.is_none());
+    }
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::context::{deserialize_triggers, ContextTriggers};
+use crate::roles::Difficulty;
+use crate::transitions::LocalizedText;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum StepType {
@@ -59,6 +63,47 @@ pub struct Step {
     pub answers: Option<Vec<Answer>>,
     #[serde(default)]
     pub hints: Option<Vec<String>>,
+    /// Single free-form hint (role scenes use this instead of `hints`)
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// Delivery note for TTS / narrator (e.g. "Professional tone, medium pace")
+    #[serde(default)]
+    pub audio_hint: Option<String>,
+    /// Intended tone of the learner's answer (e.g. "calm_confident")
+    #[serde(default)]
+    pub emotion_tag: Option<String>,
+    #[serde(default)]
+    pub difficulty: Option<Difficulty>,
+    /// How a speak_check step is evaluated
+    #[serde(default)]
+    pub eval: Option<EvalMode>,
+    /// Learning target of the step (e.g. "ordering phrases")
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub instruction: Option<String>,
+    #[serde(default)]
+    pub examples: Option<Vec<String>>,
+    /// Whether the app should record the learner's answer
+    #[serde(default)]
+    pub recording: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvalMode {
+    Pronunciation,
+    Fluency,
+    Grammar,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Importance {
+    Low,
+    #[default]
+    Medium,
+    High,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +114,12 @@ pub struct Rehearsal {
     pub next_ping_sec_min: u32, // 90
     #[serde(default = "default_ping_max")]
     pub next_ping_sec_max: u32, // 3600
+    #[serde(default = "default_success_threshold")]
+    pub success_threshold: f32, // 0.0..1.0
+    #[serde(default)]
+    pub importance: Importance,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 fn default_decay() -> f32 {
@@ -80,6 +131,9 @@ fn default_ping_min() -> u32 {
 fn default_ping_max() -> u32 {
     3600
 }
+fn default_success_threshold() -> f32 {
+    0.75
+}
 
 impl Default for Rehearsal {
     fn default() -> Self {
@@ -87,6 +141,9 @@ impl Default for Rehearsal {
             decay_alpha: default_decay(),
             next_ping_sec_min: default_ping_min(),
             next_ping_sec_max: default_ping_max(),
+            success_threshold: default_success_threshold(),
+            importance: Importance::default(),
+            tags: Vec::new(),
         }
     }
 }
@@ -94,14 +151,129 @@ impl Default for Rehearsal {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Script {
     pub id: String,
+    /// Owning role for scenes under `content/roles/<role>/`
+    #[serde(default)]
+    pub role_id: Option<String>,
     pub title: String,
     #[serde(default)]
     pub description: Option<String>,
     #[serde(default)]
-    pub context_triggers: serde_json::Value,
+    pub difficulty: Option<Difficulty>,
+    /// Intended emotional shape of the scene (e.g. "calm_confident")
+    #[serde(default)]
+    pub emotion_wave: Option<String>,
+    /// Situation briefing shown before the first step
+    #[serde(default)]
+    pub context: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_triggers")]
+    pub context_triggers: ContextTriggers,
     #[serde(default)]
     pub goals: Vec<String>,
     pub steps: Vec<Step>,
     #[serde(default)]
     pub rehearsal: Rehearsal,
+    /// Share of the role's coherence this scene contributes
+    #[serde(default)]
+    pub coherence_weight: Option<f32>,
+    /// Message shown in the liminal transition after this scene, plain or
+    /// per language; may use `{role}`-style template variables
+    #[serde(default)]
+    pub transition_message: Option<LocalizedText>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_role_scene_fields() {
+        let yaml = r#"
+id: qa_interview_01
+role_id: qa_engineer_abroad
+title: "Tech Interview"
+difficulty: beginner
+emotion_wave: calm_confident
+context: |
+  You're interviewing for a QA Engineer position.
+steps:
+  - type: listen
+    prompt: "Tell me about your QA process"
+    audio_hint: "Professional tone, medium pace"
+  - type: speak_check
+    prompt: "Describe your approach"
+    ref_text: "I start by reviewing the requirements"
+    difficulty: intermediate
+    emotion_tag: confident
+  - type: apply_to_life
+    prompt: "Adapt it to your experience"
+    hint: "Speak naturally"
+rehearsal:
+  decay_alpha: 0.84
+  next_ping_sec_min: 180
+  next_ping_sec_max: 3600
+  success_threshold: 0.8
+coherence_weight: 0.15
+transition_message: "The interview is just the beginning."
+"#;
+        let script: Script = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(script.role_id.as_deref(), Some("qa_engineer_abroad"));
+        assert_eq!(script.difficulty, Some(Difficulty::Beginner));
+        assert_eq!(script.emotion_wave.as_deref(), Some("calm_confident"));
+        assert!(script.context.unwrap().contains("QA Engineer"));
+        assert_eq!(script.coherence_weight, Some(0.15));
+        assert_eq!(
+            script.transition_message.as_ref().map(|m| m.text("ru")),
+            Some("The interview is just the beginning.")
+        );
+        assert_eq!(script.rehearsal.success_threshold, 0.8);
+
+        assert_eq!(
+            script.steps[0].audio_hint.as_deref(),
+            Some("Professional tone, medium pace")
+        );
+        assert_eq!(script.steps[1].difficulty, Some(Difficulty::Intermediate));
+        assert_eq!(script.steps[1].emotion_tag.as_deref(), Some("confident"));
+        assert_eq!(script.steps[2].hint.as_deref(), Some("Speak naturally"));
+    }
+
+    #[test]
+    fn test_parse_asset_script_fields() {
+        let yaml = r#"
+id: cafe-to-go-01
+title: "Coffee Shop"
+steps:
+  - type: listen
+    prompt: "Listen"
+    target: "ordering phrases"
+  - type: speak_check
+    prompt: "Order"
+    ref_text: "Can I get a medium cappuccino to go"
+    eval: pronunciation
+  - type: apply_to_life
+    prompt: "Order your favorite drink"
+    instruction: "Say your real coffee order out loud"
+    examples:
+      - "Can I get a small black coffee to go?"
+    recording: true
+rehearsal:
+  decay_alpha: 0.85
+  importance: high
+  tags:
+    - cafe
+"#;
+        let script: Script = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(script.steps[0].target.as_deref(), Some("ordering phrases"));
+        assert_eq!(script.steps[1].eval, Some(EvalMode::Pronunciation));
+        assert!(!script.steps[1].recording);
+        assert!(script.steps[2].recording);
+        assert_eq!(script.steps[2].examples.as_ref().unwrap().len(), 1);
+        assert_eq!(script.rehearsal.importance, Importance::High);
+        assert_eq!(script.rehearsal.tags, vec!["cafe".to_string()]);
+        // Defaults for fields the file omits
+        assert_eq!(script.rehearsal.next_ping_sec_min, 90);
+        assert_eq!(script.rehearsal.success_threshold, 0.75);
+        assert!(script.role_id.is_none());
+    }
 }
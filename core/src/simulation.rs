@@ -0,0 +1,392 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::phrases::extract_phrases;
+use crate::retention::MemoryLink;
+use crate::scheduler::{Grade, Scheduler, WaveScheduler};
+use crate::scripts::Script;
+
+/// Synthetic learner for [`simulate_retention`]
+///
+/// True recall follows `ceiling * 0.5^(elapsed / half_life)`; the half-life
+/// grows on every success and shrinks on every lapse. The learner opens the
+/// app `sessions_per_day` times, evenly spread over the waking hours, and
+/// answers every ping that is due by then.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearnerProfile {
+    /// Recall probability right after practice
+    pub recall_ceiling: f32,
+    pub initial_half_life_hours: f32,
+    pub success_growth: f32,
+    pub lapse_factor: f32,
+    pub sessions_per_day: u32,
+    pub wake_hour: u32,
+    pub sleep_hour: u32,
+    pub seconds_per_review: u32,
+}
+
+impl Default for LearnerProfile {
+    fn default() -> Self {
+        Self {
+            recall_ceiling: 0.95,
+            initial_half_life_hours: 12.0,
+            success_growth: 2.0,
+            lapse_factor: 0.8,
+            sessions_per_day: 4,
+            wake_hour: 8,
+            sleep_hour: 22,
+            seconds_per_review: 20,
+        }
+    }
+}
+
+impl LearnerProfile {
+    /// Named presets: `casual`, `regular` (the default) and `intensive`
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "casual" => Some(Self {
+                recall_ceiling: 0.9,
+                initial_half_life_hours: 8.0,
+                success_growth: 1.8,
+                sessions_per_day: 2,
+                ..Default::default()
+            }),
+            "regular" => Some(Self::default()),
+            "intensive" => Some(Self {
+                initial_half_life_hours: 24.0,
+                success_growth: 2.5,
+                sessions_per_day: 8,
+                ..Default::default()
+            }),
+            _ => None,
+        }
+    }
+
+    fn session_times(&self, day_start: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let awake_secs = (self.sleep_hour.saturating_sub(self.wake_hour) * 3600) as i64;
+        let sessions = self.sessions_per_day as i64;
+        (0..sessions)
+            .map(|k| {
+                day_start
+                    + Duration::hours(self.wake_hour as i64)
+                    + Duration::seconds(awake_secs * k / sessions)
+            })
+            .collect()
+    }
+}
+
+/// Per-script, per-day totals
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DayStats {
+    pub script_id: String,
+    pub day: u32,
+    pub pings: u32,
+    pub recalled: u32,
+    pub forgotten: u32,
+    /// Model wave at the end of the day, averaged over the script's phrases
+    pub mean_wave: f32,
+    pub min_wave: f32,
+    /// Learner's true recall probability at the end of the day
+    pub mean_recall: f32,
+    pub workload_minutes: f32,
+}
+
+/// Mean model wave of a script's phrases at one hour of the run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WavePoint {
+    pub script_id: String,
+    pub hour: u32,
+    pub mean_wave: f32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub days: Vec<DayStats>,
+    pub trajectory: Vec<WavePoint>,
+}
+
+impl SimulationReport {
+    pub fn daily_csv(&self) -> String {
+        let mut csv = String::from(
+            "script_id,day,pings,recalled,forgotten,mean_wave,min_wave,mean_recall,workload_minutes\n",
+        );
+        for d in &self.days {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.4},{:.4},{:.4},{:.1}\n",
+                d.script_id,
+                d.day,
+                d.pings,
+                d.recalled,
+                d.forgotten,
+                d.mean_wave,
+                d.min_wave,
+                d.mean_recall,
+                d.workload_minutes
+            ));
+        }
+        csv
+    }
+
+    pub fn trajectory_csv(&self) -> String {
+        let mut csv = String::from("script_id,hour,mean_wave\n");
+        for p in &self.trajectory {
+            csv.push_str(&format!("{},{},{:.4}\n", p.script_id, p.hour, p.mean_wave));
+        }
+        csv
+    }
+}
+
+/// Small deterministic PRNG so runs are reproducible
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+struct SimPhrase {
+    link: MemoryLink,
+    half_life_hours: f32,
+}
+
+impl SimPhrase {
+    fn recall_probability(&self, profile: &LearnerProfile, now: DateTime<Utc>) -> f32 {
+        let hours = (now - self.link.last_seen).num_seconds().max(0) as f32 / 3600.0;
+        profile.recall_ceiling * 0.5f32.powf(hours / self.half_life_hours)
+    }
+}
+
+/// Run each script's `rehearsal` settings forward for `days` days
+///
+/// All phrases harvested from a script are learned at the start of day 0
+/// and then only practised when pinged. Scripts are simulated independently
+/// so their settings can be compared side by side; scripts without
+/// reviewable phrases are left out.
+pub fn simulate_retention(
+    scripts: &[&Script],
+    profile: &LearnerProfile,
+    days: u32,
+    seed: u64,
+) -> SimulationReport {
+    let start = DateTime::parse_from_rfc3339("2025-01-06T00:00:00Z")
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_default();
+    let mut report = SimulationReport::default();
+
+    for script in scripts {
+        let rehearsal = &script.rehearsal;
+        let scheduler = WaveScheduler {
+            min_s: rehearsal.next_ping_sec_min,
+            max_s: rehearsal.next_ping_sec_max.max(rehearsal.next_ping_sec_min),
+            ..Default::default()
+        };
+        let learned_at = start + Duration::hours(profile.wake_hour as i64);
+        let mut phrases: Vec<SimPhrase> = extract_phrases(script)
+            .iter()
+            .map(|p| {
+                let mut link = p.to_memory_link(rehearsal.decay_alpha);
+                link.last_seen = learned_at;
+                SimPhrase {
+                    link,
+                    half_life_hours: profile.initial_half_life_hours.max(0.1),
+                }
+            })
+            .collect();
+        if phrases.is_empty() {
+            continue;
+        }
+        let mut rng = Lcg(seed ^ hash(&script.id));
+
+        for day in 0..days {
+            let day_start = start + Duration::days(day as i64);
+            let mut stats = DayStats {
+                script_id: script.id.clone(),
+                day,
+                pings: 0,
+                recalled: 0,
+                forgotten: 0,
+                mean_wave: 0.0,
+                min_wave: 0.0,
+                mean_recall: 0.0,
+                workload_minutes: 0.0,
+            };
+
+            let mut sessions = profile.session_times(day_start).into_iter().peekable();
+            for hour in 0..24 {
+                let hour_end = day_start + Duration::hours(hour + 1);
+                while let Some(at) = sessions.next_if(|at| *at < hour_end) {
+                    for phrase in phrases.iter_mut() {
+                        if scheduler.next_review(&phrase.link) > at {
+                            continue;
+                        }
+                        let recalled = rng.next_f32() < phrase.recall_probability(profile, at);
+                        let grade = if recalled { Grade::Good } else { Grade::Again };
+                        scheduler.review(&mut phrase.link, grade, at);
+                        if recalled {
+                            phrase.half_life_hours *= profile.success_growth;
+                            stats.recalled += 1;
+                        } else {
+                            phrase.half_life_hours =
+                                (phrase.half_life_hours * profile.lapse_factor).max(1.0);
+                            stats.forgotten += 1;
+                        }
+                        stats.pings += 1;
+                    }
+                }
+                // Nothing is learned yet, so there is no wave to report
+                let mean_wave = if hour_end <= learned_at {
+                    0.0
+                } else {
+                    mean(phrases.iter().map(|p| wave_at(&p.link, hour_end)))
+                };
+                report.trajectory.push(WavePoint {
+                    script_id: script.id.clone(),
+                    hour: day * 24 + hour as u32,
+                    mean_wave,
+                });
+            }
+
+            let day_end = day_start + Duration::days(1);
+            stats.mean_wave = mean(phrases.iter().map(|p| wave_at(&p.link, day_end)));
+            stats.min_wave = phrases
+                .iter()
+                .map(|p| wave_at(&p.link, day_end))
+                .fold(1.0, f32::min);
+            stats.mean_recall = mean(
+                phrases
+                    .iter()
+                    .map(|p| p.recall_probability(profile, day_end)),
+            );
+            stats.workload_minutes = stats.pings as f32 * profile.seconds_per_review as f32 / 60.0;
+            report.days.push(stats);
+        }
+    }
+
+    report
+}
+
+fn wave_at(link: &MemoryLink, now: DateTime<Utc>) -> f32 {
+    let mut link = link.clone();
+    link.tick(now);
+    link.wave
+}
+
+fn mean(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, n) = values.fold((0.0, 0), |(s, n), v| (s + v, n + 1));
+    if n == 0 {
+        0.0
+    } else {
+        sum / n as f32
+    }
+}
+
+/// FNV-1a, so each script gets its own stable random stream
+fn hash(value: &str) -> u64 {
+    value.bytes().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(id: &str, decay: f32, ping_min: u32, ping_max: u32) -> Script {
+        serde_yaml::from_str(&format!(
+            "id: {}\ntitle: t\nrehearsal:\n  decay_alpha: {}\n  next_ping_sec_min: {}\n  next_ping_sec_max: {}\nsteps:\n  - type: listen\n    prompt: a\n    ref_text: \"Could I get a medium cappuccino to go? Oat milk, please. Thanks so much for your help.\"\n",
+            id, decay, ping_min, ping_max
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_is_deterministic() {
+        let s = script("cafe", 0.82, 90, 3600);
+        let a = simulate_retention(&[&s], &LearnerProfile::default(), 5, 7);
+        let b = simulate_retention(&[&s], &LearnerProfile::default(), 5, 7);
+        assert_eq!(a.days, b.days);
+        assert_eq!(a.trajectory, b.trajectory);
+        assert_eq!(a.days.len(), 5);
+        assert_eq!(a.trajectory.len(), 5 * 24);
+    }
+
+    #[test]
+    fn test_pings_are_bounded_by_sessions() {
+        let s = script("cafe", 0.82, 90, 3600);
+        let phrases = extract_phrases(&s).len() as u32;
+        let profile = LearnerProfile::preset("casual").unwrap();
+        let report = simulate_retention(&[&s], &profile, 3, 1);
+
+        for day in &report.days {
+            assert!(day.pings <= phrases * profile.sessions_per_day);
+            assert_eq!(day.pings, day.recalled + day.forgotten);
+            assert_eq!(
+                day.workload_minutes,
+                day.pings as f32 * profile.seconds_per_review as f32 / 60.0
+            );
+        }
+    }
+
+    #[test]
+    fn test_longer_ping_range_means_less_work() {
+        let eager = script("eager", 0.82, 90, 600);
+        let relaxed = script("relaxed", 0.82, 4 * 3600, 12 * 3600);
+        let report = simulate_retention(&[&eager, &relaxed], &LearnerProfile::default(), 7, 3);
+
+        let total = |id: &str| -> u32 {
+            report
+                .days
+                .iter()
+                .filter(|d| d.script_id == id)
+                .map(|d| d.pings)
+                .sum()
+        };
+        assert!(total("eager") > total("relaxed"));
+    }
+
+    #[test]
+    fn test_csv_output() {
+        let s = script("cafe", 0.82, 90, 3600);
+        let report = simulate_retention(&[&s], &LearnerProfile::default(), 2, 1);
+
+        let daily = report.daily_csv();
+        assert!(daily.starts_with("script_id,day,pings,"));
+        assert_eq!(daily.lines().count(), 3);
+        assert!(daily.lines().nth(1).unwrap().starts_with("cafe,0,"));
+        assert_eq!(report.trajectory_csv().lines().count(), 1 + 48);
+    }
+
+    #[test]
+    fn test_trajectory_is_zero_before_learning() {
+        let s = script("cafe", 0.82, 90, 3600);
+        let profile = LearnerProfile::default();
+        let report = simulate_retention(&[&s], &profile, 1, 1);
+
+        let wake = profile.wake_hour as usize;
+        assert!(report.trajectory[..wake].iter().all(|p| p.mean_wave == 0.0));
+        assert!(report.trajectory[wake].mean_wave > 0.0);
+    }
+
+    #[test]
+    fn test_no_sessions_means_no_pings() {
+        let s = script("cafe", 0.82, 90, 3600);
+        let profile = LearnerProfile {
+            sessions_per_day: 0,
+            ..Default::default()
+        };
+        let report = simulate_retention(&[&s], &profile, 2, 1);
+        assert!(report.days.iter().all(|d| d.pings == 0));
+    }
+
+    #[test]
+    fn test_unknown_preset() {
+        assert!(LearnerProfile::preset("casual").is_some());
+        assert!(LearnerProfile::preset("superhuman").is_none());
+    }
+}
@@ -0,0 +1,449 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::scripts::{Script, StepType};
+
+/// How a reference word fared in the learner's transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WordStatus {
+    /// Said as expected
+    Correct,
+    /// A different word was said in its place
+    Substituted,
+    /// Missing from the transcript
+    Deleted,
+    /// Extra word in the transcript with no reference counterpart
+    Inserted,
+}
+
+/// One aligned position between reference and transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WordResult {
+    pub status: WordStatus,
+    /// Normalized reference word (None for insertions)
+    pub expected: Option<String>,
+    /// Normalized transcript word (None for deletions)
+    pub heard: Option<String>,
+}
+
+/// Word-level score of a speak_check attempt
+///
+/// Calculation:
+/// - Align normalized words with minimal edit distance
+/// - WER = (substitutions + deletions + insertions) / reference words
+/// - Score = 1 - WER, clamped to 0.0 - 1.0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakScore {
+    pub words: Vec<WordResult>,
+    pub correct: u32,
+    pub substitutions: u32,
+    pub deletions: u32,
+    pub insertions: u32,
+    pub reference_words: u32,
+    pub wer: f32,
+    pub score: f32,
+    pub threshold: f32,
+    pub passed: bool,
+}
+
+/// Score an ASR transcript against a reference text
+pub fn score_transcript(ref_text: &str, transcript: &str, threshold: f32) -> SpeakScore {
+    let expected = normalize_words(ref_text);
+    let heard = normalize_words(transcript);
+    let words = align(&expected, &heard);
+
+    let count = |status| words.iter().filter(|w| w.status == status).count() as u32;
+    let correct = count(WordStatus::Correct);
+    let substitutions = count(WordStatus::Substituted);
+    let deletions = count(WordStatus::Deleted);
+    let insertions = count(WordStatus::Inserted);
+
+    let reference_words = expected.len() as u32;
+    let errors = (substitutions + deletions + insertions) as f32;
+    let wer = if reference_words == 0 {
+        if heard.is_empty() {
+            0.0
+        } else {
+            1.0
+        }
+    } else {
+        errors / reference_words as f32
+    };
+    let score = (1.0 - wer).clamp(0.0, 1.0);
+
+    SpeakScore {
+        words,
+        correct,
+        substitutions,
+        deletions,
+        insertions,
+        reference_words,
+        wer,
+        score,
+        threshold,
+        passed: score >= threshold,
+    }
+}
+
+/// Score a transcript for a speak_check step using the script's `success_threshold`
+pub fn score_speak_check(
+    script: &Script,
+    step_index: usize,
+    transcript: &str,
+) -> Result<SpeakScore> {
+    let step = script
+        .steps
+        .get(step_index)
+        .ok_or_else(|| anyhow!("Step {} not found in '{}'", step_index, script.id))?;
+
+    if !matches!(step.r#type, StepType::SpeakCheck) {
+        return Err(anyhow!("Step {} is not a speak_check step", step_index));
+    }
+
+    let ref_text = step
+        .ref_text
+        .as_deref()
+        .ok_or_else(|| anyhow!("Step {} has no ref_text", step_index))?;
+
+    Ok(score_transcript(
+        ref_text,
+        transcript,
+        script.rehearsal.success_threshold,
+    ))
+}
+
+/// Lowercase, strip punctuation, expand contractions and spell out numbers
+pub fn normalize_words(text: &str) -> Vec<String> {
+    let lowered = text.to_lowercase().replace(['’', '‘'], "'");
+    let mut words = Vec::new();
+
+    for raw in lowered.split(|c: char| c.is_whitespace() || c == '-' || c == '/') {
+        let token: String = raw
+            .trim_matches(|c: char| !c.is_alphanumeric())
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == '\'' || *c == '.' || *c == ',')
+            .collect();
+
+        if token.is_empty() {
+            continue;
+        }
+
+        if token.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+            words.extend(number_words(&token));
+            continue;
+        }
+
+        let token: String = token.chars().filter(|c| *c != '.' && *c != ',').collect();
+        words.extend(expand_contraction(&token));
+    }
+
+    words
+}
+
+fn expand_contraction(word: &str) -> Vec<String> {
+    let split = |s: &str| s.split(' ').map(str::to_string).collect::<Vec<_>>();
+
+    let irregular = match word {
+        "can't" | "cannot" => Some("can not"),
+        "won't" => Some("will not"),
+        "shan't" => Some("shall not"),
+        "ain't" => Some("is not"),
+        "let's" => Some("let us"),
+        "it's" => Some("it is"),
+        "that's" => Some("that is"),
+        "what's" => Some("what is"),
+        "there's" => Some("there is"),
+        "here's" => Some("here is"),
+        "he's" => Some("he is"),
+        "she's" => Some("she is"),
+        "who's" => Some("who is"),
+        "where's" => Some("where is"),
+        _ => None,
+    };
+    if let Some(expanded) = irregular {
+        return split(expanded);
+    }
+
+    const SUFFIXES: [(&str, &str); 6] = [
+        ("n't", "not"),
+        ("'ll", "will"),
+        ("'re", "are"),
+        ("'ve", "have"),
+        ("'m", "am"),
+        ("'d", "would"),
+    ];
+    for (suffix, full) in SUFFIXES {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            if !stem.is_empty() {
+                return vec![stem.to_string(), full.to_string()];
+            }
+        }
+    }
+
+    // Possessives and stray quotes: keep the bare word
+    vec![word.replace('\'', "")]
+}
+
+fn number_words(token: &str) -> Vec<String> {
+    let digits: String = token.chars().filter(|c| *c != ',').collect();
+    let digits = digits.trim_end_matches('.');
+
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((w, f)) => (w, Some(f)),
+        None => (digits, None),
+    };
+
+    let mut words = match whole.parse::<u64>() {
+        Ok(n) if n < 1_000_000 => spell_number(n),
+        _ => return vec![token.to_string()],
+    };
+
+    if let Some(fraction) = fraction {
+        words.push("point".to_string());
+        for c in fraction.chars().filter(|c| c.is_ascii_digit()) {
+            words.extend(spell_number(c.to_digit(10).unwrap_or(0) as u64));
+        }
+    }
+
+    words
+}
+
+fn spell_number(n: u64) -> Vec<String> {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+
+    let mut words = Vec::new();
+    match n {
+        0..=19 => words.push(ONES[n as usize].to_string()),
+        20..=99 => {
+            words.push(TENS[(n / 10) as usize].to_string());
+            let rest = n % 10;
+            if rest > 0 {
+                words.push(ONES[rest as usize].to_string());
+            }
+        }
+        100..=999 => {
+            words.push(ONES[(n / 100) as usize].to_string());
+            words.push("hundred".to_string());
+            let rest = n % 100;
+            if rest > 0 {
+                words.extend(spell_number(rest));
+            }
+        }
+        _ => {
+            words.extend(spell_number(n / 1000));
+            words.push("thousand".to_string());
+            let rest = n % 1000;
+            if rest > 0 {
+                words.extend(spell_number(rest));
+            }
+        }
+    }
+    words
+}
+
+/// Levenshtein alignment over words with a backtrace
+fn align(expected: &[String], heard: &[String]) -> Vec<WordResult> {
+    let (n, m) = (expected.len(), heard.len());
+    let mut dist = vec![vec![0u32; m + 1]; n + 1];
+
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i as u32;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j as u32;
+    }
+
+    for i in 1..=n {
+        for j in 1..=m {
+            let cost = u32::from(expected[i - 1] != heard[j - 1]);
+            dist[i][j] = (dist[i - 1][j - 1] + cost)
+                .min(dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1);
+        }
+    }
+
+    // Backtrace preferring exact matches, then gaps, then substitutions,
+    // so a dropped word shows up as a deletion rather than a chain of swaps
+    let mut words = Vec::with_capacity(n.max(m));
+    let (mut i, mut j) = (n, m);
+    while i > 0 || j > 0 {
+        let diagonal = i > 0 && j > 0;
+        if diagonal && expected[i - 1] == heard[j - 1] && dist[i][j] == dist[i - 1][j - 1] {
+            words.push(WordResult {
+                status: WordStatus::Correct,
+                expected: Some(expected[i - 1].clone()),
+                heard: Some(heard[j - 1].clone()),
+            });
+            i -= 1;
+            j -= 1;
+        } else if i > 0 && dist[i][j] == dist[i - 1][j] + 1 {
+            words.push(WordResult {
+                status: WordStatus::Deleted,
+                expected: Some(expected[i - 1].clone()),
+                heard: None,
+            });
+            i -= 1;
+        } else if j > 0 && dist[i][j] == dist[i][j - 1] + 1 {
+            words.push(WordResult {
+                status: WordStatus::Inserted,
+                expected: None,
+                heard: Some(heard[j - 1].clone()),
+            });
+            j -= 1;
+        } else {
+            words.push(WordResult {
+                status: WordStatus::Substituted,
+                expected: Some(expected[i - 1].clone()),
+                heard: Some(heard[j - 1].clone()),
+            });
+            i -= 1;
+            j -= 1;
+        }
+    }
+
+    words.reverse();
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_punctuation_and_case() {
+        assert_eq!(
+            normalize_words("Can I get a medium cappuccino, to go?"),
+            vec!["can", "i", "get", "a", "medium", "cappuccino", "to", "go"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_contractions() {
+        assert_eq!(
+            normalize_words("I'll be there"),
+            normalize_words("I will be there")
+        );
+        assert_eq!(normalize_words("We can't"), vec!["we", "can", "not"]);
+        assert_eq!(normalize_words("won’t"), vec!["will", "not"]);
+        assert_eq!(
+            normalize_words("the user's data"),
+            vec!["the", "users", "data"]
+        );
+    }
+
+    #[test]
+    fn test_normalize_numbers() {
+        assert_eq!(normalize_words("2 days"), vec!["two", "days"]);
+        assert_eq!(
+            normalize_words("1,250 users"),
+            vec!["one", "thousand", "two", "hundred", "fifty", "users"]
+        );
+        assert_eq!(normalize_words("2.5"), vec!["two", "point", "five"]);
+        assert_eq!(normalize_words("version 10."), vec!["version", "ten"]);
+    }
+
+    #[test]
+    fn test_perfect_match() {
+        let score = score_transcript(
+            "I'll be there in an hour.",
+            "i will be there in an hour",
+            0.75,
+        );
+
+        assert_eq!(score.wer, 0.0);
+        assert_eq!(score.score, 1.0);
+        assert!(score.passed);
+        assert!(score.words.iter().all(|w| w.status == WordStatus::Correct));
+    }
+
+    #[test]
+    fn test_substitution_deletion_insertion() {
+        // "medium" -> "large", "to" deleted, "please" inserted
+        let score = score_transcript(
+            "Can I get a medium cappuccino to go",
+            "can i get a large cappuccino go please",
+            0.75,
+        );
+
+        assert_eq!(score.reference_words, 8);
+        assert_eq!(score.substitutions, 1);
+        assert_eq!(score.deletions, 1);
+        assert_eq!(score.insertions, 1);
+        assert_eq!(score.correct, 6);
+        assert!((score.wer - 3.0 / 8.0).abs() < 0.001);
+        assert!(!score.passed);
+
+        let statuses: Vec<WordStatus> = score.words.iter().map(|w| w.status).collect();
+        assert_eq!(statuses[4], WordStatus::Substituted);
+        assert_eq!(statuses[6], WordStatus::Deleted);
+        assert_eq!(statuses[8], WordStatus::Inserted);
+        assert_eq!(score.words[4].heard.as_deref(), Some("large"));
+    }
+
+    #[test]
+    fn test_empty_transcript() {
+        let score = score_transcript("Good morning", "", 0.5);
+
+        assert_eq!(score.deletions, 2);
+        assert_eq!(score.score, 0.0);
+        assert!(!score.passed);
+    }
+
+    #[test]
+    fn test_score_clamped_with_many_insertions() {
+        let score = score_transcript("yes", "yes yes yes yes", 0.5);
+
+        assert_eq!(score.insertions, 3);
+        assert!(score.wer > 1.0);
+        assert_eq!(score.score, 0.0);
+    }
+
+    #[test]
+    fn test_score_speak_check_uses_script_threshold() {
+        let yaml = r#"
+id: warmup
+title: "Warmup"
+steps:
+  - type: listen
+    prompt: "Listen"
+  - type: speak_check
+    prompt: "Say it"
+    ref_text: "I'll be there in an hour"
+rehearsal:
+  success_threshold: 0.8
+"#;
+        let script: Script = serde_yaml::from_str(yaml).unwrap();
+
+        let score = score_speak_check(&script, 1, "I will be there in a hour").unwrap();
+        assert_eq!(score.threshold, 0.8);
+        assert!(score.passed); // 1 error in 7 words
+
+        assert!(score_speak_check(&script, 0, "listen").is_err());
+        assert!(score_speak_check(&script, 5, "missing").is_err());
+    }
+}
@@ -1,161 +1,61 @@
-use anyhow::Result;
-use chrono::{DateTime, Utc};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use rusqlite::types::{Value, ValueRef};
 use rusqlite::{params, Connection};
-
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use crate::certificates::{CertificateSigner, SignedCertificate};
+use crate::coherence::CoherenceEngine;
+use crate::emotion::Tone;
+use crate::fitting::{fit_time_constant, FitReport, ReviewLogEntry};
+use crate::leech::LeechConfig;
+use crate::migrations;
 use crate::monetization::{
     ContentAccess, ContentType, ContentUnlock, Entitlement, EntitlementReason, Purchase,
     Subscription, SubscriptionStatus,
 };
-use crate::retention::MemoryLink;
-use crate::roles::{EmotionTag, Reflection, ResonanceTrace, RoleProgress};
+use crate::phrases::{extract_phrases, phrase_key};
+use crate::recommend::LearnerState;
+use crate::retention::{MemoryLink, DEFAULT_TIME_CONSTANT_SECS};
+use crate::review_queue::{QueueConfig, ReviewQueue};
+use crate::roles::{
+    CoherenceSnapshot, EmotionTag, PathProgress, Reflection, ResonanceTrace, Role, RolePath,
+    RoleProgress,
+};
+use crate::runner::RunnerState;
+use crate::scheduler::{FsrsState, Grade, Scheduler, SchedulerKind, WaveScheduler};
+use crate::scripts::Script;
+use crate::streak::{compute_streaks, days_to_freeze, histogram, StreakSummary};
 use crate::telemetry::{EventBatch, TelemetryEvent};
+use crate::transitions::DEFAULT_LANGUAGE;
+
+/// Offsets accepted by [`Store::set_utc_offset_minutes`]
+const UTC_OFFSET_RANGE_MINUTES: std::ops::RangeInclusive<i32> = -12 * 60..=14 * 60;
+
+/// Tables that stay on the device: never exported, never restored
+const LOCAL_TABLES: &[&str] = &["signing_keys"];
 
 pub struct Store {
     conn: Connection,
 }
 
 impl Store {
+    /// Open (or create) the database and migrate it to the current schema
+    ///
+    /// Fails without modifying anything when the database was written by a
+    /// newer app version.
     pub fn open(path: &str) -> Result<Self> {
-        let conn = Connection::open(path)?;
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS events(
-              id INTEGER PRIMARY KEY,
-              ts TEXT NOT NULL,
-              kind TEXT NOT NULL,
-              payload TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS memory_links(
-              id INTEGER PRIMARY KEY,
-              phrase TEXT UNIQUE NOT NULL,
-              last_seen TEXT NOT NULL,
-              wave REAL NOT NULL,
-              decay_alpha REAL NOT NULL,
-              success_count INTEGER DEFAULT 0,
-              fail_count INTEGER DEFAULT 0,
-              use_in_wild_count INTEGER DEFAULT 0
-            );
-
-            CREATE TABLE IF NOT EXISTS sessions(
-              id INTEGER PRIMARY KEY,
-              script_id TEXT NOT NULL,
-              started_at TEXT NOT NULL,
-              completed_at TEXT,
-              progress REAL DEFAULT 0.0
-            );
-
-            CREATE TABLE IF NOT EXISTS role_progress(
-              id INTEGER PRIMARY KEY,
-              role_id TEXT UNIQUE NOT NULL,
-              current_scene_index INTEGER NOT NULL,
-              total_scenes INTEGER NOT NULL,
-              coherence REAL NOT NULL,
-              last_transition TEXT,
-              consecutive_days INTEGER DEFAULT 0,
-              created_at TEXT NOT NULL,
-              updated_at TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS emotion_tags(
-              id INTEGER PRIMARY KEY,
-              role_id TEXT NOT NULL,
-              scene_id TEXT NOT NULL,
-              tone TEXT NOT NULL,
-              confidence REAL NOT NULL,
-              timestamp TEXT NOT NULL,
-              FOREIGN KEY(role_id) REFERENCES role_progress(role_id)
-            );
-
-            CREATE TABLE IF NOT EXISTS resonance_traces(
-              id TEXT PRIMARY KEY,
-              role_id TEXT NOT NULL,
-              scene_id TEXT NOT NULL,
-              message TEXT NOT NULL,
-              created_at TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS reflections(
-              id INTEGER PRIMARY KEY,
-              trace_id TEXT NOT NULL,
-              message TEXT NOT NULL,
-              created_at TEXT NOT NULL,
-              FOREIGN KEY(trace_id) REFERENCES resonance_traces(id)
-            );
-
-            CREATE TABLE IF NOT EXISTS telemetry_events(
-              id TEXT PRIMARY KEY,
-              event_type TEXT NOT NULL,
-              timestamp TEXT NOT NULL,
-              session_id TEXT,
-              user_id TEXT,
-              properties TEXT NOT NULL,
-              context TEXT NOT NULL,
-              status TEXT NOT NULL DEFAULT 'pending'
-            );
-
-            CREATE TABLE IF NOT EXISTS telemetry_batches(
-              batch_id TEXT PRIMARY KEY,
-              created_at TEXT NOT NULL,
-              sent_at TEXT,
-              size_bytes INTEGER NOT NULL,
-              event_count INTEGER NOT NULL,
-              status TEXT NOT NULL DEFAULT 'pending'
-            );
-
-            CREATE TABLE IF NOT EXISTS subscriptions(
-              id TEXT PRIMARY KEY,
-              user_id TEXT NOT NULL,
-              tier TEXT NOT NULL,
-              status TEXT NOT NULL,
-              started_at TEXT NOT NULL,
-              expires_at TEXT,
-              cancelled_at TEXT,
-              platform TEXT NOT NULL,
-              transaction_id TEXT
-            );
-
-            CREATE TABLE IF NOT EXISTS purchases(
-              id TEXT PRIMARY KEY,
-              user_id TEXT NOT NULL,
-              product_id TEXT NOT NULL,
-              platform TEXT NOT NULL,
-              transaction_id TEXT NOT NULL UNIQUE,
-              purchased_at TEXT NOT NULL,
-              price_cents INTEGER NOT NULL,
-              currency TEXT NOT NULL,
-              verified INTEGER NOT NULL DEFAULT 0,
-              metadata TEXT NOT NULL
-            );
-
-            CREATE TABLE IF NOT EXISTS content_unlocks(
-              id TEXT PRIMARY KEY,
-              user_id TEXT NOT NULL,
-              content_type TEXT NOT NULL,
-              content_id TEXT NOT NULL,
-              unlocked_at TEXT NOT NULL,
-              purchase_id TEXT,
-              FOREIGN KEY(purchase_id) REFERENCES purchases(id)
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_events_ts ON events(ts);
-            CREATE INDEX IF NOT EXISTS idx_events_kind ON events(kind);
-            CREATE INDEX IF NOT EXISTS idx_memory_wave ON memory_links(wave);
-            CREATE INDEX IF NOT EXISTS idx_emotion_tags_role ON emotion_tags(role_id);
-            CREATE INDEX IF NOT EXISTS idx_resonance_traces_role ON resonance_traces(role_id);
-            CREATE INDEX IF NOT EXISTS idx_reflections_trace ON reflections(trace_id);
-            CREATE INDEX IF NOT EXISTS idx_telemetry_status ON telemetry_events(status);
-            CREATE INDEX IF NOT EXISTS idx_telemetry_timestamp ON telemetry_events(timestamp);
-            CREATE INDEX IF NOT EXISTS idx_batch_status ON telemetry_batches(status);
-            CREATE INDEX IF NOT EXISTS idx_subscriptions_user ON subscriptions(user_id);
-            CREATE INDEX IF NOT EXISTS idx_purchases_user ON purchases(user_id);
-            CREATE INDEX IF NOT EXISTS idx_content_unlocks_user ON content_unlocks(user_id);
-            CREATE INDEX IF NOT EXISTS idx_content_unlocks_content ON content_unlocks(content_type, content_id);
-        "#,
-        )?;
+        let mut conn = Connection::open(path)?;
+        migrations::migrate(&mut conn)?;
         Ok(Self { conn })
     }
 
+    /// `PRAGMA user_version` of the open database
+    pub fn schema_version(&self) -> Result<u32> {
+        migrations::user_version(&self.conn)
+    }
+
     // ========================================================================
     // Events
     // ========================================================================
@@ -203,16 +103,28 @@ impl Store {
     // ========================================================================
 
     pub fn save_memory_link(&self, link: &MemoryLink) -> Result<()> {
+        let fsrs = link.fsrs.as_ref();
         self.conn.execute(
             r#"
-            INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, success_count, fail_count, use_in_wild_count)
-            VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, success_count, fail_count, use_in_wild_count,
+                                     stability, difficulty, due_at, reps, lapses, script_id, step_index,
+                                     consecutive_fails, leech_since)
+            VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
             ON CONFLICT(phrase) DO UPDATE SET
               last_seen = excluded.last_seen,
               wave = excluded.wave,
               success_count = excluded.success_count,
               fail_count = excluded.fail_count,
-              use_in_wild_count = excluded.use_in_wild_count
+              use_in_wild_count = excluded.use_in_wild_count,
+              stability = excluded.stability,
+              difficulty = excluded.difficulty,
+              due_at = excluded.due_at,
+              reps = excluded.reps,
+              lapses = excluded.lapses,
+              script_id = COALESCE(memory_links.script_id, excluded.script_id),
+              step_index = COALESCE(memory_links.step_index, excluded.step_index),
+              consecutive_fails = excluded.consecutive_fails,
+              leech_since = excluded.leech_since
             "#,
             params![
                 link.phrase,
@@ -222,68 +134,294 @@ impl Store {
                 link.success_count,
                 link.fail_count,
                 link.use_in_wild_count,
+                fsrs.map(|f| f.stability),
+                fsrs.map(|f| f.difficulty),
+                fsrs.map(|f| f.due.to_rfc3339()),
+                fsrs.map_or(0, |f| f.reps),
+                fsrs.map_or(0, |f| f.lapses),
+                link.script_id,
+                link.step_index,
+                link.consecutive_fails,
+                link.leech_since.map(|dt| dt.to_rfc3339()),
             ],
         )?;
         Ok(())
     }
 
+    /// Save memory links for every phrase of `script` not yet practised
+    ///
+    /// Existing links keep their history. Returns how many links were added.
+    pub fn harvest_phrases(&self, script: &Script) -> Result<usize> {
+        let tx = self.conn.unchecked_transaction()?;
+        // A phrase already harvested from another script, even written
+        // differently, keeps its existing link
+        let mut known: HashSet<String> = tx
+            .prepare("SELECT phrase FROM memory_links")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|phrase| phrase.map(|p| phrase_key(&p)))
+            .collect::<Result<_, _>>()?;
+
+        let mut added = 0;
+        for phrase in extract_phrases(script) {
+            if !known.insert(phrase_key(&phrase.phrase)) {
+                continue;
+            }
+            let link = phrase.to_memory_link(script.rehearsal.decay_alpha);
+            added += tx.execute(
+                r#"
+                INSERT INTO memory_links(phrase, last_seen, wave, decay_alpha, script_id, step_index)
+                VALUES(?1, ?2, ?3, ?4, ?5, ?6)
+                ON CONFLICT(phrase) DO NOTHING
+                "#,
+                params![
+                    link.phrase,
+                    link.last_seen.to_rfc3339(),
+                    link.wave,
+                    link.decay_alpha,
+                    link.script_id,
+                    link.step_index,
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    pub fn get_memory_links_for_script(&self, script_id: &str) -> Result<Vec<MemoryLink>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM memory_links WHERE script_id = ?1 ORDER BY step_index, id",
+            MEMORY_LINK_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map(params![script_id], memory_link_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
     pub fn load_memory_link(&self, phrase: &str) -> Result<Option<MemoryLink>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT phrase, last_seen, wave, decay_alpha, success_count, fail_count, use_in_wild_count
-             FROM memory_links WHERE phrase = ?1",
-        )?;
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM memory_links WHERE phrase = ?1",
+            MEMORY_LINK_COLUMNS
+        ))?;
 
         let mut rows = stmt.query(params![phrase])?;
-        if let Some(row) = rows.next()? {
-            let last_seen_str: String = row.get(1)?;
-            let last_seen = DateTime::parse_from_rfc3339(&last_seen_str)?.with_timezone(&Utc);
-
-            Ok(Some(MemoryLink {
-                phrase: row.get(0)?,
-                last_seen,
-                wave: row.get(2)?,
-                decay_alpha: row.get(3)?,
-                success_count: row.get(4)?,
-                fail_count: row.get(5)?,
-                use_in_wild_count: row.get(6)?,
-            }))
-        } else {
-            Ok(None)
+        match rows.next()? {
+            Some(row) => Ok(Some(memory_link_from_row(row)?)),
+            None => Ok(None),
         }
     }
 
     pub fn get_all_memory_links(&self) -> Result<Vec<MemoryLink>> {
-        let mut stmt = self.conn.prepare(
-            "SELECT phrase, last_seen, wave, decay_alpha, success_count, fail_count, use_in_wild_count
-             FROM memory_links ORDER BY wave ASC",
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM memory_links ORDER BY wave ASC",
+            MEMORY_LINK_COLUMNS
+        ))?;
+
+        let rows = stmt.query_map([], memory_link_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Practice queue as of `now` under the learner's scheduler; phrases
+    /// reviewed earlier on the learner's local day count towards the daily
+    /// cap
+    pub fn review_queue(&self, now: DateTime<Utc>, config: &QueueConfig) -> Result<ReviewQueue> {
+        let links = self.get_all_memory_links()?;
+        let offset = self.get_utc_offset()?;
+        let today = now.with_timezone(&offset).date_naive();
+
+        let reviewed_today = links
+            .iter()
+            .filter(|l| l.success_count + l.fail_count + l.use_in_wild_count > 0)
+            .filter(|l| l.last_seen.with_timezone(&offset).date_naive() == today)
+            .count() as u32;
+
+        Ok(ReviewQueue::build(
+            &links,
+            self.scheduler()?.as_ref(),
+            now,
+            reviewed_today,
+            config,
+        ))
+    }
+
+    pub fn get_scheduler_kind(&self) -> Result<SchedulerKind> {
+        match self.get_setting("scheduler")? {
+            Some(kind) => Ok(serde_json::from_str(&kind)?),
+            None => Ok(SchedulerKind::default()),
+        }
+    }
+
+    pub fn set_scheduler_kind(&self, kind: SchedulerKind) -> Result<()> {
+        self.set_setting("scheduler", &serde_json::to_string(&kind)?)
+    }
+
+    /// The learner's scheduler, with the personal wave time constant applied
+    pub fn scheduler(&self) -> Result<Box<dyn Scheduler + Send + Sync>> {
+        Ok(match self.get_scheduler_kind()? {
+            SchedulerKind::Wave => Box::new(WaveScheduler {
+                time_constant_secs: self.get_wave_time_constant()?,
+                ..Default::default()
+            }),
+            kind => kind.build(),
+        })
+    }
+
+    /// Grade a review of `phrase` with the learner's scheduler and save it
+    ///
+    /// Every review is also appended to `review_log` for parameter fitting.
+    pub fn review_memory_link(
+        &self,
+        phrase: &str,
+        grade: Grade,
+        now: DateTime<Utc>,
+    ) -> Result<MemoryLink> {
+        let mut link = self
+            .load_memory_link(phrase)?
+            .ok_or_else(|| anyhow!("Memory link not found: {}", phrase))?;
+        let entry = ReviewLogEntry {
+            phrase: link.phrase.clone(),
+            reviewed_at: now,
+            grade,
+            recalled: grade != Grade::Again,
+            elapsed_secs: (now - link.last_seen).num_seconds().max(0),
+            wave_before: link.wave,
+            decay_alpha: link.decay_alpha,
+        };
+        let kind = self.get_scheduler_kind()?;
+
+        self.scheduler()?.review(&mut link, grade, now);
+        if grade == Grade::Again
+            && link.leech_since.is_none()
+            && self.get_leech_config()?.is_leech(&link)
+        {
+            link.leech_since = Some(now);
+        }
+        self.save_memory_link(&link)?;
+        self.log_review(&entry, kind)?;
+        Ok(link)
+    }
+
+    fn log_review(&self, entry: &ReviewLogEntry, kind: SchedulerKind) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO review_log
+                (phrase, reviewed_at, grade, recalled, elapsed_secs, wave_before, decay_alpha, scheduler)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                entry.phrase,
+                entry.reviewed_at.to_rfc3339(),
+                serde_json::to_value(entry.grade)?.as_str(),
+                entry.recalled,
+                entry.elapsed_secs,
+                entry.wave_before,
+                entry.decay_alpha,
+                serde_json::to_value(kind)?.as_str(),
+            ],
         )?;
+        Ok(())
+    }
 
-        let rows = stmt.query_map([], |row| {
-            let last_seen_str: String = row.get(1)?;
-            let last_seen = DateTime::parse_from_rfc3339(&last_seen_str)
-                .map_err(|e| {
+    /// Reviews logged under `scheduler`, oldest first
+    pub fn get_review_log(&self, scheduler: SchedulerKind) -> Result<Vec<ReviewLogEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT phrase, reviewed_at, grade, recalled, elapsed_secs, wave_before, decay_alpha
+             FROM review_log WHERE scheduler = ?1 ORDER BY reviewed_at, id",
+        )?;
+        let kind = serde_json::to_value(scheduler)?;
+        let rows = stmt.query_map(params![kind.as_str()], |row| {
+            let grade: String = row.get(2)?;
+            Ok(ReviewLogEntry {
+                phrase: row.get(0)?,
+                reviewed_at: parse_timestamp(1, &row.get::<_, String>(1)?)?,
+                grade: serde_json::from_str(&format!("\"{}\"", grade)).map_err(|e| {
                     rusqlite::Error::FromSqlConversionFailure(
-                        1,
+                        2,
                         rusqlite::types::Type::Text,
                         Box::new(e),
                     )
-                })?
-                .with_timezone(&Utc);
-
-            Ok(MemoryLink {
-                phrase: row.get(0)?,
-                last_seen,
-                wave: row.get(2)?,
-                decay_alpha: row.get(3)?,
-                success_count: row.get(4)?,
-                fail_count: row.get(5)?,
-                use_in_wild_count: row.get(6)?,
+                })?,
+                recalled: row.get(3)?,
+                elapsed_secs: row.get(4)?,
+                wave_before: row.get(5)?,
+                decay_alpha: row.get(6)?,
             })
         })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Personal wave time constant in seconds (defaults to the global one)
+    pub fn get_wave_time_constant(&self) -> Result<f32> {
+        Ok(self
+            .get_setting("wave_time_constant_secs")?
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or(DEFAULT_TIME_CONSTANT_SECS))
+    }
+
+    pub fn set_wave_time_constant(&self, secs: f32) -> Result<()> {
+        if !secs.is_finite() || secs <= 0.0 {
+            return Err(anyhow!("Invalid time constant: {}", secs));
+        }
+        self.set_setting("wave_time_constant_secs", &secs.to_string())
+    }
+
+    /// Fit the wave time constant to the learner's wave-scheduled reviews
+    ///
+    /// Returns `None` when there are too few reviews; with `apply` the
+    /// fitted constant becomes the learner's setting.
+    pub fn fit_personal_decay(&self, apply: bool) -> Result<Option<FitReport>> {
+        let entries = self.get_review_log(SchedulerKind::Wave)?;
+        let report = fit_time_constant(&entries, self.get_wave_time_constant()?);
+        if let (true, Some(report)) = (apply, &report) {
+            self.set_wave_time_constant(report.time_constant_secs)?;
+        }
+        Ok(report)
+    }
+
+    pub fn get_leech_config(&self) -> Result<LeechConfig> {
+        match self.get_setting("leech_config")? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(LeechConfig::default()),
+        }
+    }
+
+    pub fn set_leech_config(&self, config: &LeechConfig) -> Result<()> {
+        self.set_setting("leech_config", &serde_json::to_string(config)?)
+    }
+
+    pub fn get_coherence_config(&self) -> Result<CoherenceEngine> {
+        match self.get_setting("coherence_config")? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(CoherenceEngine::default()),
+        }
+    }
+
+    pub fn set_coherence_config(&self, config: &CoherenceEngine) -> Result<()> {
+        self.set_setting("coherence_config", &serde_json::to_string(config)?)
+    }
+
+    /// Phrases currently flagged as leeches, longest-standing first
+    pub fn get_leeches(&self) -> Result<Vec<MemoryLink>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM memory_links WHERE leech_since IS NOT NULL ORDER BY leech_since, id",
+            MEMORY_LINK_COLUMNS
+        ))?;
 
+        let rows = stmt.query_map([], memory_link_from_row)?;
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
+    /// Clear the leech flag after remediation; the phrase is flagged again
+    /// only if it keeps failing
+    pub fn resolve_leech(&self, phrase: &str) -> Result<()> {
+        let updated = self.conn.execute(
+            "UPDATE memory_links SET leech_since = NULL, consecutive_fails = 0 WHERE phrase = ?1",
+            params![phrase],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("Memory link not found: {}", phrase));
+        }
+        Ok(())
+    }
+
     // ========================================================================
     // Sessions
     // ========================================================================
@@ -298,27 +436,281 @@ impl Store {
     }
 
     pub fn complete_session(&self, session_id: i64, progress: f32) -> Result<()> {
-        let ts = Utc::now().to_rfc3339();
+        let now = Utc::now();
         self.conn.execute(
             "UPDATE sessions SET completed_at = ?1, progress = ?2 WHERE id = ?3",
-            params![ts, progress, session_id],
+            params![now.to_rfc3339(), progress, session_id],
         )?;
+        self.apply_streak_freezes(self.local_date(now)?)?;
         Ok(())
     }
 
+    /// Persist a runner, creating its session row on first save
+    ///
+    /// Sets `state.session_id` when a row is created. A completed runner
+    /// stamps `completed_at` once, which makes the session count for streaks.
+    pub fn save_runner_session(&self, state: &mut RunnerState, progress: f32) -> Result<i64> {
+        state.updated_at = Utc::now();
+
+        if let Some(id) = state.session_id {
+            let state_json = serde_json::to_string(state)?;
+            let updated = self.conn.execute(
+                r#"
+                UPDATE sessions SET
+                  current_index = ?1,
+                  progress = ?2,
+                  state = ?3,
+                  updated_at = ?4,
+                  completed_at = CASE WHEN ?5 THEN COALESCE(completed_at, ?4) ELSE NULL END
+                WHERE id = ?6
+                "#,
+                params![
+                    state.current_index as i64,
+                    progress,
+                    state_json,
+                    state.updated_at.to_rfc3339(),
+                    state.completed,
+                    id,
+                ],
+            )?;
+            if updated > 0 {
+                if state.completed {
+                    self.apply_streak_freezes(self.local_date(state.updated_at)?)?;
+                }
+                return Ok(id);
+            }
+        }
+
+        self.conn.execute(
+            "INSERT INTO sessions(script_id, started_at) VALUES(?1, ?2)",
+            params![state.script_id, state.started_at.to_rfc3339()],
+        )?;
+        state.session_id = Some(self.conn.last_insert_rowid());
+        self.save_runner_session(state, progress)
+    }
+
+    pub fn load_runner_session(&self, session_id: i64) -> Result<Option<RunnerState>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, state FROM sessions WHERE id = ?1 AND state IS NOT NULL")?;
+
+        let mut rows = stmt.query(params![session_id])?;
+        rows.next()?.map(runner_from_row).transpose()
+    }
+
+    /// Speaking quality of the latest completed run of a script that
+    /// recorded speak_check scores
+    pub fn get_speak_quality(&self, script_id: &str) -> Result<Option<f32>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT state FROM sessions
+             WHERE script_id = ?1 AND completed_at IS NOT NULL AND state IS NOT NULL
+             ORDER BY completed_at DESC, id DESC",
+        )?;
+        let states = stmt.query_map(params![script_id], |row| row.get::<_, String>(0))?;
+        for state_json in states {
+            let state: RunnerState = serde_json::from_str(&state_json?)?;
+            if let Some(quality) = state.speak_quality() {
+                return Ok(Some(quality));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Most recently touched unfinished runner, optionally for one script
+    pub fn get_resumable_session(&self, script_id: Option<&str>) -> Result<Option<RunnerState>> {
+        let mut query =
+            "SELECT id, state FROM sessions WHERE completed_at IS NULL AND state IS NOT NULL"
+                .to_string();
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+        if let Some(sid) = script_id {
+            query.push_str(" AND script_id = ?1");
+            params_vec.push(Box::new(sid.to_string()));
+        }
+        query.push_str(" ORDER BY updated_at DESC, id DESC LIMIT 1");
+
+        let mut stmt = self.conn.prepare(&query)?;
+        let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec
+            .iter()
+            .map(|p| p.as_ref() as &dyn rusqlite::ToSql)
+            .collect();
+
+        let mut rows = stmt.query(params_refs.as_slice())?;
+        rows.next()?.map(runner_from_row).transpose()
+    }
+
     // ========================================================================
     // Statistics
     // ========================================================================
 
+    /// Current streak in the learner's local calendar
     pub fn get_streak(&self) -> Result<u32> {
-        // Simple implementation: count consecutive days with completed sessions
-        // TODO: More sophisticated streak calculation
-        let count: u32 = self.conn.query_row(
-            "SELECT COUNT(DISTINCT DATE(started_at)) FROM sessions WHERE completed_at IS NOT NULL",
-            [],
-            |row| row.get(0),
+        Ok(self.get_streak_summary(0)?.current)
+    }
+
+    pub fn get_streak_summary(&self, histogram_days: u32) -> Result<StreakSummary> {
+        self.streak_summary_at(Utc::now(), histogram_days)
+    }
+
+    /// Streak summary as of `now`
+    ///
+    /// Read-only: missed days are only covered once freezes have been
+    /// spent through [`apply_streak_freezes`](Self::apply_streak_freezes).
+    pub fn streak_summary_at(
+        &self,
+        now: DateTime<Utc>,
+        histogram_days: u32,
+    ) -> Result<StreakSummary> {
+        let today = self.local_date(now)?;
+        let active = self.get_activity_days()?;
+        let frozen = self.get_frozen_days()?;
+        let (current, longest) = compute_streaks(&active, &frozen, today);
+
+        Ok(StreakSummary {
+            current,
+            longest,
+            active_today: active.contains_key(&today),
+            last_active_day: active.keys().next_back().copied(),
+            freeze_tokens: self.get_streak_freeze_tokens()?,
+            histogram: histogram(&active, &frozen, today, histogram_days),
+        })
+    }
+
+    /// Completed sessions per local calendar day
+    pub fn get_activity_days(&self) -> Result<BTreeMap<NaiveDate, u32>> {
+        let offset = self.get_utc_offset()?;
+        let mut stmt = self
+            .conn
+            .prepare("SELECT completed_at FROM sessions WHERE completed_at IS NOT NULL")?;
+
+        let mut days = BTreeMap::new();
+        for completed_at in stmt.query_map([], |row| row.get::<_, String>(0))? {
+            let local = DateTime::parse_from_rfc3339(&completed_at?)?.with_timezone(&offset);
+            *days.entry(local.date_naive()).or_insert(0) += 1;
+        }
+        Ok(days)
+    }
+
+    pub fn get_frozen_days(&self) -> Result<BTreeSet<NaiveDate>> {
+        let mut stmt = self.conn.prepare("SELECT day FROM streak_freezes")?;
+        let days = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|day| Ok(NaiveDate::parse_from_str(&day?, "%Y-%m-%d")?))
+            .collect::<Result<BTreeSet<_>>>()?;
+        Ok(days)
+    }
+
+    /// Learner's local calendar day at `now`
+    pub fn local_date(&self, now: DateTime<Utc>) -> Result<NaiveDate> {
+        Ok(now.with_timezone(&self.get_utc_offset()?).date_naive())
+    }
+
+    /// Spend tokens on the days missed since the last active day
+    ///
+    /// Called when a session completes, and by the app when it wants a
+    /// lapsed streak rescued before showing it. Returns the days that were
+    /// frozen (empty if none were needed or there weren't enough tokens to
+    /// save the streak).
+    pub fn apply_streak_freezes(&self, today: NaiveDate) -> Result<Vec<NaiveDate>> {
+        let tokens = self.get_streak_freeze_tokens()?;
+        if tokens == 0 {
+            return Ok(Vec::new());
+        }
+
+        let active = self.get_activity_days()?;
+        let frozen = self.get_frozen_days()?;
+        let gap = days_to_freeze(&active, &frozen, today, tokens);
+
+        let now = Utc::now().to_rfc3339();
+        for day in &gap {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO streak_freezes(day, applied_at) VALUES(?1, ?2)",
+                params![day.format("%Y-%m-%d").to_string(), now],
+            )?;
+        }
+        if !gap.is_empty() {
+            self.set_setting(
+                "streak_freeze_tokens",
+                &(tokens - gap.len() as u32).to_string(),
+            )?;
+        }
+
+        Ok(gap)
+    }
+
+    pub fn get_streak_freeze_tokens(&self) -> Result<u32> {
+        Ok(self
+            .get_setting("streak_freeze_tokens")?
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or(0))
+    }
+
+    pub fn add_streak_freeze_tokens(&self, count: u32) -> Result<u32> {
+        let balance = self.get_streak_freeze_tokens()? + count;
+        self.set_setting("streak_freeze_tokens", &balance.to_string())?;
+        Ok(balance)
+    }
+
+    // ========================================================================
+    // Settings
+    // ========================================================================
+
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT value FROM settings WHERE key = ?1")?;
+        let mut rows = stmt.query(params![key])?;
+        if let Some(row) = rows.next()? {
+            Ok(Some(row.get(0)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO settings(key, value) VALUES(?1, ?2)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
         )?;
-        Ok(count)
+        Ok(())
+    }
+
+    /// Learner's time zone as a fixed UTC offset (defaults to UTC)
+    pub fn get_utc_offset(&self) -> Result<FixedOffset> {
+        let minutes: i32 = self
+            .get_setting("utc_offset_minutes")?
+            .map(|v| v.parse())
+            .transpose()?
+            .unwrap_or(0);
+        minutes
+            .checked_mul(60)
+            .and_then(FixedOffset::east_opt)
+            .ok_or_else(|| anyhow!("Invalid UTC offset: {} minutes", minutes))
+    }
+
+    /// Store the learner's offset; real zones span UTC-12:00 to UTC+14:00
+    pub fn set_utc_offset_minutes(&self, minutes: i32) -> Result<()> {
+        if !UTC_OFFSET_RANGE_MINUTES.contains(&minutes) {
+            return Err(anyhow!("Invalid UTC offset: {} minutes", minutes));
+        }
+        self.set_setting("utc_offset_minutes", &minutes.to_string())
+    }
+
+    /// Learner's native language (e.g. `ru`), used to localize transitions
+    pub fn get_native_language(&self) -> Result<String> {
+        Ok(self
+            .get_setting("native_language")?
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string()))
+    }
+
+    pub fn set_native_language(&self, language: &str) -> Result<()> {
+        let language = language.trim();
+        if language.is_empty() {
+            return Err(anyhow!("Native language must not be empty"));
+        }
+        self.set_setting("native_language", language)
     }
 
     pub fn get_use_in_wild_count(&self) -> Result<u32> {
@@ -333,17 +725,180 @@ impl Store {
         Ok(count)
     }
 
+    /// Real-world uses of phrases harvested from `script_ids`
+    pub fn get_use_in_wild_count_for_scripts(&self, script_ids: &[String]) -> Result<u32> {
+        if script_ids.is_empty() {
+            return Ok(0);
+        }
+        let placeholders = (1..=script_ids.len())
+            .map(|i| format!("?{}", i))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let count: Option<u32> = self.conn.query_row(
+            &format!(
+                "SELECT SUM(use_in_wild_count) FROM memory_links WHERE script_id IN ({})",
+                placeholders
+            ),
+            rusqlite::params_from_iter(script_ids),
+            |row| row.get(0),
+        )?;
+        Ok(count.unwrap_or(0))
+    }
+
     pub fn export_json(&self) -> Result<String> {
-        let events = self.get_events(None, 1000)?;
-        let links = self.get_all_memory_links()?;
+        Ok(serde_json::to_string_pretty(&self.export_snapshot()?)?)
+    }
+
+    // ========================================================================
+    // Backup / Restore
+    // ========================================================================
+
+    /// Tables that backups carry; device-local tables are left out
+    fn table_names(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let names = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?
+            .into_iter()
+            .filter(|name| !LOCAL_TABLES.contains(&name.as_str()))
+            .collect();
+        Ok(names)
+    }
+
+    /// Every row of every table, tagged with the schema version
+    pub fn export_snapshot(&self) -> Result<Snapshot> {
+        let mut tables = BTreeMap::new();
+
+        for table in self.table_names()? {
+            let mut stmt = self
+                .conn
+                .prepare(&format!("SELECT * FROM \"{}\" ORDER BY rowid", table))?;
+            let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+
+            let mut rows = stmt.query([])?;
+            let mut out = Vec::new();
+            while let Some(row) = rows.next()? {
+                let mut record = serde_json::Map::new();
+                for (i, column) in columns.iter().enumerate() {
+                    record.insert(
+                        column.clone(),
+                        sql_to_json(row.get_ref(i)?, &table, column)?,
+                    );
+                }
+                out.push(record);
+            }
+            tables.insert(table, out);
+        }
+
+        Ok(Snapshot {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            schema_version: self.schema_version()?,
+            exported_at: Utc::now(),
+            tables,
+        })
+    }
+
+    pub fn import_json(&self, json: &str, mode: ImportMode) -> Result<ImportSummary> {
+        let snapshot: Snapshot = serde_json::from_str(json)?;
+        self.import_snapshot(&snapshot, mode)
+    }
+
+    /// Restore a snapshot in a single transaction
+    ///
+    /// `Replace` empties every table in the snapshot first. `Merge` keeps
+    /// existing rows and skips snapshot rows whose natural key is already
+    /// present. Rowid keys are local to each device, so in `Merge` they are
+    /// reassigned and a row is a duplicate only if every other column
+    /// matches. Device-local tables are never touched.
+    pub fn import_snapshot(&self, snapshot: &Snapshot, mode: ImportMode) -> Result<ImportSummary> {
+        if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+            return Err(anyhow!(
+                "Backup format {} is newer than supported format {}",
+                snapshot.format_version,
+                SNAPSHOT_FORMAT_VERSION
+            ));
+        }
+        if snapshot.schema_version > migrations::SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Backup schema version {} is newer than supported version {}; update the app",
+                snapshot.schema_version,
+                migrations::SCHEMA_VERSION
+            ));
+        }
 
-        let export = serde_json::json!({
-            "events": events,
-            "memory_links": links,
-            "exported_at": Utc::now().to_rfc3339(),
-        });
+        let known = self.table_names()?;
+        if let Some(unknown) = snapshot.tables.keys().find(|t| !known.contains(t)) {
+            return Err(anyhow!("Backup contains unknown table: {}", unknown));
+        }
+
+        let tx = self.conn.unchecked_transaction()?;
+        // Tables are restored in name order; check references at commit
+        tx.pragma_update(None, "defer_foreign_keys", true)?;
+        let mut summary = ImportSummary::default();
+
+        for (table, rows) in &snapshot.tables {
+            if mode == ImportMode::Replace {
+                tx.execute(&format!("DELETE FROM \"{}\"", table), [])?;
+            }
+
+            let rowid_key = match mode {
+                ImportMode::Merge => rowid_key(&tx, table)?,
+                ImportMode::Replace => None,
+            };
+
+            // Existing rows per column set, read once per table
+            let mut existing: HashMap<Vec<String>, HashSet<String>> = HashMap::new();
+            let mut imported = 0;
+            for record in rows {
+                // Backups from before `signing_keys` carry the seed here
+                if table == "settings"
+                    && record.get("key").and_then(|k| k.as_str()) == Some("certificate_signing_key")
+                {
+                    continue;
+                }
+                let fields: Vec<(&String, &serde_json::Value)> = record
+                    .iter()
+                    .filter(|(column, _)| Some(*column) != rowid_key.as_ref())
+                    .collect();
+                let columns: Vec<&String> = fields.iter().map(|(column, _)| *column).collect();
+                if rowid_key.is_some() {
+                    let set: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+                    let seen = match existing.entry(set) {
+                        Entry::Occupied(entry) => entry.into_mut(),
+                        Entry::Vacant(entry) => entry.insert(row_keys(&tx, table, &columns)?),
+                    };
+                    if !seen.insert(row_key(fields.iter().map(|(_, value)| (*value).clone()))) {
+                        continue;
+                    }
+                }
+                let values: Vec<Value> =
+                    fields.iter().map(|(_, value)| json_to_sql(value)).collect();
+
+                let sql = format!(
+                    "INSERT OR IGNORE INTO \"{}\"({}) VALUES({})",
+                    table,
+                    columns
+                        .iter()
+                        .map(|c| format!("\"{}\"", c))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    (1..=columns.len())
+                        .map(|i| format!("?{}", i))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+                imported += tx.execute(&sql, rusqlite::params_from_iter(values))?;
+            }
 
-        Ok(serde_json::to_string_pretty(&export)?)
+            summary.rows_imported += imported;
+            summary.rows_skipped += rows.len() - imported;
+            summary.tables.insert(table.clone(), imported);
+        }
+
+        tx.commit()?;
+        Ok(summary)
     }
 
     // ========================================================================
@@ -387,6 +942,22 @@ impl Store {
             self.save_emotion_tag(&progress.role_id, emotion)?;
         }
 
+        self.conn.execute(
+            "DELETE FROM coherence_history WHERE role_id = ?1",
+            params![progress.role_id],
+        )?;
+        for snapshot in &progress.coherence_history {
+            self.conn.execute(
+                "INSERT INTO coherence_history(role_id, scene_index, coherence, recorded_at) VALUES(?1, ?2, ?3, ?4)",
+                params![
+                    progress.role_id,
+                    snapshot.scene_index as i64,
+                    snapshot.coherence,
+                    snapshot.recorded_at.to_rfc3339(),
+                ],
+            )?;
+        }
+
         Ok(())
     }
 
@@ -410,6 +981,7 @@ impl Store {
 
             // Load emotion tags
             let emotion_tags = self.load_emotion_tags(role_id)?;
+            let coherence_history = self.load_coherence_history(role_id)?;
 
             Ok(Some(RoleProgress {
                 role_id: row.get(0)?,
@@ -421,55 +993,208 @@ impl Store {
                 created_at,
                 updated_at,
                 emotion_tags,
+                coherence_history,
             }))
         } else {
             Ok(None)
         }
     }
 
-    fn save_emotion_tag(&self, role_id: &str, emotion: &EmotionTag) -> Result<()> {
+    /// Key that signs completion certificates, created on first use
+    ///
+    /// The seed lives in `signing_keys`, which never goes into a backup, so
+    /// each device signs with its own key. Certificates carry their public
+    /// key and stay verifiable after a restore.
+    pub fn certificate_signer(&self) -> Result<CertificateSigner> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT seed FROM signing_keys WHERE name = 'certificate'")?;
+        if let Some(row) = stmt.query([])?.next()? {
+            return CertificateSigner::from_hex(&row.get::<_, String>(0)?);
+        }
+        let signer = CertificateSigner::generate()?;
         self.conn.execute(
-            "INSERT INTO emotion_tags(role_id, scene_id, tone, confidence, timestamp) VALUES(?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO signing_keys(name, seed) VALUES('certificate', ?1)",
+            params![signer.seed_hex()],
+        )?;
+        Ok(signer)
+    }
+
+    pub fn save_certificate(&self, certificate: &SignedCertificate) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO certificates(id, role_id, issued_at, certificate) VALUES(?1, ?2, ?3, ?4)",
             params![
-                role_id,
-                emotion.scene_id,
-                emotion.tone,
-                emotion.confidence,
-                emotion.timestamp.to_rfc3339(),
+                certificate.record.certificate_id,
+                certificate.record.role_id,
+                certificate.record.issued_at.to_rfc3339(),
+                serde_json::to_string(certificate)?,
             ],
         )?;
         Ok(())
     }
 
-    fn load_emotion_tags(&self, role_id: &str) -> Result<Vec<EmotionTag>> {
+    /// Issued certificates, newest first, optionally for one role
+    pub fn get_certificates(&self, role_id: Option<&str>) -> Result<Vec<SignedCertificate>> {
         let mut stmt = self.conn.prepare(
-            "SELECT scene_id, tone, confidence, timestamp FROM emotion_tags WHERE role_id = ?1 ORDER BY timestamp ASC",
+            "SELECT certificate FROM certificates WHERE ?1 IS NULL OR role_id = ?1 ORDER BY issued_at DESC",
         )?;
-
-        let rows = stmt.query_map(params![role_id], |row| {
-            let timestamp_str: String = row.get(3)?;
-            let timestamp = DateTime::parse_from_rfc3339(&timestamp_str)
-                .map_err(|e| {
-                    rusqlite::Error::FromSqlConversionFailure(
-                        3,
-                        rusqlite::types::Type::Text,
-                        Box::new(e),
-                    )
-                })?
-                .with_timezone(&Utc);
-
-            Ok(EmotionTag {
-                scene_id: row.get(0)?,
-                tone: row.get(1)?,
-                confidence: row.get(2)?,
-                timestamp,
-            })
-        })?;
-
-        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+        let rows = stmt.query_map(params![role_id], |row| row.get::<_, String>(0))?;
+        rows.map(|json| Ok(serde_json::from_str(&json?)?)).collect()
     }
 
-    // ========================================================================
+    /// Everything the recommender needs about `user_id`
+    ///
+    /// `roles` supplies each role's access level for entitlement checks.
+    pub fn learner_state(
+        &self,
+        user_id: &str,
+        roles: &HashMap<String, Role>,
+    ) -> Result<LearnerState> {
+        let mut progress = Vec::new();
+        for role_id in self.get_role_coherences()?.keys() {
+            progress.extend(self.load_role_progress(role_id)?);
+        }
+        progress.sort_by(|a, b| a.role_id.cmp(&b.role_id));
+
+        let mut accessible_roles = BTreeSet::new();
+        for role in roles.values() {
+            let entitlement = self.check_entitlement(
+                user_id,
+                &role.access_level,
+                Some(&ContentType::Role),
+                Some(&role.id),
+            )?;
+            if entitlement.has_access {
+                accessible_roles.insert(role.id.clone());
+            }
+        }
+
+        Ok(LearnerState {
+            progress,
+            emotion_history: self.get_emotion_history(None)?,
+            memory_links: self.get_all_memory_links()?,
+            accessible_roles,
+            unlocked_roles: self.get_unlocked_roles()?,
+        })
+    }
+
+    /// Stored coherence of every started role
+    pub fn get_role_coherences(&self) -> Result<HashMap<String, f32>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT role_id, coherence FROM role_progress")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Record that a path opened a role; returns false if it was already open
+    pub fn unlock_role(&self, role_id: &str, path_id: &str) -> Result<bool> {
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO role_unlocks(role_id, path_id, unlocked_at) VALUES(?1, ?2, ?3)",
+            params![role_id, path_id, Utc::now().to_rfc3339()],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    pub fn get_unlocked_roles(&self) -> Result<BTreeSet<String>> {
+        let mut stmt = self.conn.prepare("SELECT role_id FROM role_unlocks")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<BTreeSet<_>, _>>().map_err(Into::into)
+    }
+
+    /// Open the next role of each path once `progress` is transition ready
+    ///
+    /// Returns the newly unlocked role ids and logs a `role_unlocked` event
+    /// for each.
+    pub fn unlock_next_roles(
+        &self,
+        paths: &[RolePath],
+        progress: &RoleProgress,
+    ) -> Result<Vec<String>> {
+        if !progress.is_transition_ready() {
+            return Ok(Vec::new());
+        }
+
+        let coherences = self.get_role_coherences()?;
+        let mut unlocked = Vec::new();
+        for path in paths {
+            let Some(next) = path.role_to_unlock(&progress.role_id, &coherences) else {
+                continue;
+            };
+            if self.unlock_role(next, &path.id)? {
+                self.add_event(
+                    "role_unlocked",
+                    &serde_json::json!({ "role_id": next, "path_id": path.id }).to_string(),
+                )?;
+                unlocked.push(next.to_string());
+            }
+        }
+        Ok(unlocked)
+    }
+
+    pub fn get_path_progress(&self, path: &RolePath) -> Result<PathProgress> {
+        Ok(path.progress_for(&self.get_role_coherences()?, &self.get_unlocked_roles()?))
+    }
+
+    fn save_emotion_tag(&self, role_id: &str, emotion: &EmotionTag) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO emotion_tags(role_id, scene_id, tone, confidence, timestamp) VALUES(?1, ?2, ?3, ?4, ?5)",
+            params![
+                role_id,
+                emotion.scene_id,
+                emotion.tone.as_str(),
+                emotion.confidence,
+                emotion.timestamp.to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn load_emotion_tags(&self, role_id: &str) -> Result<Vec<EmotionTag>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT scene_id, tone, confidence, timestamp FROM emotion_tags WHERE role_id = ?1 ORDER BY timestamp ASC",
+        )?;
+
+        let rows = stmt.query_map(params![role_id], emotion_tag_from_row)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    fn load_coherence_history(&self, role_id: &str) -> Result<Vec<CoherenceSnapshot>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT scene_index, coherence, recorded_at FROM coherence_history WHERE role_id = ?1 ORDER BY id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![role_id], |row| {
+            let recorded_at: String = row.get(2)?;
+            Ok((row.get::<_, i64>(0)?, row.get(1)?, recorded_at))
+        })?;
+        rows.map(|row| {
+            let (scene_index, coherence, recorded_at) = row?;
+            Ok(CoherenceSnapshot {
+                scene_index: scene_index as usize,
+                coherence,
+                recorded_at: DateTime::parse_from_rfc3339(&recorded_at)?.with_timezone(&Utc),
+            })
+        })
+        .collect()
+    }
+
+    /// `(role_id, tag)` for every stored emotion tag, oldest first,
+    /// optionally for one role
+    pub fn get_emotion_history(&self, role_id: Option<&str>) -> Result<Vec<(String, EmotionTag)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT scene_id, tone, confidence, timestamp, role_id FROM emotion_tags
+             WHERE ?1 IS NULL OR role_id = ?1 ORDER BY timestamp ASC, id ASC",
+        )?;
+
+        let rows = stmt.query_map(params![role_id], |row| {
+            Ok((row.get(4)?, emotion_tag_from_row(row)?))
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    // ========================================================================
     // v1.1: Social Resonance
     // ========================================================================
 
@@ -1085,6 +1810,182 @@ impl Store {
     }
 }
 
+const MEMORY_LINK_COLUMNS: &str = "phrase, last_seen, wave, decay_alpha, success_count, fail_count, use_in_wild_count, stability, difficulty, due_at, reps, lapses, script_id, step_index, consecutive_fails, leech_since";
+
+fn parse_timestamp(idx: usize, value: &str) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+        })
+}
+
+/// Columns: scene_id, tone, confidence, timestamp
+fn emotion_tag_from_row(row: &rusqlite::Row) -> rusqlite::Result<EmotionTag> {
+    let timestamp_str: String = row.get(3)?;
+    let timestamp = DateTime::parse_from_rfc3339(&timestamp_str)
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?
+        .with_timezone(&Utc);
+
+    Ok(EmotionTag {
+        scene_id: row.get(0)?,
+        tone: Tone::parse(&row.get::<_, String>(1)?),
+        confidence: row.get(2)?,
+        timestamp,
+    })
+}
+
+fn memory_link_from_row(row: &rusqlite::Row) -> rusqlite::Result<MemoryLink> {
+    let last_seen: String = row.get(1)?;
+    let stability: Option<f32> = row.get(7)?;
+    let difficulty: Option<f32> = row.get(8)?;
+    let due_at: Option<String> = row.get(9)?;
+    let leech_since: Option<String> = row.get(15)?;
+
+    let fsrs = match (stability, difficulty, due_at) {
+        (Some(stability), Some(difficulty), Some(due_at)) => Some(FsrsState {
+            stability,
+            difficulty,
+            due: parse_timestamp(9, &due_at)?,
+            reps: row.get(10)?,
+            lapses: row.get(11)?,
+        }),
+        _ => None,
+    };
+
+    Ok(MemoryLink {
+        phrase: row.get(0)?,
+        last_seen: parse_timestamp(1, &last_seen)?,
+        wave: row.get(2)?,
+        decay_alpha: row.get(3)?,
+        success_count: row.get(4)?,
+        fail_count: row.get(5)?,
+        use_in_wild_count: row.get(6)?,
+        script_id: row.get(12)?,
+        step_index: row.get(13)?,
+        consecutive_fails: row.get(14)?,
+        leech_since: leech_since.map(|ts| parse_timestamp(15, &ts)).transpose()?,
+        fsrs,
+    })
+}
+
+/// Runner from an `(id, state)` row; the row id wins over the one in the
+/// state JSON, which is stale after a merged import
+fn runner_from_row(row: &rusqlite::Row) -> Result<RunnerState> {
+    let mut state: RunnerState = serde_json::from_str(&row.get::<_, String>(1)?)?;
+    state.session_id = Some(row.get(0)?);
+    Ok(state)
+}
+
+/// Bumped when the shape of [`Snapshot`] itself changes
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Full backup of the database
+///
+/// Rows are kept as column-name maps so a backup taken at an older schema
+/// version can be restored after newer migrations have run.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub format_version: u32,
+    pub schema_version: u32,
+    pub exported_at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub tables: BTreeMap<String, Vec<serde_json::Map<String, serde_json::Value>>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportMode {
+    Merge,
+    Replace,
+}
+
+#[derive(Debug, Default, serde::Serialize)]
+pub struct ImportSummary {
+    pub rows_imported: usize,
+    pub rows_skipped: usize,
+    /// Rows imported per table
+    pub tables: BTreeMap<String, usize>,
+}
+
+/// `INTEGER PRIMARY KEY` column of `table`, if its key is a plain rowid
+fn rowid_key(conn: &Connection, table: &str) -> Result<Option<String>> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(\"{}\")", table))?;
+    let keys = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })?
+        .filter(|column| !matches!(column, Ok((_, _, 0))))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(match keys.as_slice() {
+        [(name, kind, _)] if kind.eq_ignore_ascii_case("INTEGER") => Some(name.clone()),
+        _ => None,
+    })
+}
+
+/// Every row of `table` as a [`row_key`] over `columns`
+///
+/// Read once per table so a merge finds duplicates without scanning the
+/// table for each imported row.
+fn row_keys(conn: &Connection, table: &str, columns: &[&String]) -> Result<HashSet<String>> {
+    let select = if columns.is_empty() {
+        "1".to_string()
+    } else {
+        columns
+            .iter()
+            .map(|c| format!("\"{}\"", c))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM \"{}\"", select, table))?;
+    let mut rows = stmt.query([])?;
+    let mut keys = HashSet::new();
+    while let Some(row) = rows.next()? {
+        let values = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| sql_to_json(row.get_ref(i)?, table, column))
+            .collect::<Result<Vec<_>>>()?;
+        keys.insert(row_key(values.into_iter()));
+    }
+    Ok(keys)
+}
+
+/// Column values of one row in a form that compares equal across export
+/// and import
+fn row_key(values: impl Iterator<Item = serde_json::Value>) -> String {
+    serde_json::Value::Array(values.collect()).to_string()
+}
+
+fn sql_to_json(value: ValueRef, table: &str, column: &str) -> Result<serde_json::Value> {
+    Ok(match value {
+        ValueRef::Null => serde_json::Value::Null,
+        ValueRef::Integer(n) => n.into(),
+        ValueRef::Real(f) => f.into(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+        ValueRef::Blob(_) => return Err(anyhow!("Unsupported BLOB in {}.{}", table, column)),
+    })
+}
+
+fn json_to_sql(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
 #[derive(Debug, serde::Serialize)]
 pub struct TelemetryStats {
     pub pending_events: usize,
@@ -1096,6 +1997,7 @@ pub struct TelemetryStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::roles::PathRoleStatus;
 
     #[test]
     fn test_store_open() {
@@ -1117,6 +2019,470 @@ mod tests {
         assert_eq!(loaded.decay_alpha, 0.8);
     }
 
+    #[test]
+    fn test_review_memory_link_with_fsrs_persists_state() {
+        let store = Store::open(":memory:").unwrap();
+        store
+            .save_memory_link(&MemoryLink::new("to go, please".to_string(), 0.8))
+            .unwrap();
+        store.set_scheduler_kind(SchedulerKind::Fsrs).unwrap();
+
+        let now = Utc::now();
+        let reviewed = store
+            .review_memory_link("to go, please", Grade::Good, now)
+            .unwrap();
+        let loaded = store.load_memory_link("to go, please").unwrap().unwrap();
+
+        let state = loaded.fsrs.unwrap();
+        assert_eq!(Some(state.clone()), reviewed.fsrs);
+        assert_eq!(state.reps, 1);
+        assert!(state.due > now);
+        assert_eq!(loaded.success_count, 1);
+
+        assert!(store
+            .review_memory_link("missing", Grade::Good, now)
+            .is_err());
+    }
+
+    #[test]
+    fn test_harvest_phrases_keeps_existing_history() {
+        let store = Store::open(":memory:").unwrap();
+        let script: Script = serde_yaml::from_str(
+            "id: cafe\ntitle: t\nsteps:\n  - type: listen\n    prompt: a\n    content: \"Can I get a latte to go? For here, please.\"\nrehearsal:\n  decay_alpha: 0.88\n",
+        )
+        .unwrap();
+
+        assert_eq!(store.harvest_phrases(&script).unwrap(), 2);
+        let mut link = store.load_memory_link("For here, please").unwrap().unwrap();
+        assert_eq!(link.decay_alpha, 0.88);
+        assert_eq!(link.script_id.as_deref(), Some("cafe"));
+        assert_eq!(link.step_index, Some(0));
+
+        link.reinforce(Utc::now(), 0.1);
+        store.save_memory_link(&link).unwrap();
+
+        // Completing the scenario again adds nothing and keeps the review history
+        assert_eq!(store.harvest_phrases(&script).unwrap(), 0);
+        let again = store.load_memory_link("For here, please").unwrap().unwrap();
+        assert_eq!(again.success_count, 1);
+        assert_eq!(store.get_memory_links_for_script("cafe").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_harvest_dedupes_across_scripts() {
+        let store = Store::open(":memory:").unwrap();
+        let script = |id: &str, content: &str| -> Script {
+            serde_yaml::from_str(&format!(
+                "id: {}\ntitle: t\nsteps:\n  - type: listen\n    prompt: a\n    content: \"{}\"\n",
+                id, content
+            ))
+            .unwrap()
+        };
+
+        let cafe = script("cafe", "Can I get a latte to go? A café au lait, please.");
+        let kiosk = script("kiosk", "can I get a LATTE to go! Can't wait for lunch.");
+        assert_eq!(store.harvest_phrases(&cafe).unwrap(), 2);
+        assert_eq!(store.harvest_phrases(&kiosk).unwrap(), 1);
+
+        let latte = store.get_memory_links_for_script("cafe").unwrap();
+        assert_eq!(latte.len(), 2);
+        assert!(latte.iter().any(|l| l.phrase == "A café au lait, please"));
+        assert_eq!(
+            store.get_memory_links_for_script("kiosk").unwrap()[0].phrase,
+            "Can't wait for lunch"
+        );
+    }
+
+    #[test]
+    fn test_review_queue_counts_todays_reviews() {
+        let store = Store::open(":memory:").unwrap();
+        let now = utc("2025-03-10T12:00:00Z");
+
+        for (phrase, last_seen, reviewed) in [
+            ("reviewed this morning", "2025-03-10T07:00:00Z", true),
+            ("reviewed last week", "2025-03-03T07:00:00Z", true),
+            ("just harvested", "2025-03-10T07:00:00Z", false),
+        ] {
+            let mut link = MemoryLink::new(phrase.to_string(), 0.8);
+            link.last_seen = utc(last_seen);
+            link.wave = 0.2;
+            link.success_count = u32::from(reviewed);
+            store.save_memory_link(&link).unwrap();
+        }
+
+        let config = QueueConfig {
+            daily_cap: 2,
+            ..Default::default()
+        };
+        let queue = store.review_queue(now, &config).unwrap();
+
+        assert_eq!(queue.due_count(), 1);
+        assert_eq!(queue.deferred, 2);
+        assert_eq!(queue.next().unwrap().phrase, "reviewed last week");
+    }
+
+    #[test]
+    fn test_failed_reviews_flag_and_resolve_leech() {
+        let store = Store::open(":memory:").unwrap();
+        store
+            .save_memory_link(&MemoryLink::new("could you elaborate".to_string(), 0.8))
+            .unwrap();
+
+        let now = Utc::now();
+        for _ in 0..2 {
+            store
+                .review_memory_link("could you elaborate", Grade::Again, now)
+                .unwrap();
+        }
+        assert!(store.get_leeches().unwrap().is_empty());
+
+        let link = store
+            .review_memory_link("could you elaborate", Grade::Again, now)
+            .unwrap();
+        assert_eq!(link.consecutive_fails, 3);
+        let leeches = store.get_leeches().unwrap();
+        assert_eq!(leeches.len(), 1);
+        assert!(leeches[0].leech_since.is_some());
+
+        store.resolve_leech("could you elaborate").unwrap();
+        assert!(store.get_leeches().unwrap().is_empty());
+        let link = store
+            .load_memory_link("could you elaborate")
+            .unwrap()
+            .unwrap();
+        assert_eq!(link.consecutive_fails, 0);
+        assert_eq!(link.fail_count, 3);
+
+        assert!(store.resolve_leech("missing").is_err());
+    }
+
+    #[test]
+    fn test_reviews_are_logged_and_fitted() {
+        let store = Store::open(":memory:").unwrap();
+        let start = Utc::now() - chrono::Duration::days(10);
+        let mut link = MemoryLink::new("to go, please".to_string(), 0.8);
+        link.last_seen = start;
+        store.save_memory_link(&link).unwrap();
+
+        // Recalled after short gaps, forgotten after long ones: decay is
+        // much slower than the default ten-minute time constant suggests
+        let mut at = start;
+        for i in 0..30 {
+            let gap = if i % 3 == 2 { 36 } else { 2 };
+            at += chrono::Duration::hours(gap);
+            let grade = if gap > 2 { Grade::Again } else { Grade::Good };
+            store
+                .review_memory_link("to go, please", grade, at)
+                .unwrap();
+        }
+
+        let log = store.get_review_log(SchedulerKind::Wave).unwrap();
+        assert_eq!(log.len(), 30);
+        assert_eq!(log[0].elapsed_secs, 2 * 3600);
+        assert!(log[0].recalled);
+        assert_eq!(log[2].grade, Grade::Again);
+        assert!(store
+            .get_review_log(SchedulerKind::Fsrs)
+            .unwrap()
+            .is_empty());
+
+        let report = store.fit_personal_decay(false).unwrap().unwrap();
+        assert_eq!(report.samples, 30);
+        assert!(report.time_constant_secs > DEFAULT_TIME_CONSTANT_SECS);
+        assert!(report.log_loss_after < report.log_loss_before);
+        assert_eq!(
+            store.get_wave_time_constant().unwrap(),
+            DEFAULT_TIME_CONSTANT_SECS
+        );
+
+        store.fit_personal_decay(true).unwrap();
+        assert_eq!(
+            store.get_wave_time_constant().unwrap(),
+            report.time_constant_secs
+        );
+        assert!(store.set_wave_time_constant(0.0).is_err());
+    }
+
+    fn runner_for(script_id: &str) -> RunnerState {
+        let script: crate::scripts::Script = serde_yaml::from_str(&format!(
+            "id: {}\ntitle: t\nsteps:\n  - type: listen\n    prompt: a\n  - type: listen\n    prompt: b\n",
+            script_id
+        ))
+        .unwrap();
+        RunnerState::new(&script)
+    }
+
+    #[test]
+    fn test_runner_session_save_resume() {
+        let store = Store::open(":memory:").unwrap();
+        let mut state = runner_for("cafe-to-go-01");
+
+        let id = store.save_runner_session(&mut state, 0.5).unwrap();
+        assert_eq!(state.session_id, Some(id));
+
+        state.current_index = 1;
+        state.hints_revealed.push(1);
+        assert_eq!(store.save_runner_session(&mut state, 1.0).unwrap(), id);
+
+        let resumed = store.get_resumable_session(None).unwrap().unwrap();
+        assert_eq!(resumed.session_id, Some(id));
+        assert_eq!(resumed.current_index, 1);
+        assert_eq!(resumed.hints_revealed, vec![1]);
+
+        assert!(store
+            .get_resumable_session(Some("other-script"))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_completed_runner_session_counts_for_streak() {
+        let store = Store::open(":memory:").unwrap();
+        let mut state = runner_for("cafe-to-go-01");
+        store.save_runner_session(&mut state, 0.5).unwrap();
+        assert_eq!(store.get_streak().unwrap(), 0);
+
+        state.completed = true;
+        store.save_runner_session(&mut state, 1.0).unwrap();
+
+        assert_eq!(store.get_streak().unwrap(), 1);
+        assert!(store.get_resumable_session(None).unwrap().is_none());
+        let loaded = store
+            .load_runner_session(state.session_id.unwrap())
+            .unwrap()
+            .unwrap();
+        assert!(loaded.completed);
+    }
+
+    #[test]
+    fn test_reset_runner_keeps_completed_session() {
+        let store = Store::open(":memory:").unwrap();
+        let mut state = runner_for("cafe-to-go-01");
+        state.completed = true;
+        let finished = store.save_runner_session(&mut state, 1.0).unwrap();
+
+        state.reset();
+        let restarted = store.save_runner_session(&mut state, 0.0).unwrap();
+
+        assert_ne!(restarted, finished);
+        assert!(
+            store
+                .load_runner_session(finished)
+                .unwrap()
+                .unwrap()
+                .completed
+        );
+        assert_eq!(store.get_streak().unwrap(), 1);
+        assert_eq!(
+            store
+                .get_resumable_session(None)
+                .unwrap()
+                .unwrap()
+                .session_id,
+            Some(restarted)
+        );
+    }
+
+    #[test]
+    fn test_speak_quality_from_latest_completed_run() {
+        let store = Store::open(":memory:").unwrap();
+        assert_eq!(store.get_speak_quality("cafe-to-go-01").unwrap(), None);
+
+        let mut first = runner_for("cafe-to-go-01");
+        first.record_speak_score(1, &crate::speech::score_transcript("a b", "a", 0.75));
+        first.completed = true;
+        store.save_runner_session(&mut first, 1.0).unwrap();
+        assert_eq!(store.get_speak_quality("cafe-to-go-01").unwrap(), Some(0.5));
+
+        // Unfinished runs and runs without speak checks don't count
+        let mut unfinished = runner_for("cafe-to-go-01");
+        unfinished.record_speak_score(1, &crate::speech::score_transcript("a b", "a b", 0.75));
+        store.save_runner_session(&mut unfinished, 0.5).unwrap();
+        let mut silent = runner_for("cafe-to-go-01");
+        silent.completed = true;
+        store.save_runner_session(&mut silent, 1.0).unwrap();
+        assert_eq!(store.get_speak_quality("cafe-to-go-01").unwrap(), Some(0.5));
+    }
+
+    #[test]
+    fn test_runner_session_recreated_when_row_missing() {
+        let store = Store::open(":memory:").unwrap();
+        let mut state = runner_for("cafe-to-go-01");
+        state.session_id = Some(42);
+
+        let id = store.save_runner_session(&mut state, 0.5).unwrap();
+
+        assert_ne!(id, 42);
+        assert_eq!(state.session_id, Some(id));
+    }
+
+    #[test]
+    fn test_open_upgrades_unversioned_database() {
+        let path =
+            std::env::temp_dir().join(format!("liminal_old_sessions_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sessions(id INTEGER PRIMARY KEY, script_id TEXT NOT NULL, started_at TEXT NOT NULL, completed_at TEXT, progress REAL DEFAULT 0.0);",
+            )
+            .unwrap();
+        }
+
+        let store = Store::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(store.schema_version().unwrap(), migrations::SCHEMA_VERSION);
+        let mut state = runner_for("cafe-to-go-01");
+        assert!(store.save_runner_session(&mut state, 0.5).is_ok());
+
+        drop(store);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_open_refuses_newer_database() {
+        let path = std::env::temp_dir().join(format!("liminal_newer_{}.db", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.pragma_update(None, "user_version", migrations::SCHEMA_VERSION + 1)
+                .unwrap();
+        }
+
+        assert!(Store::open(path.to_str().unwrap()).is_err());
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    fn complete_session_at(store: &Store, completed_at: DateTime<Utc>) {
+        store
+            .conn
+            .execute(
+                "INSERT INTO sessions(script_id, started_at, completed_at) VALUES('s', ?1, ?1)",
+                params![completed_at.to_rfc3339()],
+            )
+            .unwrap();
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_streak_consecutive_days() {
+        let store = Store::open(":memory:").unwrap();
+        for day in ["2025-03-01", "2025-03-03", "2025-03-04", "2025-03-05"] {
+            complete_session_at(&store, utc(&format!("{}T10:00:00Z", day)));
+        }
+        // Two sessions on the same day count once
+        complete_session_at(&store, utc("2025-03-05T18:00:00Z"));
+
+        let summary = store
+            .streak_summary_at(utc("2025-03-06T09:00:00Z"), 7)
+            .unwrap();
+
+        assert_eq!(summary.current, 3);
+        assert_eq!(summary.longest, 3);
+        assert!(!summary.active_today);
+        assert_eq!(summary.histogram.len(), 7);
+        assert_eq!(summary.histogram[5].sessions, 2);
+
+        let broken = store
+            .streak_summary_at(utc("2025-03-08T09:00:00Z"), 7)
+            .unwrap();
+        assert_eq!(broken.current, 0);
+        assert_eq!(broken.longest, 3);
+    }
+
+    #[test]
+    fn test_streak_uses_local_time_zone() {
+        let store = Store::open(":memory:").unwrap();
+        // 23:30 UTC on the 1st is already the 2nd in UTC+3
+        complete_session_at(&store, utc("2025-03-01T23:30:00Z"));
+        complete_session_at(&store, utc("2025-03-02T10:00:00Z"));
+
+        let now = utc("2025-03-02T12:00:00Z");
+        assert_eq!(store.streak_summary_at(now, 0).unwrap().current, 2);
+
+        store.set_utc_offset_minutes(180).unwrap();
+        assert_eq!(store.streak_summary_at(now, 0).unwrap().current, 1);
+    }
+
+    #[test]
+    fn test_utc_offset_out_of_range() {
+        let store = Store::open(":memory:").unwrap();
+        assert!(store.set_utc_offset_minutes(i32::MAX).is_err());
+        assert!(store.set_utc_offset_minutes(-721).is_err());
+        assert!(store.set_utc_offset_minutes(841).is_err());
+        store.set_utc_offset_minutes(-720).unwrap();
+        store.set_utc_offset_minutes(840).unwrap();
+
+        // A bad value written by an older build is reported, not a panic
+        store
+            .set_setting("utc_offset_minutes", &i32::MAX.to_string())
+            .unwrap();
+        assert!(store.get_utc_offset().is_err());
+    }
+
+    #[test]
+    fn test_native_language_setting() {
+        let store = Store::open(":memory:").unwrap();
+        assert_eq!(store.get_native_language().unwrap(), "en");
+
+        store.set_native_language(" ru ").unwrap();
+        assert_eq!(store.get_native_language().unwrap(), "ru");
+        assert!(store.set_native_language("").is_err());
+    }
+
+    #[test]
+    fn test_streak_freeze_tokens() {
+        let store = Store::open(":memory:").unwrap();
+        complete_session_at(&store, utc("2025-03-01T10:00:00Z"));
+        complete_session_at(&store, utc("2025-03-02T10:00:00Z"));
+        assert_eq!(store.add_streak_freeze_tokens(2).unwrap(), 2);
+
+        // Missed the 3rd: viewing the streak spends nothing
+        let march_4 = utc("2025-03-04T08:00:00Z");
+        let summary = store.streak_summary_at(march_4, 0).unwrap();
+        assert_eq!(summary.current, 0);
+        assert_eq!(summary.freeze_tokens, 2);
+        assert!(store.get_frozen_days().unwrap().is_empty());
+
+        // Applying freezes spends one token to keep the streak alive
+        let frozen = store
+            .apply_streak_freezes(store.local_date(march_4).unwrap())
+            .unwrap();
+        assert_eq!(frozen.len(), 1);
+        let summary = store.streak_summary_at(march_4, 0).unwrap();
+        assert_eq!(summary.current, 2);
+        assert_eq!(summary.freeze_tokens, 1);
+
+        // Missing three more days is more than the remaining token covers
+        let march_8 = utc("2025-03-08T08:00:00Z");
+        assert!(store
+            .apply_streak_freezes(store.local_date(march_8).unwrap())
+            .unwrap()
+            .is_empty());
+        let summary = store.streak_summary_at(march_8, 0).unwrap();
+        assert_eq!(summary.current, 0);
+        assert_eq!(summary.freeze_tokens, 1);
+    }
+
+    #[test]
+    fn test_completing_a_session_applies_freezes() {
+        let store = Store::open(":memory:").unwrap();
+        let yesterday = Utc::now() - chrono::Duration::days(1);
+        complete_session_at(&store, yesterday - chrono::Duration::days(1));
+        store.add_streak_freeze_tokens(1).unwrap();
+
+        let mut state = runner_for("cafe-to-go-01");
+        state.completed = true;
+        store.save_runner_session(&mut state, 1.0).unwrap();
+
+        assert_eq!(store.get_frozen_days().unwrap().len(), 1);
+        assert_eq!(store.get_streak_freeze_tokens().unwrap(), 0);
+        assert_eq!(store.get_streak().unwrap(), 2);
+    }
+
     #[test]
     fn test_role_progress_save_load() {
         let store = Store::open(":memory:").unwrap();
@@ -1149,11 +2515,225 @@ mod tests {
         assert!(loaded.is_some());
         let loaded = loaded.unwrap();
         assert_eq!(loaded.emotion_tags.len(), 2);
-        assert_eq!(loaded.emotion_tags[0].tone, "Calm");
-        assert_eq!(loaded.emotion_tags[1].tone, "Confident");
+        assert_eq!(loaded.emotion_tags[0].tone, Tone::Calm);
+        assert_eq!(loaded.emotion_tags[1].tone, Tone::Confident);
         assert_eq!(loaded.current_scene_index, 2);
     }
 
+    #[test]
+    fn test_legacy_emotion_tones_load_leniently() {
+        let store = Store::open(":memory:").unwrap();
+        store
+            .save_role_progress(&RoleProgress::new("qa_abroad".to_string(), 5))
+            .unwrap();
+        for tone in ["Professional", "excited", "whatever"] {
+            store
+                .conn
+                .execute(
+                    "INSERT INTO emotion_tags(role_id, scene_id, tone, confidence, timestamp) VALUES('qa_abroad', 's', ?1, 0.5, ?2)",
+                    params![tone, Utc::now().to_rfc3339()],
+                )
+                .unwrap();
+        }
+
+        let loaded = store.load_role_progress("qa_abroad").unwrap().unwrap();
+        let tones: Vec<Tone> = loaded.emotion_tags.iter().map(|e| e.tone).collect();
+        assert_eq!(tones, vec![Tone::Confident, Tone::Excited, Tone::Neutral]);
+
+        store
+            .save_role_progress(&RoleProgress::new("visa_journey".to_string(), 3))
+            .unwrap();
+        let mut visa = store.load_role_progress("visa_journey").unwrap().unwrap();
+        visa.complete_scene(EmotionTag::new("v".to_string(), Tone::Calm, 0.9));
+        store.save_role_progress(&visa).unwrap();
+
+        assert_eq!(store.get_emotion_history(None).unwrap().len(), 4);
+        let history = store.get_emotion_history(Some("visa_journey")).unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].0, "visa_journey");
+    }
+
+    fn populated_store() -> Store {
+        let store = Store::open(":memory:").unwrap();
+        store.add_event("warmup", "{}").unwrap();
+        store
+            .save_memory_link(&MemoryLink::new("to go, please".to_string(), 0.85))
+            .unwrap();
+
+        let mut progress = RoleProgress::new("qa_abroad".to_string(), 5);
+        progress.complete_scene(EmotionTag::new(
+            "scene1".to_string(),
+            "Calm".to_string(),
+            0.85,
+        ));
+        store.save_role_progress(&progress).unwrap();
+
+        let mut trace = ResonanceTrace::new(
+            "trace1".to_string(),
+            "qa_abroad".to_string(),
+            "scene1".to_string(),
+            "Made it through".to_string(),
+        );
+        trace.add_reflection(Reflection::new("trace1".to_string(), "Nice!".to_string()));
+        store.save_resonance_trace(&trace).unwrap();
+
+        let mut state = runner_for("cafe-to-go-01");
+        store.save_runner_session(&mut state, 0.5).unwrap();
+        store.add_streak_freeze_tokens(2).unwrap();
+        store
+    }
+
+    #[test]
+    fn test_snapshot_round_trip_into_fresh_database() {
+        let source = populated_store();
+        let json = source.export_json().unwrap();
+
+        let target = Store::open(":memory:").unwrap();
+        let summary = target.import_json(&json, ImportMode::Merge).unwrap();
+
+        assert_eq!(summary.rows_skipped, 0);
+        assert_eq!(summary.tables["reflections"], 1);
+        assert_eq!(
+            target.export_snapshot().unwrap().tables,
+            source.export_snapshot().unwrap().tables
+        );
+
+        let progress = target.load_role_progress("qa_abroad").unwrap().unwrap();
+        assert_eq!(progress.emotion_tags[0].tone, Tone::Calm);
+        assert!(target
+            .get_resumable_session(Some("cafe-to-go-01"))
+            .unwrap()
+            .is_some());
+        assert_eq!(target.get_streak_freeze_tokens().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_merge_keeps_existing_rows() {
+        let json = populated_store().export_json().unwrap();
+
+        let target = Store::open(":memory:").unwrap();
+        target
+            .save_memory_link(&MemoryLink::new("to go, please".to_string(), 0.75))
+            .unwrap();
+        target
+            .save_memory_link(&MemoryLink::new("local only".to_string(), 0.5))
+            .unwrap();
+
+        let summary = target.import_json(&json, ImportMode::Merge).unwrap();
+
+        assert!(summary.rows_skipped >= 1);
+        let kept = target.load_memory_link("to go, please").unwrap().unwrap();
+        assert_eq!(kept.decay_alpha, 0.75);
+        assert!(target.load_memory_link("local only").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_snapshot_merge_adds_rows_from_another_device() {
+        let count = |store: &Store, table: &str| -> i64 {
+            store
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+        };
+        let device = |role_id: &str, event: &str| {
+            let store = Store::open(":memory:").unwrap();
+            store.add_event(event, "{}").unwrap();
+            store.add_event(event, "{\"n\": 2}").unwrap();
+            let mut progress = RoleProgress::new(role_id.to_string(), 3);
+            for scene in ["scene1", "scene2"] {
+                progress.complete_scene(EmotionTag::new(
+                    scene.to_string(),
+                    "Calm".to_string(),
+                    0.8,
+                ));
+            }
+            store.save_role_progress(&progress).unwrap();
+            let mut state = runner_for("cafe-to-go-01");
+            store.save_runner_session(&mut state, 0.5).unwrap();
+            store
+        };
+
+        let phone = device("qa_abroad", "phone");
+        let tablet = device("visa_journey", "tablet");
+        let tables = ["events", "emotion_tags", "sessions", "coherence_history"];
+        let expected: Vec<i64> = tables
+            .iter()
+            .map(|t| count(&phone, t) + count(&tablet, t))
+            .collect();
+
+        let backup = tablet.export_json().unwrap();
+        phone.import_json(&backup, ImportMode::Merge).unwrap();
+        let merged: Vec<i64> = tables.iter().map(|t| count(&phone, t)).collect();
+        assert_eq!(merged, expected);
+        assert!(phone.load_role_progress("visa_journey").unwrap().is_some());
+
+        // Merging the same backup again adds nothing
+        let again = phone.import_json(&backup, ImportMode::Merge).unwrap();
+        assert_eq!(again.rows_imported, 0);
+        assert_eq!(
+            tables.iter().map(|t| count(&phone, t)).collect::<Vec<_>>(),
+            expected
+        );
+
+        // The tablet's runner got row 2 here; saving it must not touch row 1
+        let mut merged_runner = phone.load_runner_session(2).unwrap().unwrap();
+        assert_eq!(merged_runner.session_id, Some(2));
+        merged_runner.completed = true;
+        phone.save_runner_session(&mut merged_runner, 1.0).unwrap();
+        assert!(!phone.load_runner_session(1).unwrap().unwrap().completed);
+        assert_eq!(count(&phone, "sessions"), expected[2]);
+    }
+
+    #[test]
+    fn test_snapshot_merge_dedupes_repeated_rows_within_a_backup() {
+        let source = Store::open(":memory:").unwrap();
+        source.add_event("tap", "{}").unwrap();
+        source.add_event("swipe", "{}").unwrap();
+        let mut snapshot = source.export_snapshot().unwrap();
+        let events = snapshot.tables.get_mut("events").unwrap();
+        let mut copy = events[0].clone();
+        copy.insert("id".to_string(), 99.into());
+        events.push(copy);
+
+        let target = Store::open(":memory:").unwrap();
+        let summary = target
+            .import_snapshot(&snapshot, ImportMode::Merge)
+            .unwrap();
+        assert_eq!(summary.tables["events"], 2);
+        assert_eq!(target.export_snapshot().unwrap().tables["events"].len(), 2);
+    }
+
+    #[test]
+    fn test_snapshot_replace_discards_existing_rows() {
+        let json = populated_store().export_json().unwrap();
+
+        let target = Store::open(":memory:").unwrap();
+        target
+            .save_memory_link(&MemoryLink::new("local only".to_string(), 0.5))
+            .unwrap();
+
+        target.import_json(&json, ImportMode::Replace).unwrap();
+
+        assert!(target.load_memory_link("local only").unwrap().is_none());
+        assert!(target.load_memory_link("to go, please").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_snapshot_from_newer_schema_is_refused() {
+        let mut snapshot = populated_store().export_snapshot().unwrap();
+        snapshot.schema_version = migrations::SCHEMA_VERSION + 1;
+
+        let target = Store::open(":memory:").unwrap();
+        let err = target
+            .import_snapshot(&snapshot, ImportMode::Replace)
+            .unwrap_err();
+
+        assert!(err.to_string().contains("newer"));
+        assert!(target.get_all_memory_links().unwrap().is_empty());
+    }
+
     #[test]
     fn test_role_progress_update() {
         let store = Store::open(":memory:").unwrap();
@@ -1173,6 +2753,139 @@ mod tests {
         assert_eq!(loaded.consecutive_days, 7);
     }
 
+    #[test]
+    fn test_coherence_history_round_trip() {
+        let store = Store::open(":memory:").unwrap();
+        let mut progress = RoleProgress::new("qa_abroad".to_string(), 5);
+        progress.calculate_coherence();
+        progress.complete_scene(EmotionTag::new("s1".to_string(), "Calm", 0.9));
+        progress.complete_scene(EmotionTag::new("s2".to_string(), "Calm", 0.9));
+        store.save_role_progress(&progress).unwrap();
+
+        let loaded = store.load_role_progress("qa_abroad").unwrap().unwrap();
+        assert_eq!(loaded.coherence_history, progress.coherence_history);
+        let indices: Vec<usize> = loaded
+            .coherence_history
+            .iter()
+            .map(|s| s.scene_index)
+            .collect();
+        assert_eq!(indices, vec![0, 1, 2]);
+
+        // Saving again replaces rather than duplicates
+        store.save_role_progress(&loaded).unwrap();
+        let reloaded = store.load_role_progress("qa_abroad").unwrap().unwrap();
+        assert_eq!(reloaded.coherence_history.len(), 3);
+    }
+
+    #[test]
+    fn test_use_in_wild_count_for_scripts() {
+        let store = Store::open(":memory:").unwrap();
+        for (phrase, script, uses) in [("a", "visa", 2), ("b", "visa", 1), ("c", "cafe", 5)] {
+            let mut link = MemoryLink::new(phrase.to_string(), 0.8);
+            link.script_id = Some(script.to_string());
+            link.use_in_wild_count = uses;
+            store.save_memory_link(&link).unwrap();
+        }
+
+        let ids = |ids: &[&str]| ids.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(store.get_use_in_wild_count().unwrap(), 8);
+        assert_eq!(
+            store
+                .get_use_in_wild_count_for_scripts(&ids(&["visa"]))
+                .unwrap(),
+            3
+        );
+        assert_eq!(
+            store
+                .get_use_in_wild_count_for_scripts(&ids(&["other"]))
+                .unwrap(),
+            0
+        );
+        assert_eq!(store.get_use_in_wild_count_for_scripts(&[]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_certificate_signer_is_persisted() {
+        let store = Store::open(":memory:").unwrap();
+        let first = store.certificate_signer().unwrap();
+        let second = store.certificate_signer().unwrap();
+        assert_eq!(first.public_key_hex(), second.public_key_hex());
+    }
+
+    #[test]
+    fn test_signing_seed_stays_out_of_snapshots() {
+        let store = populated_store();
+        let seed = store.certificate_signer().unwrap().seed_hex();
+
+        let json = store.export_json().unwrap();
+        assert!(!json.contains(&seed));
+        assert!(!json.contains("signing_keys"));
+
+        let mut snapshot = store.export_snapshot().unwrap();
+        snapshot
+            .tables
+            .insert("signing_keys".to_string(), Vec::new());
+        assert!(store
+            .import_snapshot(&snapshot, ImportMode::Replace)
+            .is_err());
+
+        // A replace restore leaves the device key alone
+        let target = Store::open(":memory:").unwrap();
+        let own = target.certificate_signer().unwrap().seed_hex();
+        target.import_json(&json, ImportMode::Replace).unwrap();
+        assert_eq!(target.certificate_signer().unwrap().seed_hex(), own);
+
+        // Older backups kept the seed in settings; it is not restored
+        let mut old = store.export_snapshot().unwrap();
+        let mut row = serde_json::Map::new();
+        row.insert("key".into(), "certificate_signing_key".into());
+        row.insert("value".into(), seed.clone().into());
+        old.tables.get_mut("settings").unwrap().push(row);
+        target.import_snapshot(&old, ImportMode::Merge).unwrap();
+        assert!(!target.export_json().unwrap().contains(&seed));
+    }
+
+    #[test]
+    fn test_transition_ready_role_unlocks_next_on_path() {
+        let store = Store::open(":memory:").unwrap();
+        let path: RolePath = serde_yaml::from_str(
+            "id: work_abroad\ntitle: Work Abroad\ndescription: d\nroles: [visa_journey, qa_engineer_abroad]\n",
+        )
+        .unwrap();
+        let paths = vec![path.clone()];
+
+        let mut progress = RoleProgress::new("visa_journey".to_string(), 3);
+        progress.coherence = 0.5;
+        store.save_role_progress(&progress).unwrap();
+        assert!(store
+            .unlock_next_roles(&paths, &progress)
+            .unwrap()
+            .is_empty());
+
+        progress.coherence = 0.8;
+        store.save_role_progress(&progress).unwrap();
+        assert_eq!(
+            store.unlock_next_roles(&paths, &progress).unwrap(),
+            vec!["qa_engineer_abroad"]
+        );
+        // Only reported once
+        assert!(store
+            .unlock_next_roles(&paths, &progress)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            store.get_events(Some("role_unlocked"), 10).unwrap().len(),
+            1
+        );
+
+        // The unlock survives a later drop in coherence
+        progress.coherence = 0.4;
+        store.save_role_progress(&progress).unwrap();
+        let status = store.get_path_progress(&path).unwrap();
+        assert_eq!(status.roles[1].status, PathRoleStatus::Unlocked);
+        assert_eq!(status.current_role.as_deref(), Some("visa_journey"));
+    }
+
     #[test]
     fn test_resonance_trace_save_load() {
         let store = Store::open(":memory:").unwrap();
@@ -1281,8 +2994,8 @@ mod tests {
 
         assert_eq!(loaded1.emotion_tags.len(), 2);
         assert_eq!(loaded2.emotion_tags.len(), 1);
-        assert_eq!(loaded1.emotion_tags[0].tone, "Calm");
-        assert_eq!(loaded2.emotion_tags[0].tone, "Nervous");
+        assert_eq!(loaded1.emotion_tags[0].tone, Tone::Calm);
+        assert_eq!(loaded2.emotion_tags[0].tone, Tone::Nervous);
     }
 
     #[test]
@@ -0,0 +1,198 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Activity on one local calendar day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DayActivity {
+    pub date: NaiveDate,
+    pub sessions: u32,
+    /// Day was missed but covered by a streak-freeze token
+    pub frozen: bool,
+}
+
+/// Streak statistics in the learner's local calendar
+///
+/// Rules:
+/// - A day counts when at least one session was completed on it
+/// - The current streak ends today, or yesterday if today has no activity yet
+/// - Frozen days keep a streak alive but do not add to its length
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreakSummary {
+    pub current: u32,
+    pub longest: u32,
+    pub active_today: bool,
+    pub last_active_day: Option<NaiveDate>,
+    pub freeze_tokens: u32,
+    /// Oldest first, one entry per day including inactive ones
+    pub histogram: Vec<DayActivity>,
+}
+
+/// Current and longest streak over a set of active and frozen days
+pub fn compute_streaks(
+    active: &BTreeMap<NaiveDate, u32>,
+    frozen: &BTreeSet<NaiveDate>,
+    today: NaiveDate,
+) -> (u32, u32) {
+    let is_active = |d: &NaiveDate| active.get(d).is_some_and(|n| *n > 0);
+    let covered = |d: &NaiveDate| is_active(d) || frozen.contains(d);
+
+    // Longest: walk every covered day in order, resetting on gaps
+    let mut longest = 0;
+    let mut run = 0;
+    let mut prev: Option<NaiveDate> = None;
+    let days: BTreeSet<NaiveDate> = active
+        .iter()
+        .filter(|(_, n)| **n > 0)
+        .map(|(d, _)| *d)
+        .chain(frozen.iter().copied())
+        .collect();
+    for day in days {
+        if prev.is_none_or(|p| day - p != Duration::days(1)) {
+            run = 0;
+        }
+        if is_active(&day) {
+            run += 1;
+        }
+        longest = longest.max(run);
+        prev = Some(day);
+    }
+
+    // Current: walk back from today (or yesterday) while days are covered
+    let mut day = if covered(&today) {
+        today
+    } else {
+        today - Duration::days(1)
+    };
+    let mut current = 0;
+    while covered(&day) {
+        if is_active(&day) {
+            current += 1;
+        }
+        day -= Duration::days(1);
+    }
+
+    (current, longest)
+}
+
+/// Missed days that should be frozen to keep the streak alive
+///
+/// Returns the days between the last covered day and yesterday when there
+/// are enough tokens to cover all of them; otherwise nothing (the streak is
+/// already lost and tokens are kept).
+pub fn days_to_freeze(
+    active: &BTreeMap<NaiveDate, u32>,
+    frozen: &BTreeSet<NaiveDate>,
+    today: NaiveDate,
+    tokens: u32,
+) -> Vec<NaiveDate> {
+    let last_covered = active
+        .iter()
+        .filter(|(d, n)| **n > 0 && **d < today)
+        .map(|(d, _)| *d)
+        .chain(frozen.iter().copied().filter(|d| *d < today))
+        .max();
+
+    let Some(last) = last_covered else {
+        return Vec::new();
+    };
+
+    let gap: Vec<NaiveDate> = last
+        .succ_opt()
+        .into_iter()
+        .flat_map(|start| start.iter_days())
+        .take_while(|d| *d < today)
+        .collect();
+
+    if gap.is_empty() || gap.len() as u32 > tokens {
+        return Vec::new();
+    }
+    gap
+}
+
+/// Per-day activity for the `days` days ending today
+pub fn histogram(
+    active: &BTreeMap<NaiveDate, u32>,
+    frozen: &BTreeSet<NaiveDate>,
+    today: NaiveDate,
+    days: u32,
+) -> Vec<DayActivity> {
+    (0..days as i64)
+        .rev()
+        .map(|back| {
+            let date = today - Duration::days(back);
+            DayActivity {
+                date,
+                sessions: active.get(&date).copied().unwrap_or(0),
+                frozen: frozen.contains(&date),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    fn active(days: &[u32]) -> BTreeMap<NaiveDate, u32> {
+        days.iter().map(|d| (day(*d), 1)).collect()
+    }
+
+    #[test]
+    fn test_streak_ending_today() {
+        let (current, longest) = compute_streaks(&active(&[8, 9, 10]), &BTreeSet::new(), day(10));
+        assert_eq!(current, 3);
+        assert_eq!(longest, 3);
+    }
+
+    #[test]
+    fn test_streak_ending_yesterday_still_counts() {
+        let (current, _) = compute_streaks(&active(&[8, 9]), &BTreeSet::new(), day(10));
+        assert_eq!(current, 2);
+    }
+
+    #[test]
+    fn test_streak_broken_by_gap() {
+        let (current, longest) =
+            compute_streaks(&active(&[1, 2, 3, 4, 7, 8]), &BTreeSet::new(), day(10));
+        assert_eq!(current, 0);
+        assert_eq!(longest, 4);
+    }
+
+    #[test]
+    fn test_frozen_day_bridges_without_counting() {
+        let frozen: BTreeSet<NaiveDate> = [day(9)].into_iter().collect();
+        let (current, longest) = compute_streaks(&active(&[7, 8, 10]), &frozen, day(10));
+        assert_eq!(current, 3);
+        assert_eq!(longest, 3);
+    }
+
+    #[test]
+    fn test_days_to_freeze_covers_gap_when_tokens_suffice() {
+        let gap = days_to_freeze(&active(&[5, 6]), &BTreeSet::new(), day(9), 2);
+        assert_eq!(gap, vec![day(7), day(8)]);
+
+        // Not enough tokens: the streak is lost, keep the tokens
+        assert!(days_to_freeze(&active(&[5, 6]), &BTreeSet::new(), day(9), 1).is_empty());
+
+        // Active yesterday: nothing to freeze
+        assert!(days_to_freeze(&active(&[8]), &BTreeSet::new(), day(9), 2).is_empty());
+    }
+
+    #[test]
+    fn test_histogram_includes_inactive_days() {
+        let frozen: BTreeSet<NaiveDate> = [day(9)].into_iter().collect();
+        let hist = histogram(&active(&[8, 10]), &frozen, day(10), 4);
+
+        assert_eq!(hist.len(), 4);
+        assert_eq!(hist[0].date, day(7));
+        assert_eq!(hist[0].sessions, 0);
+        assert_eq!(hist[1].sessions, 1);
+        assert!(hist[2].frozen);
+        assert_eq!(hist[3].date, day(10));
+    }
+}
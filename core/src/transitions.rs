@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::roles::{LiminalTransition, Role, RoleProgress};
+use crate::scripts::Script;
+
+/// Language used when a text has no translation for the learner
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Variables available in transition templates, e.g. `{role}`
+pub const TEMPLATE_VARIABLES: &[&str] =
+    &["role", "scene", "coherence", "scenes_done", "scenes_total"];
+
+/// Text authored either in English only or per language
+///
+/// ```yaml
+/// transition_message: "Stand-ups feel natural now."
+/// transition_message:
+///   en: "Stand-ups feel natural now."
+///   ru: "Стендапы стали привычными."
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LocalizedText {
+    Plain(String),
+    Translations(BTreeMap<String, String>),
+}
+
+impl LocalizedText {
+    /// Text for `language` (`ru-RU` also matches `ru`), else English, else
+    /// any translation
+    pub fn text(&self, language: &str) -> &str {
+        match self {
+            LocalizedText::Plain(text) => text,
+            LocalizedText::Translations(map) => {
+                let base = language.split(['-', '_']).next().unwrap_or(language);
+                [language, base, DEFAULT_LANGUAGE]
+                    .iter()
+                    .find_map(|lang| map.get(*lang))
+                    .or_else(|| map.values().next())
+                    .map(String::as_str)
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn texts(&self) -> Vec<&str> {
+        match self {
+            LocalizedText::Plain(text) => vec![text.as_str()],
+            LocalizedText::Translations(map) => map.values().map(String::as_str).collect(),
+        }
+    }
+}
+
+impl From<&str> for LocalizedText {
+    fn from(text: &str) -> Self {
+        LocalizedText::Plain(text.to_string())
+    }
+}
+
+/// Look of a transition; unset fields fall through to the next level
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransitionStyle {
+    #[serde(default)]
+    pub message: Option<LocalizedText>,
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    #[serde(default)]
+    pub color_from: Option<String>,
+    #[serde(default)]
+    pub color_to: Option<String>,
+    #[serde(default)]
+    pub sound: Option<String>,
+}
+
+/// Style used once coherence reaches `min_coherence`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoherenceBand {
+    pub min_coherence: f32,
+    #[serde(flatten)]
+    pub style: TransitionStyle,
+}
+
+/// `transitions:` block of a role manifest
+///
+/// ```yaml
+/// transitions:
+///   sound: chime_soft.mp3
+///   bands:
+///     - min_coherence: 0.75
+///       message:
+///         en: "{role} is who you are becoming."
+///         ru: "{role} — это уже вы."
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoleTransitions {
+    #[serde(flatten)]
+    pub style: TransitionStyle,
+    #[serde(default)]
+    pub bands: Vec<CoherenceBand>,
+}
+
+impl RoleTransitions {
+    /// Band with the highest threshold that `coherence` reaches
+    pub fn band(&self, coherence: f32) -> Option<&CoherenceBand> {
+        self.bands
+            .iter()
+            .filter(|b| coherence >= b.min_coherence)
+            .max_by(|a, b| {
+                a.min_coherence
+                    .partial_cmp(&b.min_coherence)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+}
+
+/// Built-in message for a coherence level
+fn default_message(coherence: f32, language: &str) -> &'static str {
+    let russian = language.starts_with("ru");
+    match (coherence, russian) {
+        (c, false) if c >= 0.9 => "You've mastered being {role}. Ready for the next chapter?",
+        (c, true) if c >= 0.9 => "Роль «{role}» освоена. Готовы к следующей главе?",
+        (c, false) if c >= 0.75 => "You're one step closer to being the {role} you want to be.",
+        (c, true) if c >= 0.75 => "Ещё шаг к тому, кем вы хотите быть: {role}.",
+        (c, false) if c >= 0.5 => "Half way there. The {role} in you is emerging.",
+        (c, true) if c >= 0.5 => "Половина пути. {role} в вас уже проявляется.",
+        (c, false) if c >= 0.25 => "You're on the path to becoming {role}.",
+        (c, true) if c >= 0.25 => "Вы на пути к роли «{role}».",
+        (_, false) => "First step taken as {role}. Keep going.",
+        (_, true) => "Первый шаг в роли «{role}» сделан. Продолжайте.",
+    }
+}
+
+/// Replace `{name}` placeholders; unknown names are left as written
+pub fn render_template(template: &str, vars: &[(&str, String)]) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// Placeholders in `template` that are not in [`TEMPLATE_VARIABLES`]
+pub fn unknown_variables(template: &str) -> Vec<String> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .filter(|name| !TEMPLATE_VARIABLES.contains(name))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Transition after the latest scene of `progress`
+///
+/// The message comes from the scene's `transition_message`, then the
+/// role's band for the current coherence, then the role's own
+/// `transitions.message`, then the built-in text. Duration, colours and
+/// sound resolve band → role → defaults. The animation runs from the
+/// coherence before the latest scene to the current one.
+pub fn resolve_transition(
+    progress: &RoleProgress,
+    role: Option<&Role>,
+    scene: Option<&Script>,
+    language: &str,
+) -> LiminalTransition {
+    build_transition(
+        progress,
+        role.map_or(progress.role_id.as_str(), |r| r.title.as_str()),
+        role.and_then(|r| r.transitions.as_ref()),
+        scene,
+        language,
+    )
+}
+
+pub(crate) fn build_transition(
+    progress: &RoleProgress,
+    role_title: &str,
+    role_styles: Option<&RoleTransitions>,
+    scene: Option<&Script>,
+    language: &str,
+) -> LiminalTransition {
+    let coherence = progress.coherence;
+    let band = role_styles
+        .and_then(|t| t.band(coherence))
+        .map(|b| &b.style);
+    let styles: Vec<&TransitionStyle> = band
+        .into_iter()
+        .chain(role_styles.map(|t| &t.style))
+        .collect();
+
+    let template = scene
+        .and_then(|s| s.transition_message.as_ref())
+        .or_else(|| styles.iter().find_map(|s| s.message.as_ref()))
+        .map(|m| m.text(language))
+        .unwrap_or_else(|| default_message(coherence, language));
+
+    let vars = [
+        ("role", role_title.to_string()),
+        ("scene", scene.map(|s| s.title.clone()).unwrap_or_default()),
+        ("coherence", format!("{:.0}%", coherence * 100.0)),
+        ("scenes_done", progress.current_scene_index.to_string()),
+        ("scenes_total", progress.total_scenes.to_string()),
+    ];
+
+    LiminalTransition {
+        message: render_template(template, &vars),
+        prev_coherence: progress.previous_coherence(),
+        curr_coherence: coherence,
+        animation_duration_ms: styles.iter().find_map(|s| s.duration_ms).unwrap_or(2500),
+        color_from: styles
+            .iter()
+            .find_map(|s| s.color_from.clone())
+            .unwrap_or_else(|| "#4A90E2".to_string()), // Blue
+        color_to: styles
+            .iter()
+            .find_map(|s| s.color_to.clone())
+            .unwrap_or_else(|| "#7ED321".to_string()), // Green
+        sound: styles
+            .iter()
+            .find_map(|s| s.sound.clone())
+            .unwrap_or_else(|| "chime_gentle.mp3".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn role(transitions: &str) -> Role {
+        serde_yaml::from_str(&format!(
+            "id: qa\ntitle: QA Engineer\ndescription: d\n{}",
+            transitions
+        ))
+        .unwrap()
+    }
+
+    fn progress(coherence: f32) -> RoleProgress {
+        let mut progress = RoleProgress::new("qa".to_string(), 5);
+        progress.current_scene_index = 3;
+        progress.coherence = coherence;
+        progress
+    }
+
+    #[test]
+    fn test_localized_text_fallbacks() {
+        let text: LocalizedText = serde_yaml::from_str("en: Hello\nru: Привет\n").unwrap();
+        assert_eq!(text.text("ru"), "Привет");
+        assert_eq!(text.text("ru-RU"), "Привет");
+        assert_eq!(text.text("de"), "Hello");
+
+        let plain: LocalizedText = serde_yaml::from_str("\"Hello\"").unwrap();
+        assert_eq!(plain.text("ru"), "Hello");
+    }
+
+    #[test]
+    fn test_builtin_defaults() {
+        let transition = resolve_transition(&progress(0.6), None, None, "en");
+        assert_eq!(
+            transition.message,
+            "Half way there. The qa in you is emerging."
+        );
+        assert_eq!(transition.animation_duration_ms, 2500);
+        assert_eq!(transition.sound, "chime_gentle.mp3");
+
+        let role = role("");
+        let russian = resolve_transition(&progress(0.6), Some(&role), None, "ru");
+        assert_eq!(
+            russian.message,
+            "Половина пути. QA Engineer в вас уже проявляется."
+        );
+    }
+
+    #[test]
+    fn test_role_bands_and_scene_messages() {
+        let role = role(
+            "transitions:\n  sound: soft.mp3\n  message: \"{scenes_done}/{scenes_total} done\"\n  bands:\n    - min_coherence: 0.75\n      color_to: \"#FFD700\"\n      message:\n        en: \"{role} at {coherence}\"\n        ru: \"{role}: {coherence}\"\n",
+        );
+
+        let low = resolve_transition(&progress(0.5), Some(&role), None, "en");
+        assert_eq!(low.message, "3/5 done");
+        assert_eq!(low.sound, "soft.mp3");
+        assert_eq!(low.color_to, "#7ED321");
+
+        let high = resolve_transition(&progress(0.8), Some(&role), None, "ru");
+        assert_eq!(high.message, "QA Engineer: 80%");
+        assert_eq!(high.color_to, "#FFD700");
+        assert_eq!(high.sound, "soft.mp3");
+
+        let scene: Script = serde_yaml::from_str(
+            "id: s\ntitle: Stand-up\ntransition_message: \"{scene} feels natural now.\"\nsteps: []\n",
+        )
+        .unwrap();
+        let with_scene = resolve_transition(&progress(0.8), Some(&role), Some(&scene), "ru");
+        assert_eq!(with_scene.message, "Stand-up feels natural now.");
+        assert_eq!(with_scene.color_to, "#FFD700");
+    }
+
+    #[test]
+    fn test_unknown_variables() {
+        assert!(unknown_variables("{role} at {coherence}").is_empty());
+        assert_eq!(unknown_variables("Hi {name}"), vec!["name"]);
+    }
+}
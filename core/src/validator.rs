@@ -1,10 +1,11 @@
-use anyhow::{anyhow, Result};
-use std::collections::{HashMap, HashSet};
-use std::fs;
+use anyhow::Result;
 use std::path::Path;
 
-use crate::roles::Role;
+use crate::content::ContentLibrary;
+use crate::emotion::Affect;
+use crate::roles::{Role, RolePath};
 use crate::scripts::Script;
+use crate::transitions::{unknown_variables, LocalizedText};
 
 #[derive(Debug)]
 pub struct ValidationReport {
@@ -13,6 +14,7 @@ pub struct ValidationReport {
     pub warnings: Vec<String>,
     pub scripts_count: usize,
     pub roles_count: usize,
+    pub paths_count: usize,
 }
 
 impl ValidationReport {
@@ -23,6 +25,7 @@ impl ValidationReport {
             warnings: Vec::new(),
             scripts_count: 0,
             roles_count: 0,
+            paths_count: 0,
         }
     }
 
@@ -39,6 +42,7 @@ impl ValidationReport {
         println!("\n=== Validation Report ===\n");
         println!("Scripts found: {}", self.scripts_count);
         println!("Roles found: {}", self.roles_count);
+        println!("Paths found: {}", self.paths_count);
         println!();
 
         if !self.errors.is_empty() {
@@ -72,136 +76,68 @@ impl Default for ValidationReport {
 }
 
 pub struct ContentValidator {
-    scripts: HashMap<String, Script>,
-    roles: HashMap<String, Role>,
+    library: ContentLibrary,
 }
 
 impl ContentValidator {
     pub fn new() -> Self {
         Self {
-            scripts: HashMap::new(),
-            roles: HashMap::new(),
+            library: ContentLibrary::new(),
         }
     }
 
     /// Load all scripts from a directory
     pub fn load_scripts(&mut self, dir: &Path) -> Result<()> {
-        if !dir.exists() {
-            return Err(anyhow!("Scripts directory not found: {:?}", dir));
-        }
-
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().and_then(|s| s.to_str()) == Some("yaml") {
-                // Skip files starting with underscore (like _role.yaml)
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.starts_with('_') {
-                        continue;
-                    }
-                }
-
-                match self.load_script(&path) {
-                    Ok(script) => {
-                        self.scripts.insert(script.id.clone(), script);
-                    }
-                    Err(e) => {
-                        return Err(anyhow!("Failed to load script {:?}: {}", path, e));
-                    }
-                }
-            }
-        }
-
+        self.library.load_scripts_dir(dir)?;
         Ok(())
     }
 
-    fn load_script(&self, path: &Path) -> Result<Script> {
-        let content = fs::read_to_string(path)?;
-        let script: Script = serde_yaml::from_str(&content)?;
-        Ok(script)
-    }
-
-    /// Load all roles recursively from a directory
+    /// Load all roles recursively from a directory, with their scenes
     pub fn load_roles_recursive(&mut self, dir: &Path) -> Result<()> {
-        if !dir.exists() {
-            return Err(anyhow!("Roles directory not found: {:?}", dir));
-        }
-
-        self.scan_roles_dir(dir)?;
+        self.library.load_roles_dir(dir)?;
         Ok(())
     }
 
-    fn scan_roles_dir(&mut self, dir: &Path) -> Result<()> {
-        for entry in fs::read_dir(dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
-                // Recursively scan subdirectories
-                self.scan_roles_dir(&path)?;
-            } else if path.file_name().and_then(|n| n.to_str()) == Some("_role.yaml") {
-                // Load role manifest
-                match self.load_role(&path) {
-                    Ok(role) => {
-                        self.roles.insert(role.id.clone(), role);
-                    }
-                    Err(e) => {
-                        return Err(anyhow!("Failed to load role {:?}: {}", path, e));
-                    }
-                }
-            }
-        }
-
+    /// Load all role paths from a directory
+    pub fn load_paths(&mut self, dir: &Path) -> Result<()> {
+        self.library.load_paths_dir(dir)?;
         Ok(())
     }
 
-    fn load_role(&self, path: &Path) -> Result<Role> {
-        let content = fs::read_to_string(path)?;
-        let role: Role = serde_yaml::from_str(&content)?;
-        Ok(role)
-    }
-
     /// Validate all loaded content
     pub fn validate(&self) -> ValidationReport {
         let mut report = ValidationReport::new();
-        report.scripts_count = self.scripts.len();
-        report.roles_count = self.roles.len();
+        report.scripts_count = self.library.scripts.len();
+        report.roles_count = self.library.roles.len();
+        report.paths_count = self.library.paths.len();
 
         // Check for duplicate IDs
         self.check_duplicate_ids(&mut report);
 
         // Validate each script
-        for script in self.scripts.values() {
+        for script in self.library.scripts.values() {
             self.validate_script(script, &mut report);
         }
 
         // Validate each role
-        for role in self.roles.values() {
+        for role in self.library.roles.values() {
             self.validate_role(role, &mut report);
         }
 
         // Cross-validate roles and scripts
         self.validate_role_scenario_links(&mut report);
 
+        // Validate each path against the loaded roles
+        for path in self.library.paths.values() {
+            self.validate_path(path, &mut report);
+        }
+
         report
     }
 
     fn check_duplicate_ids(&self, report: &mut ValidationReport) {
-        let mut script_ids: HashSet<String> = HashSet::new();
-
-        for script in self.scripts.values() {
-            if !script_ids.insert(script.id.clone()) {
-                report.add_error(format!("Duplicate script ID: {}", script.id));
-            }
-        }
-
-        let mut role_ids: HashSet<String> = HashSet::new();
-
-        for role in self.roles.values() {
-            if !role_ids.insert(role.id.clone()) {
-                report.add_error(format!("Duplicate role ID: {}", role.id));
-            }
+        for id in &self.library.duplicates {
+            report.add_error(format!("Duplicate ID: {}", id));
         }
     }
 
@@ -236,6 +172,36 @@ impl ContentValidator {
                     script.id, i
                 ));
             }
+
+            if let Some(tag) = step.emotion_tag.as_deref() {
+                if Affect::of_tag(tag).is_none() {
+                    report.add_warning(format!(
+                        "Script '{}' step {} has unknown emotion_tag '{}'",
+                        script.id, i, tag
+                    ));
+                }
+            }
+        }
+
+        if let Some(wave) = script.emotion_wave.as_deref() {
+            if Affect::of_tag(wave).is_none() {
+                report.add_warning(format!(
+                    "Script '{}' has unknown emotion_wave '{}'",
+                    script.id, wave
+                ));
+            }
+        }
+
+        if let Some(message) = &script.transition_message {
+            warn_unknown_variables(&format!("Script '{}'", script.id), message, report);
+        }
+
+        let rehearsal = &script.rehearsal;
+        if rehearsal.next_ping_sec_min > rehearsal.next_ping_sec_max {
+            report.add_error(format!(
+                "Script '{}' has next_ping_sec_min {} above next_ping_sec_max {}",
+                script.id, rehearsal.next_ping_sec_min, rehearsal.next_ping_sec_max
+            ));
         }
     }
 
@@ -256,21 +222,100 @@ impl ContentValidator {
         if role.scenario_ids.is_empty() {
             report.add_error(format!("Role '{}' has no scenarios", role.id));
         }
+
+        if let Some(transitions) = &role.transitions {
+            let owner = format!("Role '{}'", role.id);
+            if let Some(message) = &transitions.style.message {
+                warn_unknown_variables(&owner, message, report);
+            }
+            for band in &transitions.bands {
+                if !(0.0..=1.0).contains(&band.min_coherence) {
+                    report.add_error(format!(
+                        "Role '{}' has transition band min_coherence {} outside 0..1",
+                        role.id, band.min_coherence
+                    ));
+                }
+                if let Some(message) = &band.style.message {
+                    warn_unknown_variables(&owner, message, report);
+                }
+            }
+        }
+    }
+
+    fn validate_path(&self, path: &RolePath, report: &mut ValidationReport) {
+        if path.id.is_empty() {
+            report.add_error("Path has empty ID".to_string());
+        }
+
+        if path.title.is_empty() {
+            report.add_error(format!("Path '{}' has empty title", path.id));
+        }
+
+        if path.role_ids.is_empty() {
+            report.add_error(format!("Path '{}' has no roles", path.id));
+        }
+
+        for (i, role_id) in path.role_ids.iter().enumerate() {
+            if !self.library.roles.contains_key(role_id) {
+                report.add_error(format!(
+                    "Path '{}' references role '{}' which was not found",
+                    path.id, role_id
+                ));
+            }
+            if path.role_ids[..i].contains(role_id) {
+                report.add_error(format!(
+                    "Path '{}' lists role '{}' more than once",
+                    path.id, role_id
+                ));
+            }
+        }
+
+        for (role_id, prerequisites) in &path.prerequisites {
+            let Some(position) = path.role_ids.iter().position(|id| id == role_id) else {
+                report.add_error(format!(
+                    "Path '{}' has prerequisites for role '{}' which is not on the path",
+                    path.id, role_id
+                ));
+                continue;
+            };
+
+            for req in prerequisites {
+                if !self.library.roles.contains_key(&req.role_id) {
+                    report.add_error(format!(
+                        "Path '{}' prerequisite for '{}' references role '{}' which was not found",
+                        path.id, role_id, req.role_id
+                    ));
+                }
+                if !(0.0..=1.0).contains(&req.min_coherence) {
+                    report.add_error(format!(
+                        "Path '{}' prerequisite for '{}' has min_coherence {} outside 0..1",
+                        path.id, role_id, req.min_coherence
+                    ));
+                }
+                if path.role_ids[position..].contains(&req.role_id) {
+                    report.add_error(format!(
+                        "Path '{}' role '{}' requires '{}', which comes no earlier on the path",
+                        path.id, role_id, req.role_id
+                    ));
+                }
+            }
+        }
     }
 
     fn validate_role_scenario_links(&self, report: &mut ValidationReport) {
-        for role in self.roles.values() {
-            for scenario_id in &role.scenario_ids {
-                // Check if scenario file exists (scenario_id is filename)
-                let scenario_exists = self.scripts.values().any(|s| {
-                    // Match by filename or ID
-                    s.id == *scenario_id || scenario_id.contains(&s.id)
-                });
-
-                if !scenario_exists {
+        for (role_id, scene) in &self.library.unresolved {
+            report.add_warning(format!(
+                "Role '{}' references scenario '{}' which was not found",
+                role_id, scene
+            ));
+        }
+
+        for role in self.library.roles.values() {
+            for script in self.library.scripts_for_role(&role.id) {
+                if let Some(owner) = script.role_id.as_ref().filter(|owner| **owner != role.id) {
                     report.add_warning(format!(
-                        "Role '{}' references scenario '{}' which was not found",
-                        role.id, scenario_id
+                        "Role '{}' lists scenario '{}' which belongs to role '{}'",
+                        role.id, script.id, owner
                     ));
                 }
             }
@@ -284,6 +329,17 @@ impl Default for ContentValidator {
     }
 }
 
+fn warn_unknown_variables(owner: &str, message: &LocalizedText, report: &mut ValidationReport) {
+    for text in message.texts() {
+        for name in unknown_variables(text) {
+            report.add_warning(format!(
+                "{} transition message uses unknown variable '{{{}}}'",
+                owner, name
+            ));
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -314,4 +370,78 @@ mod tests {
         assert!(report.valid);
         assert_eq!(report.warnings.len(), 1);
     }
+
+    #[test]
+    fn test_path_errors() {
+        let dir = std::env::temp_dir().join(format!("liminal_paths_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("bad.yaml"),
+            "id: bad\ntitle: Bad\ndescription: d\nroles: [visa_journey, ghost, visa_journey]\nprerequisites:\n  visa_journey:\n    - role_id: qa_engineer_abroad\n      min_coherence: 1.5\n",
+        )
+        .unwrap();
+
+        let mut validator = ContentValidator::new();
+        validator
+            .load_roles_recursive(Path::new("../content/roles"))
+            .unwrap();
+        validator.load_paths(&dir).unwrap();
+        let report = validator.validate();
+
+        assert_eq!(report.paths_count, 1);
+        for needle in ["'ghost'", "more than once", "outside 0..1"] {
+            assert!(
+                report.errors.iter().any(|e| e.contains(needle)),
+                "{}: {:?}",
+                needle,
+                report.errors
+            );
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_role_transition_checks() {
+        let role: Role = serde_yaml::from_str(
+            "id: r\ntitle: R\ndescription: d\nscenes: [s]\ntransitions:\n  message: \"Hi {name}\"\n  bands:\n    - min_coherence: 1.2\n      message:\n        ru: \"{role} {coherence}\"\n",
+        )
+        .unwrap();
+        let mut report = ValidationReport::new();
+        ContentValidator::new().validate_role(&role, &mut report);
+
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("outside 0..1"));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("'{name}'"));
+    }
+
+    #[test]
+    fn test_inverted_ping_range_is_an_error() {
+        let script: Script = serde_yaml::from_str(
+            "id: s\ntitle: S\nsteps:\n  - type: listen\n    prompt: p\nrehearsal:\n  next_ping_sec_min: 600\n  next_ping_sec_max: 300\n",
+        )
+        .unwrap();
+        let mut report = ValidationReport::new();
+        ContentValidator::new().validate_script(&script, &mut report);
+
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].contains("next_ping_sec_min 600"));
+    }
+
+    #[test]
+    fn test_repo_paths_are_valid() {
+        let mut validator = ContentValidator::new();
+        validator
+            .load_scripts(Path::new("../assets/scripts"))
+            .unwrap();
+        validator
+            .load_roles_recursive(Path::new("../content/roles"))
+            .unwrap();
+        validator.load_paths(Path::new("../content/paths")).unwrap();
+        let report = validator.validate();
+
+        assert_eq!(report.paths_count, 3);
+        assert!(report.valid, "{:?}", report.errors);
+    }
 }
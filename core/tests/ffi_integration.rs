@@ -136,6 +136,7 @@ fn test_ffi_statistics() {
 }
 
 #[test]
+#[allow(clippy::len_zero)]
 fn test_ffi_events() {
     init_test_storage!("test_ffi_events").expect("Failed to init storage");
 
@@ -173,6 +174,26 @@ fn test_ffi_export_data() {
     assert!(export["exported_at"].is_string());
 }
 
+#[test]
+fn test_ffi_import_data() {
+    init_test_storage!("test_ffi_import_data").expect("Failed to init storage");
+
+    add_event("warmup".to_string(), r#"{"completed": true}"#.to_string()).ok();
+    let backup = export_data().unwrap();
+
+    let summary = import_data(backup.clone(), "replace".to_string()).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&summary).unwrap();
+    assert!(summary["rows_imported"].as_u64().unwrap() >= 1);
+    assert_eq!(summary["rows_skipped"], 0);
+
+    // Importing the same backup again skips every row
+    let summary = import_data(backup.clone(), "merge".to_string()).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&summary).unwrap();
+    assert_eq!(summary["rows_imported"], 0);
+
+    assert!(import_data(backup, "overwrite".to_string()).is_err());
+}
+
 #[test]
 fn test_ffi_consecutive_days_update() {
     init_test_storage!("test_ffi_consecutive_days_update").expect("Failed to init storage");
@@ -180,14 +201,14 @@ fn test_ffi_consecutive_days_update() {
     let role_id = "test_role".to_string();
     start_role_progress(role_id.clone(), 3).ok();
 
-    // Update consecutive days
-    let result = update_consecutive_days(role_id.clone(), 7);
+    // Consecutive days come from the streak engine
+    let result = refresh_consecutive_days(role_id.clone());
     assert!(result.is_ok());
 
     let updated_json = result.unwrap();
     let updated: serde_json::Value = serde_json::from_str(&updated_json).unwrap();
 
-    assert_eq!(updated["consecutive_days"], 7);
+    assert_eq!(updated["consecutive_days"], get_streak().unwrap());
 
     // Verify coherence was recalculated
     assert!(updated["coherence"].as_f64().is_some());
@@ -301,3 +322,694 @@ fn test_ffi_recent_traces_pagination() {
 
     assert_eq!(all_traces.as_array().unwrap().len(), 10);
 }
+
+#[test]
+fn test_ffi_runner_contrast_answer() {
+    let count = load_scripts_from_dir("../assets/scripts".to_string()).expect("Failed to load");
+    assert!(count > 0);
+
+    let mut state_json = start_runner("cafe-to-go-01".to_string()).unwrap();
+    state_json = runner_next(state_json).unwrap();
+    state_json = runner_next(state_json).unwrap();
+
+    // Step 2 is a contrast step: advancing is blocked until answered correctly
+    assert!(!runner_can_advance(state_json.clone()).unwrap());
+    assert!(runner_next(state_json.clone())
+        .unwrap_err()
+        .contains("answered correctly"));
+
+    let result = runner_submit_answer(state_json, 2, 1).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(value["feedback"]["correct"], false);
+    assert_eq!(value["feedback"]["attempts"], 1);
+
+    let result = runner_submit_answer(value["state"].to_string(), 2, 0).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(value["feedback"]["correct"], true);
+    assert_eq!(value["feedback"]["can_advance"], true);
+
+    let state_json = runner_next(value["state"].to_string()).unwrap();
+    let state: serde_json::Value = serde_json::from_str(&state_json).unwrap();
+    assert_eq!(state["current_index"], 3);
+}
+
+#[test]
+fn test_ffi_suggest_scripts_for_context() {
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+
+    let context =
+        r#"{"local_time": "08:30:00", "place": "work", "available_minutes": 3, "weekday": "Mon"}"#;
+    let matches: serde_json::Value =
+        serde_json::from_str(&suggest_scripts_for_context(context.to_string()).unwrap()).unwrap();
+    let matches = matches.as_array().unwrap();
+    let ids: Vec<&str> = matches
+        .iter()
+        .map(|m| m["script_id"].as_str().unwrap())
+        .collect();
+
+    // Needs 4 minutes, only 3 are available
+    assert!(!ids.contains(&"morning-warmup-01"));
+    let standup = matches
+        .iter()
+        .find(|m| m["script_id"] == "standup-update-01")
+        .unwrap();
+    assert!(standup["score"].as_f64().unwrap() > 0.8);
+    assert!(standup["reasons"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|r| r.as_str().unwrap().contains("work")));
+
+    let unlimited = format!(
+        r#"{{"local_time": "08:30:00", "available_minutes": {}, "weekday": "Mon"}}"#,
+        u32::MAX
+    );
+    let matches = suggest_scripts_for_context(unlimited).unwrap();
+    assert!(matches.contains("morning-warmup-01"));
+
+    assert!(suggest_scripts_for_context("{}".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_runner_session_resume() {
+    init_test_storage!("test_ffi_runner_session_resume").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).expect("Failed to load");
+
+    let state_json = start_runner("morning-warmup-01".to_string()).unwrap();
+    let state_json = runner_next(state_json).unwrap();
+    let result = runner_reveal_hint(state_json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert!(value["hint"].as_str().unwrap().contains("silent"));
+
+    // Simulate an app restart: the Flutter side lost its state JSON
+    let resumed = resume_runner(Some("morning-warmup-01".to_string()))
+        .unwrap()
+        .expect("Session should be resumable");
+    let resumed: serde_json::Value = serde_json::from_str(&resumed).unwrap();
+
+    assert_eq!(resumed["current_index"], 1);
+    assert_eq!(resumed["session_id"], value["state"]["session_id"]);
+    assert_eq!(resumed["hints_revealed"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_ffi_streak_summary() {
+    init_test_storage!("test_ffi_streak_summary").expect("Failed to init storage");
+
+    set_user_timezone(180).expect("Failed to set timezone");
+    assert_eq!(add_streak_freeze_tokens(2).unwrap(), 2);
+
+    let summary_json = get_streak_summary(7).unwrap();
+    let summary: serde_json::Value = serde_json::from_str(&summary_json).unwrap();
+
+    assert_eq!(summary["current"], 0);
+    assert_eq!(summary["freeze_tokens"], 2);
+    assert_eq!(summary["histogram"].as_array().unwrap().len(), 7);
+
+    // No activity yet, so there is no streak to rescue
+    assert_eq!(apply_streak_freezes().unwrap(), "[]");
+    assert_eq!(add_streak_freeze_tokens(0).unwrap(), 2);
+}
+
+#[test]
+fn test_ffi_scheduler_selection() {
+    init_test_storage!("test_ffi_scheduler_selection").expect("Failed to init storage");
+
+    assert!(set_scheduler("fsrs".to_string()).is_ok());
+    assert!(set_scheduler("wave".to_string()).is_ok());
+    assert!(set_scheduler("sm2".to_string()).is_err());
+
+    assert!(review_phrase("never seen".to_string(), "good".to_string()).is_err());
+    assert!(review_phrase("never seen".to_string(), "perfect".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_completion_harvests_phrases() {
+    init_test_storage!("test_ffi_completion_harvests_phrases").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+
+    let mut state = start_runner("elevator-smalltalk-01".to_string()).unwrap();
+    state = runner_set_require_correct(state, false).unwrap();
+    for _ in 0..10 {
+        state = runner_next(state).unwrap();
+    }
+
+    let links: serde_json::Value = serde_json::from_str(
+        &get_memory_links_json(Some("elevator-smalltalk-01".to_string())).unwrap(),
+    )
+    .unwrap();
+    let links = links.as_array().unwrap();
+    assert!(!links.is_empty());
+    assert!(links
+        .iter()
+        .all(|l| l["script_id"] == "elevator-smalltalk-01"));
+
+    // Already harvested on completion
+    assert_eq!(
+        harvest_script_phrases("elevator-smalltalk-01".to_string()).unwrap(),
+        0
+    );
+    assert!(review_phrase(
+        links[0]["phrase"].as_str().unwrap().to_string(),
+        "good".to_string()
+    )
+    .is_ok());
+}
+
+#[test]
+fn test_ffi_review_queue() {
+    init_test_storage!("test_ffi_review_queue").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+
+    assert_eq!(get_due_review_count(None).unwrap(), 0);
+    assert!(get_next_review_item(None).unwrap().is_none());
+
+    let added = harvest_script_phrases("cafe-to-go-01".to_string()).unwrap();
+    assert!(added > 0);
+    assert_eq!(get_due_review_count(None).unwrap(), added);
+
+    let next: serde_json::Value =
+        serde_json::from_str(&get_next_review_item(None).unwrap().unwrap()).unwrap();
+    assert_eq!(next["script_id"], "cafe-to-go-01");
+    assert_eq!(next["is_new"], true);
+
+    // Reviewing uses up the daily cap
+    review_phrase(
+        next["phrase"].as_str().unwrap().to_string(),
+        "good".to_string(),
+    )
+    .unwrap();
+    let capped = Some(r#"{"daily_cap": 1}"#.to_string());
+    assert_eq!(get_due_review_count(capped).unwrap(), 0);
+}
+
+#[test]
+fn test_ffi_plan_notifications() {
+    init_test_storage!("test_ffi_plan_notifications").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+    harvest_script_phrases("cafe-to-go-01".to_string()).unwrap();
+
+    let config = r#"{"quiet_start": "00:00:00", "quiet_end": "00:00:00", "max_per_day": 100, "min_spacing_minutes": 1}"#;
+    let plan: serde_json::Value =
+        serde_json::from_str(&plan_notifications_json(Some(config.to_string())).unwrap()).unwrap();
+
+    let slots = plan["slots"].as_array().unwrap();
+    assert!(!slots.is_empty());
+    assert!(slots[0]["deep_link"]
+        .as_str()
+        .unwrap()
+        .starts_with("liminal://ping?phrase="));
+    assert_eq!(slots[0]["script_id"], "cafe-to-go-01");
+
+    let endless = format!(r#"{{"horizon_hours": {}}}"#, u32::MAX);
+    assert!(plan_notifications_json(Some(endless)).is_err());
+}
+
+#[test]
+fn test_ffi_leech_remediation() {
+    init_test_storage!("test_ffi_leech_remediation").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+    harvest_script_phrases("cafe-to-go-01".to_string()).unwrap();
+    set_leech_config(r#"{"consecutive_fails": 2}"#.to_string()).unwrap();
+
+    let phrase = "Can I get a medium cappuccino to go".to_string();
+    review_phrase(phrase.clone(), "again".to_string()).unwrap();
+    review_phrase(phrase.clone(), "again".to_string()).unwrap();
+
+    let leeches: serde_json::Value = serde_json::from_str(&get_leeches_json().unwrap()).unwrap();
+    let leeches = leeches.as_array().unwrap();
+    assert_eq!(leeches.len(), 1);
+    assert_eq!(leeches[0]["phrase"], phrase.as_str());
+    assert_eq!(leeches[0]["remediation"]["kind"], "contrast_step");
+    assert_eq!(leeches[0]["remediation"]["step_index"], 2);
+
+    resolve_leech(phrase).unwrap();
+    assert_eq!(get_leeches_json().unwrap(), "[]");
+}
+
+#[test]
+fn test_ffi_fit_retention_parameters() {
+    init_test_storage!("test_ffi_fit_retention_parameters").expect("Failed to init storage");
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+    harvest_script_phrases("cafe-to-go-01".to_string()).unwrap();
+
+    // Too few reviews to fit anything yet
+    let phrase = "Can I get a medium cappuccino to go".to_string();
+    review_phrase(phrase, "good".to_string()).unwrap();
+    assert_eq!(fit_retention_parameters(false).unwrap(), None);
+}
+
+#[test]
+fn test_ffi_fitted_time_constant_changes_schedule() {
+    init_test_storage!("test_ffi_fitted_time_constant_changes_schedule")
+        .expect("Failed to init storage");
+
+    // Remembered after a day every time: decay is far slower than the default
+    let now = chrono::Utc::now();
+    let mut backup: serde_json::Value = serde_json::from_str(&export_data().unwrap()).unwrap();
+    backup["memory_links"] = serde_json::json!([{
+        "phrase": "Could you say that again",
+        "last_seen": (now - chrono::Duration::hours(2)).to_rfc3339(),
+        "wave": 1.0,
+        "decay_alpha": 0.8,
+        "success_count": 1,
+    }]);
+    backup["review_log"] = (0..30)
+        .map(|i| {
+            serde_json::json!({
+                "phrase": "Could you say that again",
+                "reviewed_at": (now - chrono::Duration::days(31 - i)).to_rfc3339(),
+                "grade": "good",
+                "recalled": 1,
+                "elapsed_secs": 86_400,
+                "wave_before": 1.0,
+                "decay_alpha": 0.8,
+                "scheduler": "wave",
+            })
+        })
+        .collect();
+    import_data(backup.to_string(), "merge".to_string()).unwrap();
+
+    let priority = || -> f64 {
+        let queue: serde_json::Value =
+            serde_json::from_str(&get_review_queue_json(None).unwrap()).unwrap();
+        queue["items"][0]["priority"].as_f64().unwrap()
+    };
+    let before = priority();
+
+    let report: serde_json::Value =
+        serde_json::from_str(&fit_retention_parameters(true).unwrap().unwrap()).unwrap();
+    assert!(report["time_constant_secs"].as_f64().unwrap() > 600.0);
+
+    // The phrase has decayed less under the fitted constant
+    assert!(priority() < before);
+}
+
+#[test]
+fn test_ffi_role_scripts_in_manifest_order() {
+    load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+    assert_eq!(
+        load_roles_from_dir("../content/roles".to_string()).unwrap(),
+        7
+    );
+
+    let scripts: serde_json::Value =
+        serde_json::from_str(&get_role_scripts_json("qa_engineer_abroad".to_string()).unwrap())
+            .unwrap();
+    let ids: Vec<&str> = scripts
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        ids,
+        vec![
+            "qa_interview_01",
+            "qa_bugreport_02",
+            "qa_standup_03",
+            "qa_review_04",
+            "qa_relax_05"
+        ]
+    );
+
+    // Role scenes are startable like any other script
+    assert!(start_runner("qa_interview_01".to_string()).is_ok());
+    assert!(get_script_ids().contains(&"cafe-to-go-01".to_string()));
+    assert!(get_role_scripts_json("nobody".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_reloading_content_keeps_role_scenes() {
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+    let standalone = load_scripts_from_dir("../assets/scripts".to_string()).unwrap();
+    assert_eq!(standalone, 8);
+    assert!(get_script_json("qa_interview_01".to_string()).is_ok());
+    assert!(get_script_json("cafe-to-go-01".to_string()).is_ok());
+
+    // Loading the same roles again swaps the scenes instead of piling them up
+    let total = get_script_ids().len();
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+    assert_eq!(get_script_ids().len(), total);
+
+    // A failed load keeps what was loaded before
+    assert!(load_roles_from_dir("../content/missing".to_string()).is_err());
+    assert!(get_script_json("qa_interview_01".to_string()).is_ok());
+}
+
+#[test]
+fn test_ffi_check_role_access() {
+    init_test_storage!("test_ffi_check_role_access").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    let role: serde_json::Value =
+        serde_json::from_str(&get_role_json("family_abroad".to_string()).unwrap()).unwrap();
+    assert_eq!(role["access_level"], "premium");
+    assert!(!role["themes"].as_array().unwrap().is_empty());
+
+    let premium: serde_json::Value = serde_json::from_str(
+        &check_role_access("user-1".to_string(), "family_abroad".to_string()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(premium["has_access"], false);
+    assert_eq!(premium["reason"], "requires_premium");
+
+    let free: serde_json::Value = serde_json::from_str(
+        &check_role_access("user-1".to_string(), "qa_engineer_abroad".to_string()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(free["has_access"], true);
+
+    assert!(check_role_access("user-1".to_string(), "nobody".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_role_coherence_breakdown() {
+    init_test_storage!("test_ffi_role_coherence_breakdown").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    // qa_engineer_abroad scenes weigh 0.15 and 0.20 of 1.0
+    let coherence: serde_json::Value = serde_json::from_str(
+        &calculate_role_coherence("qa_engineer_abroad".to_string(), 2, 1, 0).unwrap(),
+    )
+    .unwrap();
+    assert!((coherence["score"].as_f64().unwrap() - 0.45).abs() < 1e-4);
+    assert!((coherence["breakdown"]["completion"].as_f64().unwrap() - 0.35).abs() < 1e-4);
+    assert_eq!(
+        coherence["breakdown"]["explanation"]
+            .as_array()
+            .unwrap()
+            .len(),
+        2
+    );
+
+    set_coherence_config(r#"{"wild_bonus_per_use": 0.0}"#.to_string()).unwrap();
+    let coherence: serde_json::Value = serde_json::from_str(
+        &calculate_role_coherence("qa_engineer_abroad".to_string(), 2, 1, 0).unwrap(),
+    )
+    .unwrap();
+    assert!((coherence["score"].as_f64().unwrap() - 0.35).abs() < 1e-4);
+
+    let progress: serde_json::Value =
+        serde_json::from_str(&start_role_progress("qa_engineer_abroad".to_string(), 5).unwrap())
+            .unwrap();
+    assert_eq!(progress["coherence"], 0.0);
+}
+
+#[test]
+fn test_ffi_path_progress_and_unlock() {
+    init_test_storage!("test_ffi_path_progress_and_unlock").expect("Failed to init storage");
+    load_paths_from_dir("../content/paths".to_string()).unwrap();
+    assert!(get_path_ids().contains(&"work_abroad".to_string()));
+
+    let progress = |path_id: &str| -> serde_json::Value {
+        serde_json::from_str(&get_path_progress_json(path_id.to_string()).unwrap()).unwrap()
+    };
+    let before = progress("work_abroad");
+    assert_eq!(before["current_role"], "visa_journey");
+    assert_eq!(before["roles"][1]["status"], "locked");
+
+    // Finishing every visa scene makes the role transition ready
+    start_role_progress("visa_journey".to_string(), 3).unwrap();
+    for scene in [
+        "visa_officer_01",
+        "border_control_02",
+        "apartment_checkin_03",
+    ] {
+        complete_scene_with_emotion(
+            "visa_journey".to_string(),
+            scene.to_string(),
+            "Calm".to_string(),
+            0.9,
+        )
+        .unwrap();
+    }
+
+    let after = progress("work_abroad");
+    assert_eq!(after["roles"][0]["status"], "completed");
+    assert_eq!(after["roles"][1]["status"], "unlocked");
+    assert_eq!(after["current_role"], "qa_engineer_abroad");
+
+    let unlocked: Vec<String> = serde_json::from_str(&get_unlocked_roles_json().unwrap()).unwrap();
+    assert!(unlocked.contains(&"qa_engineer_abroad".to_string()));
+    // family_abroad opens on the life_abroad path too
+    assert!(unlocked.contains(&"family_abroad".to_string()));
+
+    assert!(get_path_progress_json("nowhere".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_compare_scene_emotion() {
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    // qa_interview_01 is meant to feel calm_confident
+    let calm: serde_json::Value = serde_json::from_str(
+        &compare_scene_emotion("qa_interview_01".to_string(), None, "Confident".to_string())
+            .unwrap()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(calm["intended_tag"], "calm_confident");
+    assert_eq!(calm["matched"], true);
+
+    let nervous: serde_json::Value = serde_json::from_str(
+        &compare_scene_emotion("qa_interview_01".to_string(), None, "nervous".to_string())
+            .unwrap()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(nervous["expressed"], "Nervous");
+    assert_eq!(nervous["matched"], false);
+
+    assert!(compare_scene_emotion("missing".to_string(), None, "Calm".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_emotion_trajectory() {
+    init_test_storage!("test_ffi_emotion_trajectory").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    start_role_progress("qa_engineer_abroad".to_string(), 5).unwrap();
+    for (scene, tone) in [("qa_interview_01", "Nervous"), ("qa_bugreport_02", "Calm")] {
+        complete_scene_with_emotion(
+            "qa_engineer_abroad".to_string(),
+            scene.to_string(),
+            tone.to_string(),
+            0.8,
+        )
+        .unwrap();
+    }
+
+    let trajectory: serde_json::Value = serde_json::from_str(
+        &get_emotion_trajectory_json(Some("qa_engineer_abroad".to_string()), None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(trajectory["points"].as_array().unwrap().len(), 2);
+    assert_eq!(trajectory["most_nervous"][0], "qa_interview_01");
+    assert_eq!(
+        trajectory["points"][0]["intended"]["intended_tag"],
+        "calm_confident"
+    );
+    assert_eq!(trajectory["roles"][0]["role_id"], "qa_engineer_abroad");
+
+    assert!(get_emotion_trajectory_json(None, Some("not json".to_string())).is_err());
+}
+
+#[test]
+fn test_ffi_localized_transition() {
+    init_test_storage!("test_ffi_localized_transition").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    let role_id = "qa_engineer_abroad".to_string();
+    start_role_progress(role_id.clone(), 5).unwrap();
+    complete_scene_with_emotion(
+        role_id.clone(),
+        "qa-standup-03".to_string(),
+        "Calm".to_string(),
+        0.9,
+    )
+    .unwrap();
+
+    let english: serde_json::Value =
+        serde_json::from_str(&get_liminal_transition_json(role_id.clone()).unwrap()).unwrap();
+    assert_eq!(
+        english["message"],
+        "Daily standups feel natural now. You're part of the rhythm."
+    );
+    assert_eq!(english["sound"], "chime_gentle.mp3");
+
+    set_native_language("ru".to_string()).unwrap();
+    let russian: serde_json::Value =
+        serde_json::from_str(&get_liminal_transition_json(role_id.clone()).unwrap()).unwrap();
+    assert_eq!(
+        russian["message"],
+        "Стендапы стали привычными. Вы уже в общем ритме команды."
+    );
+
+    // Scenes without a message fall back to the built-in text
+    complete_scene_with_emotion(
+        role_id.clone(),
+        "unscripted".to_string(),
+        "Calm".to_string(),
+        0.9,
+    )
+    .unwrap();
+    let fallback: serde_json::Value =
+        serde_json::from_str(&get_liminal_transition_json(role_id).unwrap()).unwrap();
+    assert!(fallback["message"]
+        .as_str()
+        .unwrap()
+        .contains("QA Engineer Abroad"));
+
+    assert!(set_native_language(" ".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_coherence_history() {
+    init_test_storage!("test_ffi_coherence_history").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    let role_id = "qa_engineer_abroad".to_string();
+    start_role_progress(role_id.clone(), 5).unwrap();
+    for scene in ["qa-interview-01", "qa-bugreport-02"] {
+        complete_scene_with_emotion(role_id.clone(), scene.to_string(), "Calm".to_string(), 0.9)
+            .unwrap();
+    }
+
+    let history: serde_json::Value =
+        serde_json::from_str(&get_coherence_history_json(role_id.clone()).unwrap()).unwrap();
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[2]["scene_index"], 2);
+
+    let transition: serde_json::Value =
+        serde_json::from_str(&get_liminal_transition_json(role_id).unwrap()).unwrap();
+    assert_eq!(transition["prev_coherence"], history[1]["coherence"]);
+    assert_eq!(transition["curr_coherence"], history[2]["coherence"]);
+
+    assert!(get_coherence_history_json("nobody".to_string()).is_err());
+}
+
+#[test]
+fn test_ffi_role_certificate() {
+    init_test_storage!("test_ffi_role_certificate").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+
+    let role_id = "visa_journey".to_string();
+    start_role_progress(role_id.clone(), 3).unwrap();
+    assert!(issue_role_certificate(role_id.clone())
+        .unwrap_err()
+        .contains("not ready"));
+
+    for scene in [
+        "visa-officer-01",
+        "border-control-02",
+        "apartment-checkin-03",
+    ] {
+        complete_scene_with_emotion(
+            role_id.clone(),
+            scene.to_string(),
+            "Confident".to_string(),
+            0.9,
+        )
+        .unwrap();
+    }
+
+    // Only phrases from this role's scenes count towards its certificate
+    let mut backup: serde_json::Value = serde_json::from_str(&export_data().unwrap()).unwrap();
+    backup["memory_links"] = serde_json::json!([
+        {
+            "phrase": "I am here on a work visa",
+            "last_seen": chrono::Utc::now().to_rfc3339(),
+            "wave": 1.0,
+            "decay_alpha": 0.8,
+            "use_in_wild_count": 2,
+            "script_id": "visa_officer_01",
+        },
+        {
+            "phrase": "Could I get it to go",
+            "last_seen": chrono::Utc::now().to_rfc3339(),
+            "wave": 1.0,
+            "decay_alpha": 0.8,
+            "use_in_wild_count": 5,
+            "script_id": "cafe-to-go-01",
+        },
+    ]);
+    import_data(backup.to_string(), "merge".to_string()).unwrap();
+
+    let json = issue_role_certificate(role_id.clone()).unwrap();
+    let certificate: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(certificate["record"]["level"], "completed");
+    assert_eq!(certificate["record"]["use_in_wild_count"], 2);
+    assert_eq!(certificate["record"]["scenes"].as_array().unwrap().len(), 3);
+    assert!(certificate["record"]["scenes"][0]["title"].is_string());
+    assert_eq!(
+        certificate["public_key"],
+        get_certificate_public_key().unwrap()
+    );
+
+    let key = get_certificate_public_key().unwrap();
+    assert!(verify_certificate(json.clone(), Some(key)).is_ok());
+    let html = render_certificate_html(json.clone()).unwrap();
+    assert!(verify_certificate(html, None).is_ok());
+    assert!(render_certificate_svg(json.clone())
+        .unwrap()
+        .starts_with("<svg"));
+
+    let tampered = json.replace("\"visa_journey\"", "\"global_citizen\"");
+    assert!(verify_certificate(tampered, None).is_err());
+
+    let stored: serde_json::Value =
+        serde_json::from_str(&get_certificates_json(Some(role_id)).unwrap()).unwrap();
+    assert_eq!(stored.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_ffi_recommendations() {
+    init_test_storage!("test_ffi_recommendations").expect("Failed to init storage");
+    load_roles_from_dir("../content/roles".to_string()).unwrap();
+    load_paths_from_dir("../content/paths".to_string()).unwrap();
+
+    start_role_progress("visa_journey".to_string(), 3).unwrap();
+    complete_scene_with_emotion(
+        "visa_journey".to_string(),
+        "visa-officer-01".to_string(),
+        "Nervous".to_string(),
+        0.9,
+    )
+    .unwrap();
+
+    let recs: serde_json::Value =
+        serde_json::from_str(&get_recommendations_json("user-1".to_string(), None, None).unwrap())
+            .unwrap();
+    assert_eq!(recs["scenes"][0]["id"], "border_control_02");
+    assert_eq!(recs["scenes"][0]["kind"], "scene");
+    assert!(recs["scenes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|s| s["id"] == "visa_officer_01"
+            && s["reasons"][0]
+                .as_str()
+                .unwrap()
+                .starts_with("you sounded nervous")));
+    assert_eq!(recs["roles"][0]["id"], "visa_journey");
+    assert!(recs["roles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|r| r["id"] != "family_abroad"));
+
+    let limited: serde_json::Value = serde_json::from_str(
+        &get_recommendations_json(
+            "user-1".to_string(),
+            None,
+            Some(r#"{"limit": 1}"#.to_string()),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(limited["scenes"].as_array().unwrap().len(), 1);
+
+    assert!(get_recommendations_json("user-1".to_string(), Some("{}".to_string()), None).is_err());
+}
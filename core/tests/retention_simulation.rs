@@ -0,0 +1,162 @@
+use chrono::{DateTime, Duration, Utc};
+use liminal_english_core::{FsrsScheduler, Grade, MemoryLink, Scheduler, WaveScheduler};
+
+// Simulated learners: each phrase has a hidden "true" memory that the
+// schedulers never see. Recall follows an exponential forgetting curve whose
+// 90%-recall time grows on every success and shrinks on every lapse.
+
+const PHRASES: usize = 60;
+const HORIZON_DAYS: i64 = 30;
+
+/// Small deterministic PRNG so runs are reproducible without extra deps
+struct Lcg(u64);
+
+impl Lcg {
+    fn next_f32(&mut self) -> f32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+struct TrueMemory {
+    /// Days until true recall probability drops to 90%
+    stability: f32,
+}
+
+impl TrueMemory {
+    fn recall_probability(&self, elapsed_days: f32) -> f32 {
+        (0.9f32.ln() * elapsed_days / self.stability).exp()
+    }
+
+    fn update(&mut self, recalled: bool) {
+        if recalled {
+            self.stability *= 3.0;
+        } else {
+            self.stability = (self.stability * 0.4).max(0.2);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    reviews: usize,
+    /// Mean squared error between predicted and actual recall
+    brier: f32,
+    /// Mean predicted minus observed recall at review time
+    calibration_gap: f32,
+    observed_recall: f32,
+    /// Mean true recall probability of every phrase at the horizon
+    retention_at_horizon: f32,
+}
+
+fn start() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2025-03-01T08:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f32 {
+    (to - from).num_seconds() as f32 / 86_400.0
+}
+
+fn simulate(scheduler: &dyn Scheduler, seed: u64) -> Report {
+    let mut rng = Lcg(seed);
+    let horizon = start() + Duration::days(HORIZON_DAYS);
+
+    let mut report = Report::default();
+    let mut predicted_sum = 0.0;
+    let mut recalled_sum = 0.0;
+
+    for i in 0..PHRASES {
+        let mut link = MemoryLink::new(format!("phrase {}", i), 0.8);
+        link.last_seen = start();
+        let mut truth = TrueMemory {
+            stability: 1.5 + rng.next_f32() * 4.0,
+        };
+
+        // First exposure happens while learning the scenario
+        scheduler.review(&mut link, Grade::Good, start());
+
+        loop {
+            let at = scheduler
+                .next_review(&link)
+                .max(link.last_seen + Duration::minutes(1));
+            if at > horizon {
+                break;
+            }
+
+            let predicted = scheduler.retrievability(&link, at);
+            let p = truth.recall_probability(days_between(link.last_seen, at));
+            let recalled = rng.next_f32() < p;
+            let outcome = if recalled { 1.0 } else { 0.0 };
+
+            report.reviews += 1;
+            report.brier += (predicted - outcome) * (predicted - outcome);
+            predicted_sum += predicted;
+            recalled_sum += outcome;
+
+            truth.update(recalled);
+            let grade = if recalled { Grade::Good } else { Grade::Again };
+            scheduler.review(&mut link, grade, at);
+        }
+
+        report.retention_at_horizon +=
+            truth.recall_probability(days_between(link.last_seen, horizon));
+    }
+
+    let n = report.reviews.max(1) as f32;
+    report.brier /= n;
+    report.calibration_gap = (predicted_sum - recalled_sum) / n;
+    report.observed_recall = recalled_sum / n;
+    report.retention_at_horizon /= PHRASES as f32;
+    report
+}
+
+#[test]
+fn test_fsrs_predictions_are_better_calibrated_than_wave() {
+    let wave = simulate(&WaveScheduler::default(), 7);
+    let fsrs = simulate(&FsrsScheduler::default(), 7);
+
+    assert!(
+        fsrs.brier < wave.brier,
+        "wave: {:?}\nfsrs: {:?}",
+        wave,
+        fsrs
+    );
+    assert!(
+        fsrs.calibration_gap.abs() < wave.calibration_gap.abs(),
+        "wave: {:?}\nfsrs: {:?}",
+        wave,
+        fsrs
+    );
+    assert!(fsrs.calibration_gap.abs() < 0.1, "fsrs: {:?}", fsrs);
+}
+
+#[test]
+fn test_fsrs_reviews_near_desired_retention() {
+    let fsrs = simulate(&FsrsScheduler::default(), 11);
+
+    // Reviews are scheduled for 90% predicted recall
+    assert!((fsrs.observed_recall - 0.9).abs() < 0.1);
+}
+
+#[test]
+fn test_fsrs_needs_far_fewer_reviews_for_similar_retention() {
+    let wave = simulate(&WaveScheduler::default(), 23);
+    let fsrs = simulate(&FsrsScheduler::default(), 23);
+
+    assert!(fsrs.reviews * 20 < wave.reviews);
+    assert!(fsrs.retention_at_horizon > 0.8);
+    assert!(fsrs.retention_at_horizon > wave.retention_at_horizon - 0.15);
+}
+
+#[test]
+fn test_simulation_is_deterministic() {
+    let a = simulate(&FsrsScheduler::default(), 42);
+    let b = simulate(&FsrsScheduler::default(), 42);
+    assert_eq!(a.reviews, b.reviews);
+    assert_eq!(a.brier, b.brier);
+}
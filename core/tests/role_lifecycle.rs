@@ -1,5 +1,5 @@
 use chrono::Utc;
-use liminal_english_core::{EmotionTag, Reflection, ResonanceTrace, RoleProgress, Store};
+use liminal_english_core::{EmotionTag, Reflection, ResonanceTrace, RoleProgress, Store, Tone};
 
 // Helper macro to create isolated test databases with unique names
 macro_rules! open_test_store {
@@ -97,9 +97,9 @@ fn test_complete_role_lifecycle() {
 
     // Verify emotions were saved correctly
     assert_eq!(loaded.emotion_tags[0].scene_id, "qa-interview-01");
-    assert_eq!(loaded.emotion_tags[0].tone, "Nervous");
+    assert_eq!(loaded.emotion_tags[0].tone, Tone::Nervous);
     assert_eq!(loaded.emotion_tags[4].scene_id, "qa-relax-05");
-    assert_eq!(loaded.emotion_tags[4].tone, "Relaxed");
+    assert_eq!(loaded.emotion_tags[4].tone, Tone::Relaxed);
 }
 
 #[test]
@@ -150,8 +150,8 @@ fn test_multiple_roles_isolation() {
 
     assert_eq!(loaded_qa.emotion_tags.len(), 2);
     assert_eq!(loaded_visa.emotion_tags.len(), 1);
-    assert_eq!(loaded_qa.emotion_tags[0].tone, "Confident");
-    assert_eq!(loaded_visa.emotion_tags[0].tone, "Nervous");
+    assert_eq!(loaded_qa.emotion_tags[0].tone, Tone::Confident);
+    assert_eq!(loaded_visa.emotion_tags[0].tone, Tone::Nervous);
 }
 
 #[test]